@@ -3,7 +3,7 @@ use std::io::{Read, Write};
 use std::rc::Rc;
 
 use plinth_plugin::error::Error;
-use plinth_plugin::{export_clap, export_vst3, Event, Host, HostInfo, Parameters, Plugin, ProcessorConfig};
+use plinth_plugin::{export_clap, export_vst3, Category, Event, Host, HostInfo, Parameters, Plugin, ProcessorConfig};
 use plinth_plugin::clap::ClapPlugin;
 use plinth_plugin::vst3::Vst3Plugin;
 
@@ -20,6 +20,11 @@ impl Plugin for GainPlugin {
     const VENDOR: &'static str = "Viiri Audio";
     const VERSION: &'static str = "0.1";
 
+    const CATEGORIES: &'static [Category] = &[
+        Category::Effect,
+        Category::Stereo,
+    ];
+
     type Processor = GainPluginProcessor;
     type Editor = GainPluginEditor;
     type Parameters = GainParameters;
@@ -67,18 +72,10 @@ impl Plugin for GainPlugin {
 
 impl ClapPlugin for GainPlugin {
     const CLAP_ID: &'static str = "viiri-audio.gain-example";
-    const FEATURES: &'static [plinth_plugin::clap::Feature] = &[
-        plinth_plugin::clap::Feature::AudioEffect,
-        plinth_plugin::clap::Feature::Stereo,
-    ];
 }
 
 impl Vst3Plugin for GainPlugin {
     const CLASS_ID: u128 = 0xE84410DB1788DC81;
-    const SUBCATEGORIES: &'static [plinth_plugin::vst3::Subcategory] = &[
-        plinth_plugin::vst3::Subcategory::Fx,
-        plinth_plugin::vst3::Subcategory::Stereo,
-    ];
 }
 
 export_clap!(GainPlugin);
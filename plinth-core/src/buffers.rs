@@ -1 +1,7 @@
-pub mod buffer;
\ No newline at end of file
+pub mod buffer;
+pub mod delay_line;
+pub mod fixed_buffer;
+pub mod ring_buffer;
+pub mod scratch_pool;
+#[cfg(feature = "wav")]
+pub mod wav;
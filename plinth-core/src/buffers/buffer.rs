@@ -1,21 +1,33 @@
-use crate::signals::{signal::Signal, signal_base::{SignalBase, SignalMutBase}};
+use crate::signals::{sample::Sample, signal::Signal, signal_base::{SignalBase, SignalMutBase}};
 
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct Buffer {
-    samples: Vec<Vec<f32>>,
+pub struct Buffer<T: Sample = f32> {
+    samples: Vec<Vec<T>>,
 }
 
 impl Buffer {
     pub fn new(channels: usize, length: usize) -> Self {
+        Self::silent(channels, length)
+    }
+
+    pub fn with_capacity(channels: usize, capacity: usize) -> Self {
+        Self::empty(channels, capacity)
+    }
+}
+
+impl<T: Sample> Buffer<T> {
+    /// `new()` for any sample type
+    pub fn silent(channels: usize, length: usize) -> Self {
         assert!(channels > 0);
 
         Self {
-            samples: vec![vec![0.0; length]; channels],
+            samples: vec![vec![T::EQUILIBRIUM; length]; channels],
         }
     }
 
-    pub fn with_capacity(channels: usize, capacity: usize) -> Self {
+    /// `with_capacity()` for any sample type
+    pub fn empty(channels: usize, capacity: usize) -> Self {
         assert!(channels > 0);
 
         Self {
@@ -23,7 +35,7 @@ impl Buffer {
         }
     }
 
-    pub fn from_signal(signal: &impl Signal) -> Self {
+    pub fn from_signal(signal: &impl Signal<T>) -> Self {
         let samples: Vec<_> = signal.iter_channels()
             .map(|channel| channel.to_vec())
             .collect();
@@ -39,7 +51,7 @@ impl Buffer {
 
     pub fn resize(&mut self, length: usize) {
         for channel in self.samples.iter_mut() {
-            channel.resize(length, 0.0);
+            channel.resize(length, T::EQUILIBRIUM);
         }
     }
 
@@ -50,15 +62,15 @@ impl Buffer {
     }
 }
 
-impl From<Vec<Vec<f32>>> for Buffer {
-    fn from(value: Vec<Vec<f32>>) -> Self {
+impl<T: Sample> From<Vec<Vec<T>>> for Buffer<T> {
+    fn from(value: Vec<Vec<T>>) -> Self {
         Buffer {
             samples: value,
         }
     }
 }
 
-impl SignalBase for Buffer {
+impl<T: Sample> SignalBase<T> for Buffer<T> {
     fn channels(&self) -> usize {
         self.samples.len()
     }
@@ -67,19 +79,19 @@ impl SignalBase for Buffer {
         self.samples[0].len()
     }
 
-    fn channel_ptr(&self, channel: usize) -> *const [f32] {
+    fn channel_ptr(&self, channel: usize) -> *const [T] {
         self.samples[channel].as_slice()
     }
 }
 
-impl SignalMutBase for Buffer {
-    fn channel_ptr_mut(&mut self, channel: usize) -> *mut [f32] {
+impl<T: Sample> SignalMutBase<T> for Buffer<T> {
+    fn channel_ptr_mut(&mut self, channel: usize) -> *mut [T] {
         self.samples[channel].as_mut_slice()
     }
 }
 
-impl PartialEq<Buffer> for Buffer {
-    fn eq(&self, other: &Buffer) -> bool {
+impl<T: Sample> PartialEq<Buffer<T>> for Buffer<T> {
+    fn eq(&self, other: &Buffer<T>) -> bool {
         self.samples == other.samples
     }
 }
@@ -97,6 +109,18 @@ mod tests {
         assert_eq!(buffer.channel(0), &[0.0, 0.0]);
     }
 
+    #[test]
+    fn other_sample_types() {
+        let mut buffer = Buffer::<f64>::silent(2, 3);
+        buffer.fill(0.5);
+        buffer.scale(0.5);
+        assert_eq!(buffer.channel(1), [0.25; 3]);
+
+        let mut converted = Buffer::<i16>::silent(2, 3);
+        converted.convert_from_signal(&buffer);
+        assert_eq!(converted.channel(0), [8192; 3]);
+    }
+
     #[test]
     fn read_write_1_channel_2_samples() {
         let mut buffer = Buffer::new(1, 2);
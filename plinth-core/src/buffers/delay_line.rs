@@ -0,0 +1,182 @@
+use crate::signals::{signal::{Signal, SignalMut}, signal_base::SignalBase};
+
+use super::ring_buffer::RingBuffer;
+
+// Cubic interpolation reads one sample past the integer part of the delay
+const EXTRA_SAMPLES: usize = 3;
+
+// Below this the allpass coefficient approaches -1 and the filter gets too resonant
+const MIN_ALLPASS_FRACTION: f32 = 0.1;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Interpolation {
+    /// Rounds delays down to whole samples
+    None,
+    #[default]
+    Linear,
+    /// Four point Hermite interpolation
+    Cubic,
+    /// First order allpass, which keeps the frequency response flat but is only suited to slowly changing delays
+    Allpass,
+}
+
+/// Multichannel delay line with fractional delays, allocated for `max_delay` samples up front
+pub struct DelayLine {
+    ring_buffer: RingBuffer,
+    max_delay: usize,
+    interpolation: Interpolation,
+
+    // Previous output of each channel for allpass interpolation
+    allpass_outputs: Vec<f32>,
+}
+
+impl DelayLine {
+    pub fn new(channels: usize, max_delay: usize) -> Self {
+        Self {
+            ring_buffer: RingBuffer::new(channels, max_delay + EXTRA_SAMPLES),
+            max_delay,
+            interpolation: Interpolation::default(),
+
+            allpass_outputs: vec![0.0; channels],
+        }
+    }
+
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    pub fn max_delay(&self) -> usize {
+        self.max_delay
+    }
+
+    pub fn interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    pub fn ring_buffer(&self) -> &RingBuffer {
+        &self.ring_buffer
+    }
+
+    pub fn reset(&mut self) {
+        self.ring_buffer.reset();
+        self.allpass_outputs.fill(0.0);
+    }
+
+    /// Writes `signal` to the delay line and replaces it with the output delayed by `delay` samples
+    pub fn process(&mut self, signal: &mut impl SignalMut, delay: f32) {
+        self.process_modulated(signal, |_| delay);
+    }
+
+    /// Like `process()`, but `delay` is called with each sample index in the block
+    pub fn process_modulated(&mut self, signal: &mut impl SignalMut, delay: impl Fn(usize) -> f32) {
+        assert!(signal.channels() <= self.ring_buffer.channels());
+
+        let length = self.ring_buffer.len();
+        let start_position = self.ring_buffer.write_position();
+
+        for channel in 0..signal.channels() {
+            for (index, sample) in signal.channel_mut(channel).iter_mut().enumerate() {
+                let position = (start_position + index) % length;
+                self.ring_buffer.channel_mut(channel)[position] = *sample;
+
+                *sample = self.read(channel, (position + 1) % length, delay(index));
+            }
+        }
+
+        self.ring_buffer.advance(signal.len());
+    }
+
+    // `write_position` is the storage index following the newest sample
+    fn read(&mut self, channel: usize, write_position: usize, delay: f32) -> f32 {
+        let delay = delay.clamp(0.0, self.max_delay as f32);
+        let mut integer_delay = delay as usize;
+        let mut fraction = delay - integer_delay as f32;
+
+        let samples = self.ring_buffer.channel(channel);
+        let length = samples.len();
+        let sample = |delay: usize| samples[(write_position + 2 * length - 1 - delay) % length];
+
+        match self.interpolation {
+            Interpolation::None => sample(integer_delay),
+            Interpolation::Linear => {
+                let current = sample(integer_delay);
+                current + fraction * (sample(integer_delay + 1) - current)
+            }
+            Interpolation::Cubic => {
+                // The newest sample stands in for the one that hasn't been written yet
+                let x0 = sample(integer_delay.saturating_sub(1));
+                let x1 = sample(integer_delay);
+                let x2 = sample(integer_delay + 1);
+                let x3 = sample(integer_delay + 2);
+
+                let c1 = 0.5 * (x2 - x0);
+                let c2 = x0 - 2.5 * x1 + 2.0 * x2 - 0.5 * x3;
+                let c3 = 0.5 * (x3 - x0) + 1.5 * (x1 - x2);
+
+                ((c3 * fraction + c2) * fraction + c1) * fraction + x1
+            }
+            Interpolation::Allpass => {
+                if fraction < MIN_ALLPASS_FRACTION && integer_delay > 0 {
+                    integer_delay -= 1;
+                    fraction += 1.0;
+                }
+
+                let coefficient = (1.0 - fraction) / (1.0 + fraction);
+                let previous_output = self.allpass_outputs[channel];
+                let output = coefficient * (sample(integer_delay) - previous_output) + sample(integer_delay + 1);
+
+                self.allpass_outputs[channel] = output;
+                output
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{buffers::buffer::Buffer, signals::signal::Signal};
+
+    use super::{DelayLine, Interpolation};
+
+    fn ramp(length: usize) -> Buffer {
+        Buffer::from(vec![(0..length).map(|index| index as f32).collect()])
+    }
+
+    #[test]
+    fn integer_delay() {
+        for interpolation in [Interpolation::None, Interpolation::Linear, Interpolation::Cubic, Interpolation::Allpass] {
+            let mut delay_line = DelayLine::new(1, 8).with_interpolation(interpolation);
+
+            let mut buffer = ramp(10);
+            delay_line.process(&mut buffer, 3.0);
+
+            assert_eq!(buffer.channel(0), [0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0], "{interpolation:?}");
+        }
+    }
+
+    #[test]
+    fn fractional_delay() {
+        // Every mode is exact for a ramp once the allpass has settled
+        for interpolation in [Interpolation::Linear, Interpolation::Cubic, Interpolation::Allpass] {
+            let mut delay_line = DelayLine::new(1, 8).with_interpolation(interpolation);
+
+            let mut buffer = ramp(64);
+            delay_line.process(&mut buffer, 2.5);
+
+            assert!((buffer.channel(0)[63] - 60.5).abs() < 1e-3, "{interpolation:?}: {}", buffer.channel(0)[63]);
+        }
+    }
+
+    #[test]
+    fn blocks_continue() {
+        let mut delay_line = DelayLine::new(1, 8);
+
+        let mut buffer = ramp(3);
+        delay_line.process(&mut buffer, 4.0);
+        let mut buffer = Buffer::from(vec![vec![3.0, 4.0, 5.0, 6.0]]);
+        delay_line.process(&mut buffer, 4.0);
+
+        assert_eq!(buffer.channel(0), [0.0, 0.0, 1.0, 2.0]);
+    }
+}
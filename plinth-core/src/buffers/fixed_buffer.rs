@@ -0,0 +1,100 @@
+use crate::signals::{sample::Sample, signal_base::{SignalBase, SignalMutBase}};
+
+/// Multichannel buffer that allocates its capacity up front and never reallocates
+///
+/// Size it once from the maximum block size, then set the length of each block with `set_len()`.
+#[derive(Clone, Debug)]
+pub struct FixedBuffer<T: Sample = f32> {
+    samples: Vec<Box<[T]>>,
+    length: usize,
+}
+
+impl FixedBuffer {
+    /// Starts out with a length equal to `capacity`
+    pub fn new(channels: usize, capacity: usize) -> Self {
+        Self::silent(channels, capacity)
+    }
+}
+
+impl<T: Sample> FixedBuffer<T> {
+    /// `new()` for any sample type
+    pub fn silent(channels: usize, capacity: usize) -> Self {
+        assert!(channels > 0);
+
+        Self {
+            samples: (0..channels).map(|_| vec![T::EQUILIBRIUM; capacity].into_boxed_slice()).collect(),
+            length: capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.samples[0].len()
+    }
+
+    /// Samples that become part of the signal keep the values they had the last time they were used
+    pub fn set_len(&mut self, length: usize) {
+        assert!(length <= self.capacity(), "Length {length} exceeds the fixed buffer capacity of {}", self.capacity());
+        self.length = length;
+    }
+
+    /// Sets the length and fills the whole signal with silence
+    pub fn set_len_and_clear(&mut self, length: usize) {
+        self.set_len(length);
+
+        for channel in self.samples.iter_mut() {
+            channel[..length].fill(T::EQUILIBRIUM);
+        }
+    }
+}
+
+impl<T: Sample> SignalBase<T> for FixedBuffer<T> {
+    fn len(&self) -> usize {
+        self.length
+    }
+
+    fn channels(&self) -> usize {
+        self.samples.len()
+    }
+
+    fn channel_ptr(&self, channel: usize) -> *const [T] {
+        &self.samples[channel][..self.length]
+    }
+}
+
+impl<T: Sample> SignalMutBase<T> for FixedBuffer<T> {
+    fn channel_ptr_mut(&mut self, channel: usize) -> *mut [T] {
+        &mut self.samples[channel][..self.length]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::signals::{signal::{Signal, SignalMut}, signal_base::SignalBase};
+
+    use super::FixedBuffer;
+
+    #[test]
+    fn set_len_keeps_storage() {
+        let mut buffer = FixedBuffer::new(2, 8);
+        let pointer = buffer.channel(0).as_ptr();
+
+        buffer.set_len(3);
+        buffer.fill(1.0);
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.channel(1), [1.0; 3]);
+
+        buffer.set_len(8);
+        assert_eq!(buffer.channel(0)[2..4], [1.0, 0.0]);
+        assert_eq!(buffer.channel(0).as_ptr(), pointer);
+
+        buffer.set_len_and_clear(4);
+        assert_eq!(buffer.channel(0), [0.0; 4]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn exceed_capacity() {
+        let mut buffer = FixedBuffer::new(1, 4);
+        buffer.set_len(5);
+    }
+}
@@ -0,0 +1,140 @@
+use std::ops::Range;
+
+use crate::signals::{signal::{Signal, SignalMut}, signal_base::{SignalBase, SignalMutBase}, slice::{SignalSlice, SignalSliceMut}};
+
+use super::buffer::Buffer;
+
+/// Fixed size multichannel circular buffer
+///
+/// Delays are counted back from the most recently written sample, so a delay of zero is the newest sample.
+/// As a signal, the ring buffer exposes its storage in memory order.
+#[derive(Clone, Debug)]
+pub struct RingBuffer {
+    buffer: Buffer,
+    write_position: usize,
+}
+
+impl RingBuffer {
+    pub fn new(channels: usize, length: usize) -> Self {
+        assert!(length > 0);
+
+        Self {
+            buffer: Buffer::new(channels, length),
+            write_position: 0,
+        }
+    }
+
+    /// Index in the storage where the next sample will be written
+    pub fn write_position(&self) -> usize {
+        self.write_position
+    }
+
+    pub fn reset(&mut self) {
+        self.buffer.fill(0.0);
+        self.write_position = 0;
+    }
+
+    pub fn write(&mut self, input: &impl Signal) {
+        self.write_with(input.len(), |slice, range| slice.copy_from_signal(&input.slice(range)));
+    }
+
+    /// Calls `function` with the slices to write `length` samples to and their range in the written block,
+    /// then advances the write position
+    pub fn write_with(&mut self, length: usize, function: impl FnMut(&mut SignalSliceMut<'_, Buffer>, Range<usize>)) {
+        assert!(length <= self.len(), "Can't write {length} samples to a ring buffer of length {}", self.len());
+
+        if length == 0 {
+            return;
+        }
+
+        self.buffer.apply_wrap_mut(self.write_position, length, function);
+        self.advance(length);
+    }
+
+    /// Moves the write position forward without writing, the skipped samples keep their old values
+    pub fn advance(&mut self, length: usize) {
+        self.write_position = (self.write_position + length) % self.len();
+    }
+
+    /// Calls `function` with the slices of the `length` samples that end `delay` samples before the newest sample,
+    /// along with their range in the read block
+    pub fn read(&self, delay: usize, length: usize, function: impl FnMut(&SignalSlice<'_, Buffer>, Range<usize>)) {
+        assert!(delay + length <= self.len(), "Can't read {length} samples with a delay of {delay} from a ring buffer of length {}", self.len());
+
+        if length == 0 {
+            return;
+        }
+
+        let start = (self.write_position + 2 * self.len() - delay - length) % self.len();
+        self.buffer.apply_wrap(start, length, function);
+    }
+
+    pub fn read_into(&self, delay: usize, output: &mut impl SignalMut) {
+        self.read(delay, output.len(), |slice, range| output.slice_mut(range).copy_from_signal(slice));
+    }
+
+    pub fn sample(&self, channel: usize, delay: usize) -> f32 {
+        self.buffer.channel(channel)[self.index(delay)]
+    }
+
+    /// Storage index of the sample `delay` samples before the newest one
+    pub fn index(&self, delay: usize) -> usize {
+        assert!(delay < self.len());
+        (self.write_position + 2 * self.len() - 1 - delay) % self.len()
+    }
+}
+
+impl SignalBase for RingBuffer {
+    fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn channels(&self) -> usize {
+        self.buffer.channels()
+    }
+
+    fn channel_ptr(&self, channel: usize) -> *const [f32] {
+        self.buffer.channel_ptr(channel)
+    }
+}
+
+impl SignalMutBase for RingBuffer {
+    fn channel_ptr_mut(&mut self, channel: usize) -> *mut [f32] {
+        self.buffer.channel_ptr_mut(channel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{buffers::buffer::Buffer, signals::signal::Signal};
+
+    use super::RingBuffer;
+
+    #[test]
+    fn write_wraps() {
+        let mut ring_buffer = RingBuffer::new(1, 4);
+        ring_buffer.write(&Buffer::from(vec![vec![1.0, 2.0, 3.0]]));
+        ring_buffer.write(&Buffer::from(vec![vec![4.0, 5.0]]));
+
+        assert_eq!(ring_buffer.channel(0), [5.0, 2.0, 3.0, 4.0]);
+        assert_eq!(ring_buffer.write_position(), 1);
+        assert_eq!(ring_buffer.sample(0, 0), 5.0);
+        assert_eq!(ring_buffer.sample(0, 3), 2.0);
+    }
+
+    #[test]
+    fn read_wraps() {
+        let mut ring_buffer = RingBuffer::new(2, 4);
+        ring_buffer.write(&Buffer::from(vec![vec![1.0, 2.0, 3.0], vec![-1.0, -2.0, -3.0]]));
+        ring_buffer.write(&Buffer::from(vec![vec![4.0, 5.0], vec![-4.0, -5.0]]));
+
+        let mut output = Buffer::new(2, 3);
+        ring_buffer.read_into(0, &mut output);
+        assert_eq!(output.channel(0), [3.0, 4.0, 5.0]);
+        assert_eq!(output.channel(1), [-3.0, -4.0, -5.0]);
+
+        let mut output = Buffer::new(2, 2);
+        ring_buffer.read_into(2, &mut output);
+        assert_eq!(output.channel(0), [2.0, 3.0]);
+    }
+}
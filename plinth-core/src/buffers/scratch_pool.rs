@@ -0,0 +1,153 @@
+use std::{cell::{Cell, UnsafeCell}, ops::Deref};
+
+use crate::signals::{sample::Sample, signal_base::{SignalBase, SignalMutBase}};
+
+use super::fixed_buffer::FixedBuffer;
+
+/// Preallocated temporary signals for use during processing
+///
+/// Borrowing only takes `&self`, so several scratch signals can be in use at once. They're returned when dropped.
+pub struct ScratchPool<T: Sample = f32> {
+    buffers: Box<[UnsafeCell<FixedBuffer<T>>]>,
+    in_use: Box<[Cell<bool>]>,
+    capacity: usize,
+}
+
+impl ScratchPool {
+    pub fn new(count: usize, channels: usize, capacity: usize) -> Self {
+        Self::silent(count, channels, capacity)
+    }
+}
+
+impl<T: Sample> ScratchPool<T> {
+    /// `new()` for any sample type
+    pub fn silent(count: usize, channels: usize, capacity: usize) -> Self {
+        Self {
+            buffers: (0..count).map(|_| UnsafeCell::new(FixedBuffer::silent(channels, capacity))).collect(),
+            in_use: (0..count).map(|_| Cell::new(false)).collect(),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of scratch signals that aren't borrowed
+    pub fn available(&self) -> usize {
+        self.in_use.iter().filter(|in_use| !in_use.get()).count()
+    }
+
+    /// Returns a signal of `length` samples, or `None` when all of them are in use
+    ///
+    /// The samples aren't cleared, so they hold whatever the last user left in them.
+    pub fn try_borrow(&self, length: usize) -> Option<ScratchSignal<'_, T>> {
+        // Checked before claiming a buffer, so a failed borrow doesn't leave it marked as in use
+        assert!(length <= self.capacity, "Length {length} exceeds the scratch pool capacity of {}", self.capacity);
+
+        let index = self.in_use.iter().position(|in_use| !in_use.get())?;
+        self.in_use[index].set(true);
+
+        // Only one ScratchSignal refers to a buffer while its in use flag is set
+        let buffer = unsafe { &mut *self.buffers[index].get() };
+        buffer.set_len(length);
+
+        Some(ScratchSignal {
+            buffer,
+            in_use: &self.in_use[index],
+        })
+    }
+
+    pub fn borrow(&self, length: usize) -> ScratchSignal<'_, T> {
+        self.try_borrow(length)
+            .unwrap_or_else(|| panic!("All {} scratch signals are in use", self.buffers.len()))
+    }
+}
+
+pub struct ScratchSignal<'pool, T: Sample = f32> {
+    buffer: &'pool mut FixedBuffer<T>,
+    in_use: &'pool Cell<bool>,
+}
+
+impl<T: Sample> Deref for ScratchSignal<'_, T> {
+    type Target = FixedBuffer<T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.buffer
+    }
+}
+
+impl<T: Sample> ScratchSignal<'_, T> {
+    /// See `FixedBuffer::set_len()`
+    pub fn set_len(&mut self, length: usize) {
+        self.buffer.set_len(length);
+    }
+
+    /// See `FixedBuffer::set_len_and_clear()`
+    pub fn set_len_and_clear(&mut self, length: usize) {
+        self.buffer.set_len_and_clear(length);
+    }
+}
+
+impl<T: Sample> SignalBase<T> for ScratchSignal<'_, T> {
+    fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn channels(&self) -> usize {
+        self.buffer.channels()
+    }
+
+    fn channel_ptr(&self, channel: usize) -> *const [T] {
+        self.buffer.channel_ptr(channel)
+    }
+}
+
+impl<T: Sample> SignalMutBase<T> for ScratchSignal<'_, T> {
+    fn channel_ptr_mut(&mut self, channel: usize) -> *mut [T] {
+        self.buffer.channel_ptr_mut(channel)
+    }
+}
+
+impl<T: Sample> Drop for ScratchSignal<'_, T> {
+    fn drop(&mut self) {
+        self.in_use.set(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::signals::{signal::{Signal, SignalMut}, signal_base::SignalBase};
+
+    use super::ScratchPool;
+
+    #[test]
+    fn borrow_and_return() {
+        let pool = ScratchPool::new(2, 2, 16);
+
+        {
+            let mut first = pool.borrow(8);
+            let mut second = pool.borrow(4);
+            assert!(pool.try_borrow(4).is_none());
+
+            first.fill(1.0);
+            second.copy_from_signal(&first.slice(..4));
+            assert_eq!(second.len(), 4);
+            assert_eq!(second.channel(1), [1.0; 4]);
+
+            second.set_len_and_clear(2);
+            assert_eq!(second.channel(0), [0.0; 2]);
+        }
+
+        assert_eq!(pool.available(), 2);
+    }
+
+    #[test]
+    fn oversized_borrow_keeps_buffers_available() {
+        let pool = ScratchPool::new(1, 1, 16);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| pool.try_borrow(17).is_some()));
+        assert!(result.is_err());
+        assert_eq!(pool.available(), 1);
+    }
+}
@@ -0,0 +1,290 @@
+use std::{fs::File, io::{BufReader, BufWriter, Read, Write}, path::Path};
+
+use crate::signals::signal::{Signal, SignalMut};
+
+use super::buffer::Buffer;
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_FLOAT: u16 = 3;
+const FORMAT_EXTENSIBLE: u16 = 0xfffe;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum WavFormat {
+    Pcm16,
+    Pcm24,
+    Pcm32,
+    #[default]
+    Float32,
+    Float64,
+}
+
+impl WavFormat {
+    fn format_tag(&self) -> u16 {
+        match self {
+            WavFormat::Pcm16 | WavFormat::Pcm24 | WavFormat::Pcm32 => FORMAT_PCM,
+            WavFormat::Float32 | WavFormat::Float64 => FORMAT_FLOAT,
+        }
+    }
+
+    fn bits_per_sample(&self) -> u16 {
+        match self {
+            WavFormat::Pcm16 => 16,
+            WavFormat::Pcm24 => 24,
+            WavFormat::Pcm32 | WavFormat::Float32 => 32,
+            WavFormat::Float64 => 64,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum WavError {
+    IoError(std::io::Error),
+    InvalidFile(&'static str),
+    UnsupportedFormat {
+        format_tag: u16,
+        bits_per_sample: u16,
+    },
+}
+
+impl From<std::io::Error> for WavError {
+    fn from(error: std::io::Error) -> Self {
+        Self::IoError(error)
+    }
+}
+
+/// Returns the file contents and its sample rate
+pub fn read_wav(path: impl AsRef<Path>) -> Result<(Buffer, f64), WavError> {
+    read_wav_from(BufReader::new(File::open(path)?))
+}
+
+pub fn read_wav_from(mut reader: impl Read) -> Result<(Buffer, f64), WavError> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return Err(WavError::InvalidFile("Missing RIFF/WAVE header"));
+    }
+
+    let mut format = None;
+    let mut samples = None;
+    let mut position = 12;
+
+    while position + 8 <= data.len() {
+        let id = &data[position..position + 4];
+        let size = u32::from_le_bytes(data[position + 4..position + 8].try_into().unwrap()) as usize;
+        // Streamed files can have a placeholder size, so don't trust it past the end of the data
+        let chunk = &data[position + 8..usize::min(position + 8 + size, data.len())];
+
+        match id {
+            b"fmt " => format = Some(read_format(chunk)?),
+            b"data" => samples = Some(chunk),
+            _ => {}
+        }
+
+        // Chunks are padded to an even size
+        position += 8 + size + (size & 1);
+    }
+
+    let format = format.ok_or(WavError::InvalidFile("Missing fmt chunk"))?;
+    let samples = samples.ok_or(WavError::InvalidFile("Missing data chunk"))?;
+
+    let bytes_per_sample = format.bits_per_sample as usize / 8;
+    let frame_size = bytes_per_sample * format.channels;
+    let mut buffer = Buffer::new(format.channels, samples.len() / frame_size);
+
+    for (frame_index, frame) in samples.chunks_exact(frame_size).enumerate() {
+        for (channel, sample) in frame.chunks_exact(bytes_per_sample).enumerate() {
+            buffer.channel_mut(channel)[frame_index] = decode_sample(format.format_tag, sample);
+        }
+    }
+
+    Ok((buffer, format.sample_rate as f64))
+}
+
+pub fn write_wav(path: impl AsRef<Path>, signal: &impl Signal, sample_rate: f64, format: WavFormat) -> Result<(), WavError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_wav_to(&mut writer, signal, sample_rate, format)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+pub fn write_wav_to(mut writer: impl Write, signal: &impl Signal, sample_rate: f64, format: WavFormat) -> Result<(), WavError> {
+    let channels = signal.channels() as u16;
+    let bytes_per_sample = format.bits_per_sample() / 8;
+    let block_align = channels * bytes_per_sample;
+    let data_size = signal.len() as u64 * block_align as u64;
+
+    if data_size > (u32::MAX - 37) as u64 {
+        return Err(WavError::InvalidFile("Signal is too long for a WAV file"));
+    }
+
+    let data_size = data_size as u32;
+    // Chunks are word aligned, an odd data chunk is followed by a pad byte
+    let padding = data_size & 1;
+
+    writer.write_all(b"RIFF")?;
+    writer.write_all(&(36 + data_size + padding).to_le_bytes())?;
+    writer.write_all(b"WAVE")?;
+
+    writer.write_all(b"fmt ")?;
+    writer.write_all(&16u32.to_le_bytes())?;
+    writer.write_all(&format.format_tag().to_le_bytes())?;
+    writer.write_all(&channels.to_le_bytes())?;
+    writer.write_all(&(sample_rate.round() as u32).to_le_bytes())?;
+    writer.write_all(&(sample_rate.round() as u32 * block_align as u32).to_le_bytes())?;
+    writer.write_all(&block_align.to_le_bytes())?;
+    writer.write_all(&format.bits_per_sample().to_le_bytes())?;
+
+    writer.write_all(b"data")?;
+    writer.write_all(&data_size.to_le_bytes())?;
+
+    for index in 0..signal.len() {
+        for channel in signal.iter_channels() {
+            let sample = channel[index];
+
+            match format {
+                WavFormat::Pcm16 => writer.write_all(&(quantize(sample, 16) as i16).to_le_bytes())?,
+                WavFormat::Pcm24 => writer.write_all(&quantize(sample, 24).to_le_bytes()[..3])?,
+                WavFormat::Pcm32 => writer.write_all(&quantize(sample, 32).to_le_bytes())?,
+                WavFormat::Float32 => writer.write_all(&sample.to_le_bytes())?,
+                WavFormat::Float64 => writer.write_all(&(sample as f64).to_le_bytes())?,
+            }
+        }
+    }
+
+    if padding != 0 {
+        writer.write_all(&[0])?;
+    }
+
+    Ok(())
+}
+
+struct Format {
+    format_tag: u16,
+    channels: usize,
+    sample_rate: u32,
+    bits_per_sample: u16,
+}
+
+fn read_format(chunk: &[u8]) -> Result<Format, WavError> {
+    if chunk.len() < 16 {
+        return Err(WavError::InvalidFile("fmt chunk is too short"));
+    }
+
+    let read_u16 = |offset: usize| u16::from_le_bytes([chunk[offset], chunk[offset + 1]]);
+
+    let mut format_tag = read_u16(0);
+    let channels = read_u16(2) as usize;
+    let sample_rate = u32::from_le_bytes(chunk[4..8].try_into().unwrap());
+    let bits_per_sample = read_u16(14);
+
+    // The actual format is the start of the sub-format GUID
+    if format_tag == FORMAT_EXTENSIBLE {
+        if chunk.len() < 26 {
+            return Err(WavError::InvalidFile("Extensible fmt chunk is too short"));
+        }
+
+        format_tag = read_u16(24);
+    }
+
+    let supported = match format_tag {
+        FORMAT_PCM => matches!(bits_per_sample, 8 | 16 | 24 | 32),
+        FORMAT_FLOAT => matches!(bits_per_sample, 32 | 64),
+        _ => false,
+    };
+
+    if !supported {
+        return Err(WavError::UnsupportedFormat { format_tag, bits_per_sample });
+    }
+
+    if channels == 0 {
+        return Err(WavError::InvalidFile("File has no channels"));
+    }
+
+    Ok(Format {
+        format_tag,
+        channels,
+        sample_rate,
+        bits_per_sample,
+    })
+}
+
+fn decode_sample(format_tag: u16, bytes: &[u8]) -> f32 {
+    match (format_tag, bytes.len()) {
+        (FORMAT_FLOAT, 4) => f32::from_le_bytes(bytes.try_into().unwrap()),
+        (FORMAT_FLOAT, 8) => f64::from_le_bytes(bytes.try_into().unwrap()) as f32,
+        // 8-bit PCM is unsigned
+        (_, 1) => (bytes[0] as f32 - 128.0) / 128.0,
+        (_, 2) => i16::from_le_bytes(bytes.try_into().unwrap()) as f32 / 32768.0,
+        // Shift 24-bit samples to the top of an i32 to sign extend them
+        (_, 3) => i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) as f32 / 2147483648.0,
+        (_, 4) => i32::from_le_bytes(bytes.try_into().unwrap()) as f32 / 2147483648.0,
+        _ => unreachable!(),
+    }
+}
+
+fn quantize(sample: f32, bits: u32) -> i32 {
+    let scale = (1u64 << (bits - 1)) as f64;
+    (sample as f64 * scale).round().clamp(-scale, scale - 1.0) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{buffers::buffer::Buffer, signals::signal::Signal};
+
+    use super::{read_wav_from, write_wav_to, WavError, WavFormat};
+
+    #[test]
+    fn round_trip() {
+        let buffer = Buffer::from(vec![
+            vec![0.0, 0.5, -0.5, 0.999, -1.0],
+            vec![0.25, -0.25, 0.125, -0.125, 0.0],
+            vec![0.1, 0.2, 0.3, 0.4, 0.5],
+        ]);
+
+        for (format, tolerance) in [
+            (WavFormat::Pcm16, 1.0 / 32768.0),
+            (WavFormat::Pcm24, 1.0 / 8388608.0),
+            (WavFormat::Pcm32, 1e-7),
+            (WavFormat::Float32, 0.0),
+            (WavFormat::Float64, 0.0),
+        ] {
+            let mut data = Vec::new();
+            write_wav_to(&mut data, &buffer, 44100.0, format).unwrap();
+
+            let (read_buffer, sample_rate) = read_wav_from(data.as_slice()).unwrap();
+            assert_eq!(sample_rate, 44100.0);
+
+            for (read_channel, channel) in read_buffer.iter_channels().zip(buffer.iter_channels()) {
+                assert_eq!(read_channel.len(), channel.len());
+
+                for (read_sample, sample) in read_channel.iter().zip(channel) {
+                    assert!((read_sample - sample).abs() <= tolerance, "{format:?}: {read_sample} != {sample}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn odd_data_size_is_padded() {
+        let buffer = Buffer::from(vec![vec![0.5, -0.5, 0.25]]);
+
+        let mut data = Vec::new();
+        write_wav_to(&mut data, &buffer, 48000.0, WavFormat::Pcm24).unwrap();
+
+        assert_eq!(data.len(), 44 + 9 + 1);
+        assert_eq!(u32::from_le_bytes(data[4..8].try_into().unwrap()) as usize, data.len() - 8);
+
+        assert_eq!(data.last(), Some(&0));
+
+        let (read_buffer, _) = read_wav_from(data.as_slice()).unwrap();
+        assert_eq!(read_buffer.channel(0).len(), 3);
+        assert!((read_buffer.channel(0)[2] - 0.25).abs() <= 1.0 / 8388608.0);
+    }
+
+    #[test]
+    fn invalid_file() {
+        assert!(matches!(read_wav_from(&b"RIFX\0\0\0\0WAVE"[..]), Err(WavError::InvalidFile(_))));
+    }
+}
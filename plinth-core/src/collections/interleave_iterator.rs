@@ -16,7 +16,7 @@ where
         let mut iterator_array: [Option<I>; MAX_ITERATORS] = Default::default();
 
         let mut iterator_count = 0;
-        for (it, array_it) in zip(iterators.into_iter(), iterator_array.iter_mut()) {
+        for (it, array_it) in zip(iterators, iterator_array.iter_mut()) {
             *array_it = Some(it);
             iterator_count += 1;
         }
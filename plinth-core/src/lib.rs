@@ -1,4 +1,12 @@
+#![cfg_attr(feature = "portable-simd", feature(portable_simd))]
+
 pub mod buffers;
 pub mod collections;
+pub mod metering;
+pub mod oversampling;
+pub mod resampling;
+pub mod routing;
 pub mod signals;
+pub mod simd;
+pub mod spectral;
 pub mod util;
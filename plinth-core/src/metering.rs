@@ -0,0 +1,5 @@
+pub mod loudness;
+pub mod peak;
+pub mod readout;
+pub mod rms;
+pub mod true_peak;
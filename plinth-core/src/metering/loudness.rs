@@ -0,0 +1,348 @@
+use std::{f64::consts::PI, sync::Arc};
+
+use portable_atomic::{AtomicF32, Ordering};
+
+use crate::signals::signal::Signal;
+
+const SUB_BLOCK_SECONDS: f64 = 0.1;
+const MOMENTARY_SUB_BLOCKS: usize = 4;
+const SHORT_TERM_SUB_BLOCKS: usize = 30;
+
+const ABSOLUTE_GATE: f64 = -70.0;
+const RELATIVE_GATE: f64 = -10.0;
+
+// Gating blocks are binned by loudness so integrated loudness works in constant memory
+const HISTOGRAM_MIN: f64 = ABSOLUTE_GATE;
+const HISTOGRAM_MAX: f64 = 10.0;
+const HISTOGRAM_BINS_PER_LU: f64 = 10.0;
+const HISTOGRAM_BINS: usize = ((HISTOGRAM_MAX - HISTOGRAM_MIN) * HISTOGRAM_BINS_PER_LU) as usize;
+
+/// Loudness values in LUFS, written on the audio thread and read from the editor
+///
+/// Values are `f32::NEG_INFINITY` until there's enough signal to measure.
+pub struct LoudnessReadout {
+    momentary: AtomicF32,
+    short_term: AtomicF32,
+    integrated: AtomicF32,
+}
+
+impl LoudnessReadout {
+    fn new() -> Self {
+        Self {
+            momentary: f32::NEG_INFINITY.into(),
+            short_term: f32::NEG_INFINITY.into(),
+            integrated: f32::NEG_INFINITY.into(),
+        }
+    }
+
+    /// Loudness over the last 400 ms
+    pub fn momentary(&self) -> f32 {
+        self.momentary.load(Ordering::Relaxed)
+    }
+
+    /// Loudness over the last 3 seconds
+    pub fn short_term(&self) -> f32 {
+        self.short_term.load(Ordering::Relaxed)
+    }
+
+    /// Gated loudness since the meter was reset
+    pub fn integrated(&self) -> f32 {
+        self.integrated.load(Ordering::Relaxed)
+    }
+
+    fn reset(&self) {
+        self.momentary.store(f32::NEG_INFINITY, Ordering::Relaxed);
+        self.short_term.store(f32::NEG_INFINITY, Ordering::Relaxed);
+        self.integrated.store(f32::NEG_INFINITY, Ordering::Relaxed);
+    }
+}
+
+/// ITU-R BS.1770 loudness meter
+pub struct LoudnessMeter {
+    channel_weights: Vec<f64>,
+    filters: Vec<[Biquad; 2]>,
+
+    sub_block_length: usize,
+    sub_block_position: usize,
+    sub_block_energy: f64,
+    // Mean square of the most recent sub-blocks, oldest first
+    sub_blocks: [f64; SHORT_TERM_SUB_BLOCKS],
+    sub_block_count: usize,
+
+    histogram: Vec<HistogramBin>,
+    readout: Arc<LoudnessReadout>,
+}
+
+impl LoudnessMeter {
+    pub fn new(channels: usize, sample_rate: f64) -> Self {
+        Self {
+            channel_weights: vec![1.0; channels],
+            filters: (0..channels).map(|_| k_weighting(sample_rate)).collect(),
+
+            sub_block_length: usize::max(1, (SUB_BLOCK_SECONDS * sample_rate).round() as usize),
+            sub_block_position: 0,
+            sub_block_energy: 0.0,
+            sub_blocks: [0.0; SHORT_TERM_SUB_BLOCKS],
+            sub_block_count: 0,
+
+            histogram: vec![HistogramBin::default(); HISTOGRAM_BINS],
+            readout: LoudnessReadout::new().into(),
+        }
+    }
+
+    /// Weights of the channels' energy, BS.1770 uses 1.41 for surround channels and 1.0 for the rest
+    pub fn with_channel_weights(mut self, weights: &[f64]) -> Self {
+        assert_eq!(weights.len(), self.channel_weights.len());
+        self.channel_weights.copy_from_slice(weights);
+        self
+    }
+
+    pub fn readout(&self) -> Arc<LoudnessReadout> {
+        self.readout.clone()
+    }
+
+    pub fn reset(&mut self) {
+        for filter in self.filters.iter_mut().flatten() {
+            filter.reset();
+        }
+
+        self.sub_block_position = 0;
+        self.sub_block_energy = 0.0;
+        self.sub_blocks.fill(0.0);
+        self.sub_block_count = 0;
+
+        self.reset_integrated();
+        self.readout.reset();
+    }
+
+    /// Starts a new integrated loudness measurement
+    pub fn reset_integrated(&mut self) {
+        self.histogram.fill(HistogramBin::default());
+        self.readout.integrated.store(f32::NEG_INFINITY, Ordering::Relaxed);
+    }
+
+    pub fn process(&mut self, signal: &impl Signal) {
+        let channels = usize::min(signal.channels(), self.filters.len());
+        let mut start = 0;
+
+        while start < signal.len() {
+            let length = usize::min(self.sub_block_length - self.sub_block_position, signal.len() - start);
+
+            for channel in 0..channels {
+                let [shelf, high_pass] = &mut self.filters[channel];
+                let mut energy = 0.0;
+
+                for &sample in &signal.channel(channel)[start..start + length] {
+                    let filtered = high_pass.process(shelf.process(sample as f64));
+                    energy += filtered * filtered;
+                }
+
+                self.sub_block_energy += self.channel_weights[channel] * energy;
+            }
+
+            start += length;
+            self.sub_block_position += length;
+
+            if self.sub_block_position == self.sub_block_length {
+                self.finish_sub_block();
+            }
+        }
+    }
+
+    fn finish_sub_block(&mut self) {
+        self.sub_blocks.rotate_left(1);
+        self.sub_blocks[SHORT_TERM_SUB_BLOCKS - 1] = self.sub_block_energy / self.sub_block_length as f64;
+        self.sub_block_count += 1;
+
+        self.sub_block_position = 0;
+        self.sub_block_energy = 0.0;
+
+        let momentary_energy = mean(&self.sub_blocks[SHORT_TERM_SUB_BLOCKS - MOMENTARY_SUB_BLOCKS..]);
+
+        if self.sub_block_count >= MOMENTARY_SUB_BLOCKS {
+            self.readout.momentary.store(energy_to_loudness(momentary_energy) as f32, Ordering::Relaxed);
+
+            // Momentary blocks double as the overlapping gating blocks
+            self.add_gating_block(momentary_energy);
+        }
+
+        if self.sub_block_count >= SHORT_TERM_SUB_BLOCKS {
+            self.readout.short_term.store(energy_to_loudness(mean(&self.sub_blocks)) as f32, Ordering::Relaxed);
+        }
+    }
+
+    fn add_gating_block(&mut self, energy: f64) {
+        let loudness = energy_to_loudness(energy);
+        if loudness <= ABSOLUTE_GATE {
+            return;
+        }
+
+        let bin_index = usize::min(((loudness - HISTOGRAM_MIN) * HISTOGRAM_BINS_PER_LU) as usize, HISTOGRAM_BINS - 1);
+        let bin = &mut self.histogram[bin_index];
+        bin.count += 1;
+        bin.energy += energy;
+
+        let (count, energy) = sum_bins(&self.histogram);
+        let relative_gate = energy_to_loudness(energy / count as f64) + RELATIVE_GATE;
+        let first_bin = ((relative_gate - HISTOGRAM_MIN) * HISTOGRAM_BINS_PER_LU).ceil().max(0.0) as usize;
+
+        let (count, energy) = sum_bins(&self.histogram[usize::min(first_bin, HISTOGRAM_BINS)..]);
+        if count > 0 {
+            self.readout.integrated.store(energy_to_loudness(energy / count as f64) as f32, Ordering::Relaxed);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct HistogramBin {
+    count: u64,
+    energy: f64,
+}
+
+fn sum_bins(bins: &[HistogramBin]) -> (u64, f64) {
+    bins.iter().fold((0, 0.0), |(count, energy), bin| (count + bin.count, energy + bin.energy))
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn energy_to_loudness(energy: f64) -> f64 {
+    -0.691 + 10.0 * energy.log10()
+}
+
+struct Biquad {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+
+    z1: f64,
+    z2: f64,
+}
+
+impl Biquad {
+    fn new(b0: f64, b1: f64, b2: f64, a1: f64, a2: f64) -> Self {
+        Self { b0, b1, b2, a1, a2, z1: 0.0, z2: 0.0 }
+    }
+
+    fn reset(&mut self) {
+        self.z1 = 0.0;
+        self.z2 = 0.0;
+    }
+
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b0 * x + self.z1;
+        self.z1 = self.b1 * x - self.a1 * y + self.z2;
+        self.z2 = self.b2 * x - self.a2 * y;
+
+        y
+    }
+}
+
+// The BS.1770 filters are specified at 48 kHz, these are the analog prototypes matched at any sample rate
+fn k_weighting(sample_rate: f64) -> [Biquad; 2] {
+    let shelf = {
+        let frequency = 1681.974450955533;
+        let gain_db = 3.999843853973347;
+        let q = 0.7071752369554196;
+
+        let k = (PI * frequency / sample_rate).tan();
+        let vh = 10.0f64.powf(gain_db / 20.0);
+        let vb = vh.powf(0.4996667741545416);
+        let a0 = 1.0 + k / q + k * k;
+
+        Biquad::new(
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+            2.0 * (k * k - 1.0) / a0,
+            (1.0 - k / q + k * k) / a0,
+        )
+    };
+
+    let high_pass = {
+        let frequency = 38.13547087602444;
+        let q = 0.5003270373238773;
+
+        let k = (PI * frequency / sample_rate).tan();
+        let a0 = 1.0 + k / q + k * k;
+
+        Biquad::new(
+            1.0,
+            -2.0,
+            1.0,
+            2.0 * (k * k - 1.0) / a0,
+            (1.0 - k / q + k * k) / a0,
+        )
+    };
+
+    [shelf, high_pass]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use crate::buffers::buffer::Buffer;
+
+    use super::{k_weighting, LoudnessMeter};
+
+    fn sine(sample_rate: f64, frequency: f64, amplitude: f64, seconds: f64) -> Vec<f32> {
+        (0..(sample_rate * seconds) as usize)
+            .map(|index| (amplitude * (TAU * frequency * index as f64 / sample_rate).sin()) as f32)
+            .collect()
+    }
+
+    #[test]
+    fn filter_coefficients_at_48k() {
+        let [shelf, high_pass] = k_weighting(48000.0);
+
+        for (actual, expected) in [shelf.b0, shelf.b1, shelf.b2, shelf.a1, shelf.a2].iter().zip([1.53512485958697, -2.69169618940638, 1.19839281085285, -1.69065929318241, 0.73248077421585]) {
+            assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+        }
+
+        for (actual, expected) in [high_pass.a1, high_pass.a2].iter().zip([-1.99004745483398, 0.99007225036621]) {
+            assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+        }
+    }
+
+    #[test]
+    fn full_scale_sine() {
+        // A 0 dBFS 1 kHz sine reads as -3.01 LUFS per channel, so 0 LUFS in stereo
+        let sample_rate = 48000.0;
+        let channel = sine(sample_rate, 997.0, 1.0, 5.0);
+
+        let mut meter = LoudnessMeter::new(2, sample_rate);
+        let readout = meter.readout();
+
+        for start in (0..channel.len()).step_by(512) {
+            let end = usize::min(start + 512, channel.len());
+            meter.process(&Buffer::from(vec![channel[start..end].to_vec(), channel[start..end].to_vec()]));
+        }
+
+        assert!(readout.momentary().abs() < 0.1, "{}", readout.momentary());
+        assert!(readout.short_term().abs() < 0.1, "{}", readout.short_term());
+        assert!(readout.integrated().abs() < 0.1, "{}", readout.integrated());
+    }
+
+    #[test]
+    fn integrated_gating() {
+        // EBU Tech 3341 test case 3: -36, -23 and -36 dBFS tones for 10, 60 and 10 seconds read as -23 LUFS
+        let sample_rate = 48000.0;
+        let mut meter = LoudnessMeter::new(2, sample_rate);
+        let readout = meter.readout();
+
+        for (amplitude_db, seconds) in [(-36.0, 10.0), (-23.0, 60.0), (-36.0, 10.0)] {
+            let amplitude = 10.0f64.powf(amplitude_db / 20.0);
+            let channel = sine(sample_rate, 1000.0, amplitude, seconds);
+
+            for chunk in channel.chunks(1024) {
+                meter.process(&Buffer::from(vec![chunk.to_vec(), chunk.to_vec()]));
+            }
+        }
+
+        assert!((readout.integrated() - -23.0).abs() < 0.1, "{}", readout.integrated());
+    }
+}
@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use crate::{signals::signal::Signal, simd};
+
+use super::readout::ChannelReadout;
+
+const DEFAULT_HOLD_SECONDS: f64 = 0.5;
+const DEFAULT_DECAY_DB_PER_SECOND: f64 = 20.0;
+
+/// Sample peak meter with peak hold and logarithmic decay
+pub struct PeakMeter {
+    sample_rate: f64,
+    hold_length: usize,
+    // Gain applied per sample once the hold time is over
+    decay: f64,
+
+    levels: Vec<f32>,
+    hold_remaining: Vec<usize>,
+    readout: Arc<ChannelReadout>,
+}
+
+impl PeakMeter {
+    pub fn new(channels: usize, sample_rate: f64) -> Self {
+        let mut meter = Self {
+            sample_rate,
+            hold_length: 0,
+            decay: 1.0,
+
+            levels: vec![0.0; channels],
+            hold_remaining: vec![0; channels],
+            readout: ChannelReadout::new(channels).into(),
+        };
+
+        meter.set_ballistics(DEFAULT_HOLD_SECONDS, DEFAULT_DECAY_DB_PER_SECOND);
+        meter
+    }
+
+    pub fn with_hold(mut self, hold_seconds: f64) -> Self {
+        self.set_ballistics(hold_seconds, self.decay_db_per_second());
+        self
+    }
+
+    pub fn with_decay(mut self, decay_db_per_second: f64) -> Self {
+        self.set_ballistics(self.hold_length as f64 / self.sample_rate, decay_db_per_second);
+        self
+    }
+
+    /// Linear peak levels for the editor
+    pub fn readout(&self) -> Arc<ChannelReadout> {
+        self.readout.clone()
+    }
+
+    pub fn reset(&mut self) {
+        self.levels.fill(0.0);
+        self.hold_remaining.fill(0);
+        self.readout.reset();
+    }
+
+    pub fn process(&mut self, signal: &impl Signal) {
+        for (channel, samples) in signal.iter_channels().enumerate().take(self.levels.len()) {
+            let peak = simd::abs_max(samples);
+            self.update(channel, peak, samples.len());
+        }
+    }
+
+    /// Feeds the peak of a block of `length` samples
+    pub(crate) fn update(&mut self, channel: usize, peak: f32, length: usize) {
+        let level = &mut self.levels[channel];
+        let hold_remaining = &mut self.hold_remaining[channel];
+
+        if peak >= *level {
+            *level = peak;
+            *hold_remaining = self.hold_length;
+        } else if *hold_remaining >= length {
+            *hold_remaining -= length;
+        } else {
+            let decay_length = length - *hold_remaining;
+            *hold_remaining = 0;
+            *level = f32::max(peak, *level * self.decay.powi(decay_length as i32) as f32);
+        }
+
+        self.readout.set(channel, *level);
+    }
+
+    fn decay_db_per_second(&self) -> f64 {
+        -20.0 * self.decay.log10() * self.sample_rate
+    }
+
+    fn set_ballistics(&mut self, hold_seconds: f64, decay_db_per_second: f64) {
+        self.hold_length = (hold_seconds * self.sample_rate) as usize;
+        self.decay = 10.0f64.powf(-decay_db_per_second / 20.0 / self.sample_rate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffers::buffer::Buffer;
+
+    use super::PeakMeter;
+
+    #[test]
+    fn hold_and_decay() {
+        let mut meter = PeakMeter::new(1, 100.0)
+            .with_hold(0.1)
+            .with_decay(200.0);
+        let readout = meter.readout();
+
+        meter.process(&Buffer::from(vec![vec![0.1, -0.5, 0.2]]));
+        assert_eq!(readout.get(0), 0.5);
+
+        // Held for 10 samples
+        meter.process(&Buffer::from(vec![vec![0.0; 10]]));
+        assert_eq!(readout.get(0), 0.5);
+
+        // 2 dB per sample
+        meter.process(&Buffer::from(vec![vec![0.0; 10]]));
+        assert!((readout.get(0) - 0.05).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,39 @@
+use portable_atomic::{AtomicF32, Ordering};
+
+/// Per channel meter values, written on the audio thread and read from the editor
+pub struct ChannelReadout {
+    values: Vec<AtomicF32>,
+}
+
+impl ChannelReadout {
+    pub fn new(channels: usize) -> Self {
+        Self {
+            values: (0..channels).map(|_| 0.0.into()).collect(),
+        }
+    }
+
+    pub fn channels(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn get(&self, channel: usize) -> f32 {
+        self.values[channel].load(Ordering::Relaxed)
+    }
+
+    /// Highest value over all channels
+    pub fn max(&self) -> f32 {
+        self.values.iter()
+            .map(|value| value.load(Ordering::Relaxed))
+            .fold(0.0, f32::max)
+    }
+
+    pub(crate) fn set(&self, channel: usize, value: f32) {
+        self.values[channel].store(value, Ordering::Relaxed);
+    }
+
+    pub(crate) fn reset(&self) {
+        for value in self.values.iter() {
+            value.store(0.0, Ordering::Relaxed);
+        }
+    }
+}
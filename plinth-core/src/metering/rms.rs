@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use crate::signals::signal::Signal;
+
+use super::readout::ChannelReadout;
+
+const DEFAULT_WINDOW_SECONDS: f64 = 0.3;
+
+/// RMS meter over a sliding rectangular window
+pub struct RmsMeter {
+    sample_rate: f64,
+    // Squared samples of the current window, per channel
+    squares: Vec<Vec<f32>>,
+    sums: Vec<f64>,
+    position: usize,
+    readout: Arc<ChannelReadout>,
+}
+
+impl RmsMeter {
+    pub fn new(channels: usize, sample_rate: f64) -> Self {
+        Self {
+            sample_rate,
+            squares: vec![vec![0.0; window_length(sample_rate, DEFAULT_WINDOW_SECONDS)]; channels],
+            sums: vec![0.0; channels],
+            position: 0,
+            readout: ChannelReadout::new(channels).into(),
+        }
+    }
+
+    pub fn with_window(mut self, window_seconds: f64) -> Self {
+        let window_length = window_length(self.sample_rate, window_seconds);
+        for squares in self.squares.iter_mut() {
+            *squares = vec![0.0; window_length];
+        }
+
+        self.reset();
+        self
+    }
+
+    /// Linear RMS levels for the editor
+    pub fn readout(&self) -> Arc<ChannelReadout> {
+        self.readout.clone()
+    }
+
+    pub fn reset(&mut self) {
+        for squares in self.squares.iter_mut() {
+            squares.fill(0.0);
+        }
+
+        self.sums.fill(0.0);
+        self.position = 0;
+        self.readout.reset();
+    }
+
+    pub fn process(&mut self, signal: &impl Signal) {
+        let start_position = self.position;
+
+        for (channel, samples) in signal.iter_channels().enumerate().take(self.squares.len()) {
+            let squares = &mut self.squares[channel];
+            let sum = &mut self.sums[channel];
+            let mut position = start_position;
+
+            for sample in samples {
+                let square = sample * sample;
+                *sum += square as f64 - squares[position] as f64;
+                squares[position] = square;
+
+                position += 1;
+                if position == squares.len() {
+                    position = 0;
+                }
+            }
+
+            // Rounding errors can push an empty window slightly below zero
+            let mean = f64::max(0.0, *sum / squares.len() as f64);
+            self.readout.set(channel, mean.sqrt() as f32);
+        }
+
+        if let Some(squares) = self.squares.first() {
+            self.position = (start_position + signal.len()) % squares.len();
+        }
+    }
+}
+
+fn window_length(sample_rate: f64, window_seconds: f64) -> usize {
+    usize::max(1, (window_seconds * sample_rate).round() as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffers::buffer::Buffer;
+
+    use super::RmsMeter;
+
+    #[test]
+    fn sliding_window() {
+        let mut meter = RmsMeter::new(2, 4.0).with_window(1.0);
+        let readout = meter.readout();
+
+        meter.process(&Buffer::from(vec![vec![1.0, -1.0, 1.0, -1.0], vec![0.5; 4]]));
+        assert_eq!(readout.get(0), 1.0);
+        assert_eq!(readout.get(1), 0.5);
+
+        meter.process(&Buffer::from(vec![vec![0.0; 3], vec![0.5; 3]]));
+        assert_eq!(readout.get(0), 0.5);
+        assert_eq!(readout.get(1), 0.5);
+    }
+}
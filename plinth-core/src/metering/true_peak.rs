@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use crate::{oversampling::oversampler::{OversamplingFilter, Oversampler}, signals::signal::Signal, simd};
+
+use super::{peak::PeakMeter, readout::ChannelReadout};
+
+/// BS.1770 recommends 4x oversampling for 48 kHz
+const OVERSAMPLING_FACTOR: usize = 4;
+
+/// Peak meter that estimates the peaks between samples by oversampling
+pub struct TruePeakMeter {
+    oversampler: Oversampler,
+    max_block_size: usize,
+    peak_meter: PeakMeter,
+}
+
+impl TruePeakMeter {
+    pub fn new(channels: usize, sample_rate: f64, max_block_size: usize) -> Self {
+        Self {
+            oversampler: Oversampler::new(channels, OVERSAMPLING_FACTOR, max_block_size, OversamplingFilter::Fir),
+            max_block_size,
+            peak_meter: PeakMeter::new(channels, sample_rate),
+        }
+    }
+
+    pub fn with_hold(mut self, hold_seconds: f64) -> Self {
+        self.peak_meter = self.peak_meter.with_hold(hold_seconds);
+        self
+    }
+
+    pub fn with_decay(mut self, decay_db_per_second: f64) -> Self {
+        self.peak_meter = self.peak_meter.with_decay(decay_db_per_second);
+        self
+    }
+
+    /// Linear true peak levels for the editor
+    pub fn readout(&self) -> Arc<ChannelReadout> {
+        self.peak_meter.readout()
+    }
+
+    pub fn reset(&mut self) {
+        self.oversampler.reset();
+        self.peak_meter.reset();
+    }
+
+    pub fn process(&mut self, signal: &impl Signal) {
+        let mut start = 0;
+
+        while start < signal.len() {
+            let end = usize::min(start + self.max_block_size, signal.len());
+            let oversampled = self.oversampler.upsample(&signal.slice(start..end));
+
+            for (channel, samples) in oversampled.iter_channels().enumerate() {
+                let peak = simd::abs_max(samples);
+                self.peak_meter.update(channel, peak, end - start);
+            }
+
+            start = end;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use crate::buffers::buffer::Buffer;
+
+    use super::TruePeakMeter;
+
+    #[test]
+    fn finds_peaks_between_samples() {
+        // A quarter of the sample rate with a 45 degree phase never hits its peak on a sample
+        let samples: Vec<f32> = (0..1024).map(|index| (TAU * 0.25 * index as f32 + TAU / 8.0).sin()).collect();
+        let sample_peak = samples.iter().fold(0.0, |peak: f32, sample| peak.max(sample.abs()));
+        assert!(sample_peak < 0.71);
+
+        let mut meter = TruePeakMeter::new(1, 48000.0, 256);
+        meter.process(&Buffer::from(vec![samples]));
+
+        let true_peak = meter.readout().get(0);
+        assert!((true_peak - 1.0).abs() < 0.02, "{true_peak}");
+    }
+}
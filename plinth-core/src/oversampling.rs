@@ -0,0 +1,3 @@
+pub mod half_band_fir;
+pub mod half_band_iir;
+pub mod oversampler;
@@ -0,0 +1,135 @@
+use std::f64::consts::PI;
+
+use crate::util::window::kaiser;
+
+const KAISER_BETA: f64 = 10.0;
+
+/// Linear phase 2x up- and downsampler using a windowed sinc half-band filter
+///
+/// The filter has `4 * half_length - 1` taps, but only every other tap is non-zero so each output sample costs
+/// about `half_length * 2` multiplications.
+pub struct HalfBandFir {
+    half_length: usize,
+    // The even taps, the odd ones are all zero except for the center tap of 0.5
+    taps: Vec<f32>,
+
+    up_history: History,
+    down_even_history: History,
+    down_odd_history: History,
+}
+
+impl HalfBandFir {
+    pub fn new(half_length: usize) -> Self {
+        assert!(half_length > 0);
+
+        let length = 4 * half_length - 1;
+        let center = (length - 1) as f64 / 2.0;
+
+        let mut taps: Vec<f64> = (0..length)
+            .step_by(2)
+            .map(|index| {
+                let offset = index as f64 - center;
+                let sinc = (PI * offset / 2.0).sin() / (PI * offset);
+                let window_position = 2.0 * index as f64 / (length - 1) as f64 - 1.0;
+                let window = kaiser(window_position, KAISER_BETA);
+
+                sinc * window
+            })
+            .collect();
+
+        // Both polyphase branches need unity gain at DC
+        let sum: f64 = taps.iter().sum();
+        for tap in taps.iter_mut() {
+            *tap *= 0.5 / sum;
+        }
+
+        Self {
+            half_length,
+            taps: taps.into_iter().map(|tap| tap as f32).collect(),
+
+            up_history: History::new(2 * half_length),
+            down_even_history: History::new(2 * half_length),
+            down_odd_history: History::new(half_length + 1),
+        }
+    }
+
+    /// Latency of `upsample()` followed by `downsample()`, in samples at the lower rate
+    pub fn latency(&self) -> usize {
+        2 * self.half_length - 1
+    }
+
+    pub fn reset(&mut self) {
+        self.up_history.reset();
+        self.down_even_history.reset();
+        self.down_odd_history.reset();
+    }
+
+    /// `output` needs to be twice as long as `input`
+    pub fn upsample(&mut self, input: &[f32], output: &mut [f32]) {
+        assert_eq!(input.len() * 2, output.len());
+
+        for (&sample, output_pair) in input.iter().zip(output.chunks_exact_mut(2)) {
+            self.up_history.push(sample);
+            let history = self.up_history.samples();
+
+            output_pair[0] = 2.0 * dot(&self.taps, history);
+            output_pair[1] = history[self.half_length - 1];
+        }
+    }
+
+    /// `input` needs to be twice as long as `output`
+    pub fn downsample(&mut self, input: &[f32], output: &mut [f32]) {
+        assert_eq!(input.len(), output.len() * 2);
+
+        for (input_pair, output_sample) in input.chunks_exact(2).zip(output.iter_mut()) {
+            self.down_even_history.push(input_pair[0]);
+            self.down_odd_history.push(input_pair[1]);
+
+            *output_sample = dot(&self.taps, self.down_even_history.samples())
+                + 0.5 * self.down_odd_history.samples()[self.half_length];
+        }
+    }
+}
+
+/// Delay line that's stored twice so the newest `length` samples are always contiguous, newest first
+struct History {
+    samples: Vec<f32>,
+    position: usize,
+}
+
+impl History {
+    fn new(length: usize) -> Self {
+        Self {
+            samples: vec![0.0; 2 * length],
+            position: 0,
+        }
+    }
+
+    fn length(&self) -> usize {
+        self.samples.len() / 2
+    }
+
+    fn reset(&mut self) {
+        self.samples.fill(0.0);
+        self.position = 0;
+    }
+
+    fn push(&mut self, sample: f32) {
+        let length = self.length();
+
+        self.position = if self.position == 0 { length - 1 } else { self.position - 1 };
+        self.samples[self.position] = sample;
+        self.samples[self.position + length] = sample;
+    }
+
+    fn samples(&self) -> &[f32] {
+        &self.samples[self.position..self.position + self.length()]
+    }
+}
+
+fn dot(taps: &[f32], samples: &[f32]) -> f32 {
+    taps.iter()
+        .zip(samples.iter())
+        .map(|(tap, sample)| tap * sample)
+        .sum()
+}
@@ -0,0 +1,155 @@
+use std::f64::consts::PI;
+
+/// Low latency 2x up- and downsampler using a polyphase half-band filter made of two allpass chains
+///
+/// The coefficients follow the elliptic design from Laurent de Soras' HIIR library. `transition` is the width of
+/// the transition band relative to the higher sample rate, so the passband ends at `0.25 - transition`.
+pub struct HalfBandIir {
+    latency: f64,
+
+    up_paths: [Vec<Allpass>; 2],
+    down_paths: [Vec<Allpass>; 2],
+    // The odd path of the downsampler lags the even one by a sample
+    previous_odd: f32,
+}
+
+impl HalfBandIir {
+    pub fn new(coefficient_count: usize, transition: f64) -> Self {
+        assert!(coefficient_count > 0);
+        assert!(transition > 0.0 && transition < 0.5);
+
+        let coefficients = design(coefficient_count, transition);
+
+        // Group delay at DC, where both paths agree
+        let latency = 0.5 + coefficients.iter()
+            .map(|coefficient| (1.0 - coefficient) / (1.0 + coefficient))
+            .sum::<f64>();
+
+        let paths = [
+            coefficients.iter().step_by(2).map(|&coefficient| Allpass::new(coefficient as _)).collect::<Vec<_>>(),
+            coefficients.iter().skip(1).step_by(2).map(|&coefficient| Allpass::new(coefficient as _)).collect::<Vec<_>>(),
+        ];
+
+        Self {
+            latency,
+
+            up_paths: paths.clone(),
+            down_paths: paths,
+            previous_odd: 0.0,
+        }
+    }
+
+    /// Latency of `upsample()` followed by `downsample()` at low frequencies, in samples at the lower rate
+    pub fn latency(&self) -> f64 {
+        self.latency
+    }
+
+    pub fn reset(&mut self) {
+        for allpass in self.up_paths.iter_mut().chain(self.down_paths.iter_mut()).flatten() {
+            allpass.reset();
+        }
+
+        self.previous_odd = 0.0;
+    }
+
+    /// `output` needs to be twice as long as `input`
+    pub fn upsample(&mut self, input: &[f32], output: &mut [f32]) {
+        assert_eq!(input.len() * 2, output.len());
+
+        for (&sample, output_pair) in input.iter().zip(output.chunks_exact_mut(2)) {
+            output_pair[0] = process_path(&mut self.up_paths[0], sample);
+            output_pair[1] = process_path(&mut self.up_paths[1], sample);
+        }
+    }
+
+    /// `input` needs to be twice as long as `output`
+    pub fn downsample(&mut self, input: &[f32], output: &mut [f32]) {
+        assert_eq!(input.len(), output.len() * 2);
+
+        for (input_pair, output_sample) in input.chunks_exact(2).zip(output.iter_mut()) {
+            let even = process_path(&mut self.down_paths[0], input_pair[0]);
+            let odd = process_path(&mut self.down_paths[1], self.previous_odd);
+            self.previous_odd = input_pair[1];
+
+            *output_sample = 0.5 * (even + odd);
+        }
+    }
+}
+
+/// First order allpass section running at the lower rate
+#[derive(Clone)]
+struct Allpass {
+    coefficient: f32,
+    x1: f32,
+    y1: f32,
+}
+
+impl Allpass {
+    fn new(coefficient: f32) -> Self {
+        Self {
+            coefficient,
+            x1: 0.0,
+            y1: 0.0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.x1 = 0.0;
+        self.y1 = 0.0;
+    }
+
+    fn process(&mut self, x: f32) -> f32 {
+        let y = self.coefficient * (x - self.y1) + self.x1;
+
+        self.x1 = x;
+        self.y1 = y;
+
+        y
+    }
+}
+
+fn process_path(path: &mut [Allpass], sample: f32) -> f32 {
+    path.iter_mut().fold(sample, |sample, allpass| allpass.process(sample))
+}
+
+fn design(coefficient_count: usize, transition: f64) -> Vec<f64> {
+    let k = ((1.0 - transition * 2.0) * PI / 4.0).tan().powi(2);
+    let k_sqrt = (1.0 - k * k).powf(0.25);
+    let e = 0.5 * (1.0 - k_sqrt) / (1.0 + k_sqrt);
+    let e4 = e.powi(4);
+    let q = e * (1.0 + e4 * (2.0 + e4 * (15.0 + 150.0 * e4)));
+
+    let order = (coefficient_count * 2 + 1) as f64;
+
+    (0..coefficient_count)
+        .map(|index| {
+            let c = (index + 1) as f64;
+
+            let numerator = elliptic_sum(q, 0, 1.0, |i| i * (i + 1), |i| ((2 * i + 1) as f64 * c * PI / order).sin()) * q.powf(0.25);
+            let denominator = elliptic_sum(q, 1, -1.0, |i| i * i, |i| (2.0 * i as f64 * c * PI / order).cos()) + 0.5;
+
+            let w = numerator / denominator;
+            let w2 = w * w;
+            let x = ((1.0 - w2 * k) * (1.0 - w2 / k)).sqrt() / (1.0 + w2);
+
+            (1.0 - x) / (1.0 + x)
+        })
+        .collect()
+}
+
+// Alternating series of `q^exponent(i) * factor(i)`, summed until the powers of `q` vanish
+fn elliptic_sum(q: f64, first: usize, mut sign: f64, exponent: impl Fn(usize) -> usize, factor: impl Fn(usize) -> f64) -> f64 {
+    let mut sum = 0.0;
+
+    for i in first.. {
+        let power = q.powi(exponent(i) as i32);
+        if power < 1e-100 {
+            break;
+        }
+
+        sum += sign * power * factor(i);
+        sign = -sign;
+    }
+
+    sum
+}
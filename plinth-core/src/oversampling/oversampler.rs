@@ -0,0 +1,273 @@
+use crate::{buffers::buffer::Buffer, signals::{signal::{Signal, SignalMut}, signal_base::SignalBase, slice::SignalSliceMut}};
+
+use super::{half_band_fir::HalfBandFir, half_band_iir::HalfBandIir};
+
+pub const MAX_FACTOR: usize = 16;
+
+// Later stages only need to keep the images of the original band out, so they can use cheaper filters
+const FIR_HALF_LENGTHS: [usize; 4] = [20, 10, 6, 5];
+const IIR_DESIGNS: [(usize, f64); 4] = [(10, 0.03), (6, 0.1), (4, 0.17), (4, 0.2)];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum OversamplingFilter {
+    /// Polyphase allpass filters with low latency and a non-linear phase response
+    #[default]
+    Iir,
+    /// Linear phase FIR filters with higher latency
+    Fir,
+}
+
+enum HalfBand {
+    Fir(HalfBandFir),
+    Iir(HalfBandIir),
+}
+
+impl HalfBand {
+    fn latency(&self) -> f64 {
+        match self {
+            HalfBand::Fir(filter) => filter.latency() as f64,
+            HalfBand::Iir(filter) => filter.latency(),
+        }
+    }
+
+    fn reset(&mut self) {
+        match self {
+            HalfBand::Fir(filter) => filter.reset(),
+            HalfBand::Iir(filter) => filter.reset(),
+        }
+    }
+
+    fn upsample(&mut self, input: &[f32], output: &mut [f32]) {
+        match self {
+            HalfBand::Fir(filter) => filter.upsample(input, output),
+            HalfBand::Iir(filter) => filter.upsample(input, output),
+        }
+    }
+
+    fn downsample(&mut self, input: &[f32], output: &mut [f32]) {
+        match self {
+            HalfBand::Fir(filter) => filter.downsample(input, output),
+            HalfBand::Iir(filter) => filter.downsample(input, output),
+        }
+    }
+}
+
+/// Up- and downsamples signals by a power of two up to `MAX_FACTOR` using cascaded 2x stages
+///
+/// All buffers are allocated for `max_block_size` in `new()`. The round trip latency is padded to a whole number
+/// of samples at the original rate, which is what `latency()` returns. With `OversamplingFilter::Fir` it's exact,
+/// with `OversamplingFilter::Iir` it's exact for low frequencies.
+pub struct Oversampler {
+    factor: usize,
+    latency: usize,
+
+    // Per stage, per channel
+    stages: Vec<Vec<HalfBand>>,
+    // One buffer per rate, from the original rate up to the oversampled rate
+    buffers: Vec<Buffer>,
+
+    compensation: Buffer,
+    compensation_position: usize,
+}
+
+impl Oversampler {
+    pub fn new(channels: usize, factor: usize, max_block_size: usize, filter: OversamplingFilter) -> Self {
+        assert!(factor.is_power_of_two() && factor <= MAX_FACTOR, "Oversampling factor must be a power of two up to {MAX_FACTOR}, got {factor}");
+
+        let stage_count = factor.trailing_zeros() as usize;
+
+        let stages: Vec<Vec<_>> = (0..stage_count)
+            .map(|stage| (0..channels)
+                .map(|_| match filter {
+                    OversamplingFilter::Fir => HalfBand::Fir(HalfBandFir::new(FIR_HALF_LENGTHS[stage])),
+                    OversamplingFilter::Iir => {
+                        let (coefficient_count, transition) = IIR_DESIGNS[stage];
+                        HalfBand::Iir(HalfBandIir::new(coefficient_count, transition))
+                    }
+                })
+                .collect())
+            .collect();
+
+        let buffers = (0..=stage_count)
+            .map(|stage| Buffer::with_capacity(channels, max_block_size << stage))
+            .collect();
+
+        // Each stage runs at twice the rate of the previous one
+        let stage_latency: f64 = stages.iter()
+            .enumerate()
+            .map(|(stage, filters)| filters.first().map_or(0.0, HalfBand::latency) / (1 << stage) as f64)
+            .sum();
+
+        let latency = (stage_latency - 1e-9).ceil().max(0.0) as usize;
+        let compensation_length = ((latency as f64 - stage_latency) * factor as f64).round() as usize;
+
+        Self {
+            factor,
+            latency,
+
+            stages,
+            buffers,
+
+            compensation: Buffer::new(channels, compensation_length),
+            compensation_position: 0,
+        }
+    }
+
+    pub fn factor(&self) -> usize {
+        self.factor
+    }
+
+    /// Latency of `upsample()` followed by `downsample()`, in samples at the original rate
+    pub fn latency(&self) -> usize {
+        self.latency
+    }
+
+    pub fn reset(&mut self) {
+        for filter in self.stages.iter_mut().flatten() {
+            filter.reset();
+        }
+
+        self.compensation.fill(0.0);
+        self.compensation_position = 0;
+    }
+
+    /// Upsamples `input` and returns the oversampled signal, which is `factor` times as long
+    pub fn upsample(&mut self, input: &impl Signal) -> SignalSliceMut<'_, Buffer> {
+        let length = input.len();
+        assert!(length <= self.buffers[0].capacity());
+
+        self.buffers[0].resize(length);
+        self.buffers[0].copy_from_signal(input);
+
+        for (stage, filters) in self.stages.iter_mut().enumerate() {
+            let (lower, higher) = self.buffers.split_at_mut(stage + 1);
+            let input = &lower[stage];
+            let output = &mut higher[0];
+            output.resize(length << (stage + 1));
+
+            for (channel, filter) in filters.iter_mut().enumerate() {
+                filter.upsample(input.channel(channel), output.channel_mut(channel));
+            }
+        }
+
+        let oversampled = self.buffers.last_mut().unwrap();
+        delay(&mut self.compensation, &mut self.compensation_position, oversampled);
+
+        oversampled.slice_mut(..)
+    }
+
+    /// Downsamples the signal returned by the previous `upsample()` into `output`
+    pub fn downsample(&mut self, output: &mut impl SignalMut) {
+        let length = output.len();
+        assert_eq!(length * self.factor, self.buffers.last().unwrap().len());
+
+        for (stage, filters) in self.stages.iter_mut().enumerate().rev() {
+            let (lower, higher) = self.buffers.split_at_mut(stage + 1);
+            let input = &higher[0];
+            let output = &mut lower[stage];
+
+            for (channel, filter) in filters.iter_mut().enumerate() {
+                filter.downsample(input.channel(channel), output.channel_mut(channel));
+            }
+        }
+
+        output.copy_from_signal(&self.buffers[0]);
+    }
+
+    /// Processes `signal` in place at the oversampled rate
+    pub fn process(&mut self, signal: &mut impl SignalMut, callback: impl FnOnce(&mut SignalSliceMut<'_, Buffer>)) {
+        callback(&mut self.upsample(signal));
+        self.downsample(signal);
+    }
+}
+
+fn delay(delay_line: &mut Buffer, position: &mut usize, signal: &mut Buffer) {
+    let length = delay_line.len();
+    if length == 0 {
+        return;
+    }
+
+    for (delay_channel, channel) in delay_line.iter_channels_mut().zip(signal.iter_channels_mut()) {
+        let mut channel_position = *position;
+
+        for sample in channel.iter_mut() {
+            std::mem::swap(sample, &mut delay_channel[channel_position]);
+            channel_position = (channel_position + 1) % length;
+        }
+    }
+
+    *position = (*position + signal.len()) % length;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use crate::{buffers::buffer::Buffer, signals::{signal::{Signal, SignalMut}, signal_base::SignalBase}};
+
+    use super::{OversamplingFilter, Oversampler};
+
+    fn sine(length: usize, frequency: f32) -> Buffer {
+        let mut buffer = Buffer::new(1, length);
+        for (index, sample) in buffer.channel_mut(0).iter_mut().enumerate() {
+            *sample = (TAU * frequency * index as f32).sin();
+        }
+
+        buffer
+    }
+
+    // Magnitude at `frequency` relative to a full scale sine, using a Hann window
+    fn level(samples: &[f32], frequency: f32) -> f32 {
+        let (re, im) = samples.iter()
+            .enumerate()
+            .fold((0.0, 0.0), |(re, im), (index, sample)| {
+                let window = 0.5 - 0.5 * (TAU * index as f32 / samples.len() as f32).cos();
+                let phase = TAU * frequency * index as f32;
+                (re + sample * window * phase.cos(), im + sample * window * phase.sin())
+            });
+
+        f32::sqrt(re * re + im * im) * 4.0 / samples.len() as f32
+    }
+
+    #[test]
+    fn round_trip_is_delayed_by_latency() {
+        for filter in [OversamplingFilter::Fir, OversamplingFilter::Iir] {
+            for factor in [1, 2, 4, 8, 16] {
+                let input = sine(2048, 0.005);
+                let mut output = input.clone();
+
+                let mut oversampler = Oversampler::new(1, factor, 64, filter);
+                for start in (0..output.len()).step_by(64) {
+                    oversampler.process(&mut output.slice_mut(start..start + 64), |_| {});
+                }
+
+                let latency = oversampler.latency();
+                let tolerance = if filter == OversamplingFilter::Fir { 1e-3 } else { 2e-2 };
+
+                for (output, input) in output.channel(0)[1024..].iter().zip(input.channel(0)[1024 - latency..].iter()) {
+                    assert!((output - input).abs() < tolerance, "{filter:?} {factor}x: {output} != {input}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_images() {
+        for filter in [OversamplingFilter::Fir, OversamplingFilter::Iir] {
+            for factor in [2, 4, 16] {
+                // Upsampling a tone at 0.4 of the original rate leaves an image at 0.6
+                let input = sine(1024, 0.4);
+
+                let mut oversampler = Oversampler::new(1, factor, 1024, filter);
+                let oversampled = oversampler.upsample(&input);
+                let steady_state = &oversampled.channel(0)[512 * factor..];
+
+                let tone = level(steady_state, 0.4 / factor as f32);
+                let image = level(steady_state, 0.6 / factor as f32);
+
+                assert!((tone - 1.0).abs() < 0.01, "{filter:?} {factor}x: tone level {tone}");
+                assert!(image < 1e-4, "{filter:?} {factor}x: image level {image}");
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{buffers::buffer::Buffer, signals::signal::{Signal, SignalMut}};
+
+use super::resampler::{Resampler, ResamplerQuality};
+
+const BLOCK_SIZE: usize = 4096;
+
+/// Resamples a whole signal, for example a sample or impulse response loaded from disk
+///
+/// The result is `ceil(len * output_rate / input_rate)` samples long and isn't delayed by the filter.
+pub fn resample(signal: &impl Signal, input_rate: f64, output_rate: f64, quality: ResamplerQuality) -> Buffer {
+    let channels = signal.channels();
+    let mut resampler = Resampler::new(channels, input_rate, output_rate, quality);
+
+    let output_length = (signal.len() as u64 * output_rate.round() as u64).div_ceil(input_rate.round() as u64) as usize;
+    let mut output = Buffer::new(channels, output_length);
+
+    // Zeros to flush the samples still waiting on filter lookahead
+    let flush = Buffer::new(channels, resampler.latency());
+    let mut block_output = Buffer::new(channels, resampler.max_output_len(usize::max(BLOCK_SIZE, resampler.latency())));
+    let mut written = 0;
+
+    let mut write = |produced: usize, block_output: &Buffer| {
+        let count = usize::min(produced, output_length - written);
+
+        for (target, source) in output.iter_channels_mut().zip(block_output.iter_channels()) {
+            target[written..written + count].copy_from_slice(&source[..count]);
+        }
+
+        written += count;
+    };
+
+    for start in (0..signal.len()).step_by(BLOCK_SIZE) {
+        let end = usize::min(start + BLOCK_SIZE, signal.len());
+        let produced = resampler.process(&signal.slice(start..end), &mut block_output);
+        write(produced, &block_output);
+    }
+
+    let produced = resampler.process(&flush, &mut block_output);
+    write(produced, &block_output);
+
+    debug_assert_eq!(written, output_length);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use crate::{buffers::buffer::Buffer, signals::{signal::Signal, signal_base::SignalBase}};
+
+    use super::{resample, ResamplerQuality};
+
+    fn sine(frequency: f32, sample_rate: f32, length: usize) -> Vec<f32> {
+        (0..length).map(|index| (2.0 * PI * frequency * index as f32 / sample_rate).sin()).collect()
+    }
+
+    #[test]
+    fn preserves_sine() {
+        for (input_rate, output_rate) in [(44100.0, 48000.0), (48000.0, 44100.0), (44100.0, 96000.0), (96000.0, 44100.0)] {
+            for quality in [ResamplerQuality::Fast, ResamplerQuality::Balanced, ResamplerQuality::Best] {
+                let input = Buffer::from(vec![sine(1000.0, input_rate, 4410)]);
+                let output = resample(&input, input_rate as f64, output_rate as f64, quality);
+
+                let expected = sine(1000.0, output_rate, output.len());
+                assert_eq!(output.len(), (4410.0 * output_rate / input_rate).ceil() as usize);
+
+                // Skip the edges where the input starts and stops abruptly
+                let edge = output.len() / 8;
+                let error = output.channel(0)[edge..output.len() - edge].iter()
+                    .zip(&expected[edge..output.len() - edge])
+                    .fold(0.0f32, |max, (actual, expected)| max.max((actual - expected).abs()));
+
+                assert!(error < 2e-3, "{input_rate} -> {output_rate} at {quality:?}: error {error}");
+            }
+        }
+    }
+
+    #[test]
+    fn removes_content_above_nyquist() {
+        let input = Buffer::from(vec![sine(30000.0, 96000.0, 9600)]);
+        let output = resample(&input, 96000.0, 44100.0, ResamplerQuality::Balanced);
+
+        let edge = output.len() / 8;
+        let peak = output.channel(0)[edge..output.len() - edge].iter().fold(0.0f32, |max, sample| max.max(sample.abs()));
+        assert!(peak < 1e-3, "Aliased peak {peak}");
+    }
+
+    #[test]
+    fn deterministic() {
+        let input = Buffer::from(vec![sine(440.0, 44100.0, 1000), sine(880.0, 44100.0, 1000)]);
+
+        let first = resample(&input, 44100.0, 48000.0, ResamplerQuality::Balanced);
+        let second = resample(&input, 44100.0, 48000.0, ResamplerQuality::Balanced);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn same_rate_is_identity() {
+        let input = Buffer::from(vec![sine(440.0, 44100.0, 100)]);
+        let output = resample(&input, 44100.0, 44100.0, ResamplerQuality::Fast);
+
+        for (actual, expected) in output.channel(0).iter().zip(input.channel(0)) {
+            assert!((actual - expected).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use crate::{signals::signal::{Signal, SignalMut}, util::window::{kaiser, sinc}};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ResamplerQuality {
+    Fast,
+    #[default]
+    Balanced,
+    Best,
+}
+
+impl ResamplerQuality {
+    // Kernel half length in input samples, kernel phases, Kaiser beta and cutoff relative to Nyquist
+    fn parameters(&self) -> (usize, usize, f64, f64) {
+        match self {
+            ResamplerQuality::Fast => (8, 128, 6.0, 0.9),
+            ResamplerQuality::Balanced => (24, 512, 8.5, 0.94),
+            ResamplerQuality::Best => (64, 1024, 10.0, 0.97),
+        }
+    }
+}
+
+/// Streaming windowed sinc resampler
+///
+/// Sample rates are rounded to whole Hz and the output position is tracked as an exact fraction, so the output
+/// doesn't drift and only depends on the input. Output sample `n` lines up with input time `n * input_rate / output_rate`,
+/// but it's only produced once `latency()` more input samples have arrived.
+pub struct Resampler {
+    half_length: usize,
+    phases: usize,
+    // Kernel taps for each phase from 0 to `phases` inclusive, so the last one can be interpolated towards
+    kernel: Vec<f32>,
+
+    // Input samples per output sample as `step_numerator / step_denominator`
+    step_numerator: u64,
+    step_denominator: u64,
+
+    histories: Vec<History>,
+    input_count: u64,
+    next_index: u64,
+    next_phase: u64,
+}
+
+impl Resampler {
+    pub fn new(channels: usize, input_rate: f64, output_rate: f64, quality: ResamplerQuality) -> Self {
+        let input_rate = input_rate.round() as u64;
+        let output_rate = output_rate.round() as u64;
+        assert!(input_rate > 0 && output_rate > 0);
+
+        let divisor = gcd(input_rate, output_rate);
+        let (base_half_length, phases, beta, rolloff) = quality.parameters();
+
+        // Downsampling needs a lower cutoff, and a longer kernel to keep the same transition width
+        let ratio = output_rate as f64 / input_rate as f64;
+        let (cutoff, half_length) = if input_rate == output_rate {
+            (1.0, base_half_length)
+        } else if ratio < 1.0 {
+            (rolloff * ratio, (base_half_length as f64 / ratio).ceil() as usize)
+        } else {
+            (rolloff, base_half_length)
+        };
+
+        let length = 2 * half_length;
+        let mut kernel = vec![0.0; (phases + 1) * length];
+
+        for (phase, taps) in kernel.chunks_exact_mut(length).enumerate() {
+            let fraction = phase as f64 / phases as f64;
+
+            let values: Vec<f64> = (0..length)
+                .map(|tap| {
+                    let x = fraction + half_length as f64 - 1.0 - tap as f64;
+                    let window_position = x / half_length as f64;
+                    let window = if window_position.abs() >= 1.0 {
+                        0.0
+                    } else {
+                        kaiser(window_position, beta)
+                    };
+
+                    cutoff * sinc(cutoff * x) * window
+                })
+                .collect();
+
+            // Unity gain at DC for every phase
+            let sum: f64 = values.iter().sum();
+            for (tap, value) in taps.iter_mut().zip(values) {
+                *tap = (value / sum) as f32;
+            }
+        }
+
+        Self {
+            half_length,
+            phases,
+            kernel,
+
+            step_numerator: input_rate / divisor,
+            step_denominator: output_rate / divisor,
+
+            histories: (0..channels).map(|_| History::new(length)).collect(),
+            input_count: 0,
+            next_index: 0,
+            next_phase: 0,
+        }
+    }
+
+    /// Input samples needed past an output sample's position before it can be produced
+    pub fn latency(&self) -> usize {
+        self.half_length
+    }
+
+    /// Most output samples `process()` can produce from `input_length` input samples
+    pub fn max_output_len(&self, input_length: usize) -> usize {
+        (input_length as u64 * self.step_denominator).div_ceil(self.step_numerator) as usize + 1
+    }
+
+    pub fn reset(&mut self) {
+        for history in self.histories.iter_mut() {
+            history.reset();
+        }
+
+        self.input_count = 0;
+        self.next_index = 0;
+        self.next_phase = 0;
+    }
+
+    /// Consumes all of `input` and returns the number of samples written to the start of `output`,
+    /// which needs to be at least `max_output_len()` long
+    pub fn process(&mut self, input: &impl Signal, output: &mut impl SignalMut) -> usize {
+        assert_eq!(input.channels(), self.histories.len());
+        assert_eq!(input.channels(), output.channels());
+        assert!(output.len() >= self.max_output_len(input.len()), "Resampler output needs to hold at least {} samples", self.max_output_len(input.len()));
+
+        let length = 2 * self.half_length;
+        let mut produced = 0;
+        let mut state = (self.input_count, self.next_index, self.next_phase);
+
+        for channel in 0..input.channels() {
+            let history = &mut self.histories[channel];
+            let output_channel = output.channel_mut(channel);
+            let (mut input_count, mut next_index, mut next_phase) = (self.input_count, self.next_index, self.next_phase);
+            produced = 0;
+
+            for &sample in input.channel(channel) {
+                history.push(sample);
+                input_count += 1;
+
+                // The newest sample has index `input_count - 1`
+                while next_index + (self.half_length as u64) < input_count {
+                    let position = next_phase as f64 / self.step_denominator as f64 * self.phases as f64;
+                    let phase = position as usize;
+                    let phase_fraction = (position - phase as f64) as f32;
+
+                    let taps = &self.kernel[phase * length..(phase + 2) * length];
+                    let (taps, next_taps) = taps.split_at(length);
+
+                    let mut value = 0.0;
+                    let mut next_value = 0.0;
+                    for ((tap, next_tap), sample) in taps.iter().zip(next_taps).zip(history.samples()) {
+                        value += tap * sample;
+                        next_value += next_tap * sample;
+                    }
+
+                    output_channel[produced] = value + phase_fraction * (next_value - value);
+                    produced += 1;
+
+                    next_phase += self.step_numerator;
+                    next_index += next_phase / self.step_denominator;
+                    next_phase %= self.step_denominator;
+                }
+            }
+
+            state = (input_count, next_index, next_phase);
+        }
+
+        (self.input_count, self.next_index, self.next_phase) = state;
+
+        produced
+    }
+}
+
+/// The newest `length` input samples, oldest first
+struct History {
+    samples: Vec<f32>,
+    position: usize,
+}
+
+impl History {
+    fn new(length: usize) -> Self {
+        Self {
+            samples: vec![0.0; 2 * length],
+            position: 0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.samples.fill(0.0);
+        self.position = 0;
+    }
+
+    fn push(&mut self, sample: f32) {
+        let length = self.samples.len() / 2;
+
+        self.samples[self.position] = sample;
+        self.samples[self.position + length] = sample;
+        self.position = (self.position + 1) % length;
+    }
+
+    fn samples(&self) -> &[f32] {
+        let length = self.samples.len() / 2;
+        &self.samples[self.position..self.position + length]
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{buffers::buffer::Buffer, signals::{signal::{Signal, SignalMut}, signal_base::SignalBase}};
+
+    use super::{Resampler, ResamplerQuality};
+
+    fn stream(input: &Buffer, block_size: usize) -> Vec<f32> {
+        let mut resampler = Resampler::new(1, 44100.0, 48000.0, ResamplerQuality::Fast);
+        let mut output = Buffer::new(1, resampler.max_output_len(block_size));
+        let mut result = Vec::new();
+
+        for start in (0..input.len()).step_by(block_size) {
+            let end = usize::min(start + block_size, input.len());
+            let produced = resampler.process(&input.slice(start..end), &mut output.slice_mut(..resampler.max_output_len(end - start)));
+            result.extend_from_slice(&output.channel(0)[..produced]);
+        }
+
+        result
+    }
+
+    #[test]
+    fn block_size_independent() {
+        let input = Buffer::from(vec![(0..1000).map(|index| ((index * 7919) % 100) as f32 / 50.0 - 1.0).collect()]);
+
+        let whole = stream(&input, 1000);
+        assert_eq!(stream(&input, 1), whole);
+        assert_eq!(stream(&input, 37), whole);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod channel_layout;
+pub mod channel_map;
+pub mod gain_matrix;
+pub mod mid_side;
@@ -0,0 +1,96 @@
+use std::f32::consts::FRAC_1_SQRT_2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Speaker {
+    Mono,
+    Left,
+    Right,
+    Center,
+    Lfe,
+    SurroundLeft,
+    SurroundRight,
+    RearLeft,
+    RearRight,
+}
+
+/// Speaker arrangements in the usual interleaved file and host order
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChannelLayout {
+    Mono,
+    #[default]
+    Stereo,
+    Quad,
+    Surround5_1,
+    Surround7_1,
+}
+
+impl ChannelLayout {
+    pub fn speakers(&self) -> &'static [Speaker] {
+        match self {
+            ChannelLayout::Mono => &[Speaker::Mono],
+            ChannelLayout::Stereo => &[Speaker::Left, Speaker::Right],
+            ChannelLayout::Quad => &[Speaker::Left, Speaker::Right, Speaker::SurroundLeft, Speaker::SurroundRight],
+            ChannelLayout::Surround5_1 => &[Speaker::Left, Speaker::Right, Speaker::Center, Speaker::Lfe, Speaker::SurroundLeft, Speaker::SurroundRight],
+            ChannelLayout::Surround7_1 => &[
+                Speaker::Left, Speaker::Right, Speaker::Center, Speaker::Lfe,
+                Speaker::SurroundLeft, Speaker::SurroundRight, Speaker::RearLeft, Speaker::RearRight,
+            ],
+        }
+    }
+
+    pub fn channels(&self) -> usize {
+        self.speakers().len()
+    }
+
+    pub fn index_of(&self, speaker: Speaker) -> Option<usize> {
+        self.speakers().iter().position(|&other| other == speaker)
+    }
+
+    /// Calls `function` with the channel index and gain of every channel `speaker` ends up in when played on this layout
+    ///
+    /// Downmixing uses the ITU-R BS.775 coefficients and drops the LFE channel.
+    /// Upmixing is passive: mono goes to the center or both front speakers and missing channels stay silent.
+    pub(crate) fn fold(&self, speaker: Speaker, gain: f32, function: &mut impl FnMut(usize, f32)) {
+        if let Some(index) = self.index_of(speaker) {
+            function(index, gain);
+            return;
+        }
+
+        match speaker {
+            Speaker::Mono => {
+                if self.index_of(Speaker::Center).is_some() {
+                    self.fold(Speaker::Center, gain, function);
+                } else {
+                    self.fold(Speaker::Left, gain, function);
+                    self.fold(Speaker::Right, gain, function);
+                }
+            }
+            Speaker::Center => {
+                if self.index_of(Speaker::Mono).is_some() {
+                    self.fold(Speaker::Mono, gain, function);
+                } else {
+                    self.fold(Speaker::Left, gain * FRAC_1_SQRT_2, function);
+                    self.fold(Speaker::Right, gain * FRAC_1_SQRT_2, function);
+                }
+            }
+            Speaker::Left | Speaker::Right => self.fold(Speaker::Mono, gain * 0.5, function),
+            Speaker::Lfe => {}
+            Speaker::SurroundLeft => self.fold(Speaker::Left, gain * FRAC_1_SQRT_2, function),
+            Speaker::SurroundRight => self.fold(Speaker::Right, gain * FRAC_1_SQRT_2, function),
+            Speaker::RearLeft => {
+                if self.index_of(Speaker::SurroundLeft).is_some() {
+                    self.fold(Speaker::SurroundLeft, gain, function);
+                } else {
+                    self.fold(Speaker::Left, gain * FRAC_1_SQRT_2, function);
+                }
+            }
+            Speaker::RearRight => {
+                if self.index_of(Speaker::SurroundRight).is_some() {
+                    self.fold(Speaker::SurroundRight, gain, function);
+                } else {
+                    self.fold(Speaker::Right, gain * FRAC_1_SQRT_2, function);
+                }
+            }
+        }
+    }
+}
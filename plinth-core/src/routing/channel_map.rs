@@ -0,0 +1,83 @@
+use crate::signals::{sample::Sample, signal::{Signal, SignalMut}};
+
+/// Picks the source channel of every output channel, or silence
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChannelMap<T: Sample = f32> {
+    sources: Vec<Option<usize>>,
+    // One frame of input for in place remapping
+    frame: Vec<T>,
+}
+
+impl<T: Sample> ChannelMap<T> {
+    pub fn new(sources: impl IntoIterator<Item = Option<usize>>) -> Self {
+        let sources: Vec<_> = sources.into_iter().collect();
+        let input_channels = sources.iter().flatten().max().map_or(0, |max| max + 1);
+
+        Self {
+            sources,
+            frame: vec![T::EQUILIBRIUM; input_channels],
+        }
+    }
+
+    pub fn identity(channels: usize) -> Self {
+        Self::new((0..channels).map(Some))
+    }
+
+    pub fn outputs(&self) -> usize {
+        self.sources.len()
+    }
+
+    pub fn source(&self, output: usize) -> Option<usize> {
+        self.sources[output]
+    }
+
+    pub fn remap(&self, input: &impl Signal<T>, output: &mut impl SignalMut<T>) {
+        assert!(output.channels() >= self.sources.len());
+        assert!(input.channels() >= self.frame.len());
+        assert_eq!(input.len(), output.len());
+
+        for (output_channel, source) in output.iter_channels_mut().zip(&self.sources) {
+            match source {
+                Some(source) => output_channel.copy_from_slice(input.channel(*source)),
+                None => output_channel.fill(T::EQUILIBRIUM),
+            }
+        }
+    }
+
+    /// Remaps the first `outputs()` channels of a signal, reordering and duplicating channels as needed
+    pub fn remap_in_place(&mut self, signal: &mut impl SignalMut<T>) {
+        assert!(signal.channels() >= usize::max(self.sources.len(), self.frame.len()));
+
+        for index in 0..signal.len() {
+            for (channel, sample) in self.frame.iter_mut().enumerate() {
+                *sample = signal.channel(channel)[index];
+            }
+
+            for (output, source) in self.sources.iter().enumerate() {
+                signal.channel_mut(output)[index] = source.map_or(T::EQUILIBRIUM, |source| self.frame[source]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{buffers::buffer::Buffer, signals::signal::Signal};
+
+    use super::ChannelMap;
+
+    #[test]
+    fn swap_and_duplicate() {
+        let mut map = ChannelMap::new([Some(1), Some(0), Some(0), None]);
+        let mut signal = Buffer::from(vec![vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0], vec![7.0, 8.0]]);
+
+        let mut remapped = Buffer::new(4, 2);
+        map.remap(&signal, &mut remapped);
+        map.remap_in_place(&mut signal);
+
+        assert_eq!(signal, remapped);
+        assert_eq!(signal.channel(0), [3.0, 4.0]);
+        assert_eq!(signal.channel(2), [1.0, 2.0]);
+        assert_eq!(signal.channel(3), [0.0, 0.0]);
+    }
+}
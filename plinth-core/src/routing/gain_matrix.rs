@@ -0,0 +1,165 @@
+use crate::{signals::{sample::FloatSample, signal::{Signal, SignalMut}}, simd};
+
+use super::channel_layout::ChannelLayout;
+
+/// Mixes input channels into output channels, each output being a weighted sum of the inputs
+#[derive(Clone, Debug, PartialEq)]
+pub struct GainMatrix<T: FloatSample = f32> {
+    inputs: usize,
+    outputs: usize,
+    // Row per output channel
+    gains: Vec<T>,
+    // One frame of input for in place processing
+    frame: Vec<T>,
+}
+
+impl<T: FloatSample> GainMatrix<T> {
+    /// Starts out silent
+    pub fn new(inputs: usize, outputs: usize) -> Self {
+        assert!(inputs > 0);
+
+        Self {
+            inputs,
+            outputs,
+            gains: vec![T::ZERO; inputs * outputs],
+            frame: vec![T::ZERO; inputs],
+        }
+    }
+
+    pub fn identity(channels: usize) -> Self {
+        let mut matrix = Self::new(channels, channels);
+        for channel in 0..channels {
+            matrix.set_gain(channel, channel, T::ONE);
+        }
+
+        matrix
+    }
+
+    /// Standard up- or downmix between two speaker layouts
+    pub fn for_layouts(from: ChannelLayout, to: ChannelLayout) -> Self {
+        let mut matrix = Self::new(from.channels(), to.channels());
+
+        for (input, &speaker) in from.speakers().iter().enumerate() {
+            to.fold(speaker, 1.0, &mut |output, gain| {
+                matrix.gains[output * from.channels() + input] += T::from_f64(gain as f64);
+            });
+        }
+
+        matrix
+    }
+
+    pub fn with_gain(mut self, input: usize, output: usize, gain: T) -> Self {
+        self.set_gain(input, output, gain);
+        self
+    }
+
+    pub fn inputs(&self) -> usize {
+        self.inputs
+    }
+
+    pub fn outputs(&self) -> usize {
+        self.outputs
+    }
+
+    pub fn gain(&self, input: usize, output: usize) -> T {
+        assert!(input < self.inputs && output < self.outputs);
+        self.gains[output * self.inputs + input]
+    }
+
+    pub fn set_gain(&mut self, input: usize, output: usize, gain: T) {
+        assert!(input < self.inputs && output < self.outputs);
+        self.gains[output * self.inputs + input] = gain;
+    }
+
+    pub fn process(&self, input: &impl Signal<T>, output: &mut impl SignalMut<T>) {
+        assert_eq!(input.channels(), self.inputs);
+        assert_eq!(output.channels(), self.outputs);
+        assert_eq!(input.len(), output.len());
+
+        for (output_channel, gains) in output.iter_channels_mut().zip(self.gains.chunks_exact(self.inputs)) {
+            output_channel.fill(T::ZERO);
+
+            for (input_channel, &gain) in input.iter_channels().zip(gains) {
+                if gain != T::ZERO {
+                    simd::mix(output_channel, T::ONE, input_channel, gain);
+                }
+            }
+        }
+    }
+
+    /// Reads the first `inputs()` channels and replaces the first `outputs()` channels with the mix,
+    /// any other channels are left alone
+    pub fn process_in_place(&mut self, signal: &mut impl SignalMut<T>) {
+        assert!(signal.channels() >= usize::max(self.inputs, self.outputs));
+
+        for index in 0..signal.len() {
+            for (input, sample) in self.frame.iter_mut().enumerate() {
+                *sample = signal.channel(input)[index];
+            }
+
+            for (output, gains) in self.gains.chunks_exact(self.inputs).enumerate() {
+                signal.channel_mut(output)[index] = gains.iter().zip(&self.frame).map(|(&gain, &sample)| gain * sample).sum();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_1_SQRT_2;
+
+    use crate::{buffers::buffer::Buffer, routing::channel_layout::ChannelLayout, signals::signal::Signal};
+
+    use super::GainMatrix;
+
+    #[test]
+    fn downmix_5_1_to_stereo() {
+        let matrix: GainMatrix = GainMatrix::for_layouts(ChannelLayout::Surround5_1, ChannelLayout::Stereo);
+
+        assert_eq!(matrix.gain(0, 0), 1.0);
+        assert_eq!(matrix.gain(1, 0), 0.0);
+        assert_eq!(matrix.gain(2, 0), FRAC_1_SQRT_2);
+        assert_eq!(matrix.gain(3, 0), 0.0);
+        assert_eq!(matrix.gain(4, 0), FRAC_1_SQRT_2);
+        assert_eq!(matrix.gain(5, 1), FRAC_1_SQRT_2);
+    }
+
+    #[test]
+    fn mono_stereo_round_trip() {
+        let upmix = GainMatrix::for_layouts(ChannelLayout::Mono, ChannelLayout::Stereo);
+        let mut downmix = GainMatrix::for_layouts(ChannelLayout::Stereo, ChannelLayout::Mono);
+
+        let mono = Buffer::from(vec![vec![0.5, -0.25]]);
+        let mut stereo = Buffer::new(2, 2);
+        upmix.process(&mono, &mut stereo);
+        assert_eq!(stereo.channel(1), [0.5, -0.25]);
+
+        downmix.process_in_place(&mut stereo);
+        assert_eq!(stereo.channel(0), mono.channel(0));
+    }
+
+    #[test]
+    fn upmix_in_place() {
+        let mut matrix = GainMatrix::for_layouts(ChannelLayout::Stereo, ChannelLayout::Surround5_1);
+        let mut signal = Buffer::from(vec![vec![1.0], vec![2.0], vec![3.0], vec![4.0], vec![5.0], vec![6.0]]);
+
+        matrix.process_in_place(&mut signal);
+        assert_eq!(signal, Buffer::from(vec![vec![1.0], vec![2.0], vec![0.0], vec![0.0], vec![0.0], vec![0.0]]));
+    }
+
+    #[test]
+    fn double_precision() {
+        let matrix = GainMatrix::<f64>::for_layouts(ChannelLayout::Mono, ChannelLayout::Stereo);
+
+        let mono = Buffer::from(vec![vec![0.5f64, -0.25]]);
+        let mut stereo = Buffer::<f64>::silent(2, 2);
+        matrix.process(&mono, &mut stereo);
+        assert_eq!(stereo.channel(0), [0.5, -0.25]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn no_inputs() {
+        GainMatrix::<f32>::new(0, 2);
+    }
+}
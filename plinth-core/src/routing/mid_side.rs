@@ -0,0 +1,41 @@
+use crate::{signals::{sample::FloatSample, signal::SignalMut}, simd};
+
+/// Converts the first two channels from left/right to mid/side in place
+pub fn encode_mid_side<T: FloatSample>(signal: &mut impl SignalMut<T>) {
+    sum_difference(signal, T::from_f64(0.5));
+}
+
+/// Converts the first two channels from mid/side back to left/right in place
+pub fn decode_mid_side<T: FloatSample>(signal: &mut impl SignalMut<T>) {
+    sum_difference(signal, T::ONE);
+}
+
+fn sum_difference<T: FloatSample>(signal: &mut impl SignalMut<T>, gain: T) {
+    assert!(signal.channels() >= 2);
+
+    let mut channels = signal.iter_channels_mut();
+    let left = channels.next().unwrap();
+    let right = channels.next().unwrap();
+
+    simd::sum_difference(left, right, gain);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{buffers::buffer::Buffer, signals::signal::Signal};
+
+    use super::{decode_mid_side, encode_mid_side};
+
+    #[test]
+    fn round_trip() {
+        let original = Buffer::from(vec![vec![1.0, 0.5, -0.25], vec![1.0, -0.5, 0.75]]);
+        let mut signal = original.clone();
+
+        encode_mid_side(&mut signal);
+        assert_eq!(signal.channel(0), [1.0, 0.0, 0.25]);
+        assert_eq!(signal.channel(1), [0.0, 0.5, -0.5]);
+
+        decode_mid_side(&mut signal);
+        assert_eq!(signal, original);
+    }
+}
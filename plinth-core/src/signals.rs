@@ -2,8 +2,11 @@ pub mod channels;
 pub mod frame;
 pub mod frame_iterator;
 pub mod frames_iterator;
+pub mod interleaved;
 pub mod ptr_signal;
+pub mod sample;
 pub mod signal;
 pub mod signal_base;
 pub mod signal_frame;
 pub mod slice;
+pub mod slices;
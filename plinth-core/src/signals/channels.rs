@@ -1,30 +1,35 @@
-use super::signal::{Signal, SignalMut};
+use std::marker::PhantomData;
 
-pub struct ChannelsIterator<'signal, S: Signal + ?Sized> {
+use super::{sample::Sample, signal::{Signal, SignalMut}};
+
+pub struct ChannelsIterator<'signal, S: ?Sized, T: Sample = f32> {
     signal: &'signal S,
     channel_index: usize,
+    _phantom_sample: PhantomData<T>,
 }
 
-impl<'signal, S: Signal> ChannelsIterator<'signal, S> {
-    pub fn new(signal: &'signal S) -> ChannelsIterator<'signal, S> {
+impl<'signal, S: Signal<T>, T: Sample> ChannelsIterator<'signal, S, T> {
+    pub fn new(signal: &'signal S) -> ChannelsIterator<'signal, S, T> {
         ChannelsIterator {
             signal,
             channel_index: 0,
+            _phantom_sample: PhantomData,
         }
     }
 }
 
-impl<S: Signal> Clone for ChannelsIterator<'_, S> {
+impl<S: Signal<T>, T: Sample> Clone for ChannelsIterator<'_, S, T> {
     fn clone(&self) -> Self {
         Self {
             signal: self.signal,
             channel_index: 0,
+            _phantom_sample: PhantomData,
         }
     }
 }
 
-impl<'signal, S: Signal + ?Sized> Iterator for ChannelsIterator<'signal, S> {
-    type Item = &'signal [f32];
+impl<'signal, S: Signal<T> + ?Sized, T: Sample> Iterator for ChannelsIterator<'signal, S, T> {
+    type Item = &'signal [T];
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.channel_index < self.signal.channels() {
@@ -56,22 +61,24 @@ impl<'signal, S: Signal + ?Sized> Iterator for ChannelsIterator<'signal, S> {
     }
 }
 
-pub struct ChannelsIteratorMut<'signal, S: SignalMut + ?Sized> {
+pub struct ChannelsIteratorMut<'signal, S: ?Sized, T: Sample = f32> {
     signal: &'signal mut S,
     channel_index: usize,
+    _phantom_sample: PhantomData<T>,
 }
 
-impl<'signal, S: SignalMut> ChannelsIteratorMut<'signal, S> {
-    pub fn new(signal: &'signal mut S) -> ChannelsIteratorMut<'signal, S> {
+impl<'signal, S: SignalMut<T>, T: Sample> ChannelsIteratorMut<'signal, S, T> {
+    pub fn new(signal: &'signal mut S) -> ChannelsIteratorMut<'signal, S, T> {
         ChannelsIteratorMut {
             signal,
             channel_index: 0,
+            _phantom_sample: PhantomData,
         }
     }
 }
 
-impl<'signal, S: SignalMut + ?Sized> Iterator for ChannelsIteratorMut<'signal, S> {
-    type Item = &'signal mut [f32];
+impl<'signal, S: SignalMut<T> + ?Sized, T: Sample> Iterator for ChannelsIteratorMut<'signal, S, T> {
+    type Item = &'signal mut [T];
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.channel_index < self.signal.channels() {
@@ -1,13 +1,15 @@
 use std::iter::zip;
 
-pub trait Frame<'frame> {
-    type Iterator: Iterator<Item = &'frame f32>;
+use super::sample::{FloatSample, Sample};
+
+pub trait Frame<'frame, T: Sample = f32> {
+    type Iterator: Iterator<Item = &'frame T>;
 
     fn channels(&self) -> usize;
-    fn channel(&self, index: usize) -> &f32;
+    fn channel(&self, index: usize) -> &T;
     fn iter(&'frame self) -> Self::Iterator;
 
-    fn max_amplitude(&'frame self) -> f32 {
+    fn max_amplitude(&'frame self) -> T where T: FloatSample {
         self.iter()
             .map(|sample| sample.abs())
             .max_by(|a, b| a.partial_cmp(b).unwrap())
@@ -15,15 +17,15 @@ pub trait Frame<'frame> {
     }
 }
 
-pub trait FrameMut<'frame> : Frame<'frame> {
-    type IteratorMut: Iterator<Item = &'frame mut f32>;
+pub trait FrameMut<'frame, T: Sample = f32> : Frame<'frame, T> {
+    type IteratorMut: Iterator<Item = &'frame mut T>;
 
-    fn channel_mut(&mut self, index: usize) -> &mut f32;
+    fn channel_mut(&mut self, index: usize) -> &mut T;
     fn iter_mut(&'frame mut self) -> Self::IteratorMut;
 
-    fn copy_from<'source, I>(&'frame mut self, source: &'source impl Frame<'source, Iterator = I>)
+    fn copy_from<'source, I>(&'frame mut self, source: &'source impl Frame<'source, T, Iterator = I>)
     where
-        I: Iterator<Item = &'source f32>,
+        I: Iterator<Item = &'source T>,
         'source: 'frame,
     {
         for (sample_self, sample_source) in zip(self.iter_mut(), source.iter()) {
@@ -1,23 +1,27 @@
-use super::signal::{Signal, SignalMut};
+use std::marker::PhantomData;
 
-pub struct FrameIterator<'signal, S: Signal> {
+use super::{sample::Sample, signal::{Signal, SignalMut}};
+
+pub struct FrameIterator<'signal, S: ?Sized, T: Sample = f32> {
     signal: &'signal S,
     frame_index: usize,
     channel_index: usize,
+    _phantom_sample: PhantomData<T>,
 }
 
-impl<S: Signal> FrameIterator<'_, S> {
-    pub fn new(signal: &S, frame_index: usize) -> FrameIterator<'_, S> {
+impl<S: Signal<T> + ?Sized, T: Sample> FrameIterator<'_, S, T> {
+    pub fn new(signal: &S, frame_index: usize) -> FrameIterator<'_, S, T> {
         FrameIterator {
             signal,
             frame_index,
             channel_index: 0,
+            _phantom_sample: PhantomData,
         }
     }
 }
 
-impl<'signal, S: Signal> Iterator for FrameIterator<'signal, S> {
-    type Item = &'signal f32;
+impl<'signal, S: Signal<T> + ?Sized, T: Sample> Iterator for FrameIterator<'signal, S, T> {
+    type Item = &'signal T;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.channel_index >= self.signal.channels() {
@@ -31,31 +35,33 @@ impl<'signal, S: Signal> Iterator for FrameIterator<'signal, S> {
     }
 }
 
-pub struct FrameIteratorMut<'signal, S: SignalMut> {
+pub struct FrameIteratorMut<'signal, S: ?Sized, T: Sample = f32> {
     signal: &'signal mut S,
     frame_index: usize,
     channel_index: usize,
+    _phantom_sample: PhantomData<T>,
 }
 
-impl<S: SignalMut> FrameIteratorMut<'_, S> {
-    pub fn new(signal: &mut S, frame_index: usize) -> FrameIteratorMut<'_, S> {
+impl<S: SignalMut<T> + ?Sized, T: Sample> FrameIteratorMut<'_, S, T> {
+    pub fn new(signal: &mut S, frame_index: usize) -> FrameIteratorMut<'_, S, T> {
         FrameIteratorMut {
             signal,
             frame_index,
             channel_index: 0,
+            _phantom_sample: PhantomData,
         }
     }
 }
 
-impl<'signal, S: SignalMut> Iterator for FrameIteratorMut<'signal, S> {
-    type Item = &'signal mut f32;
+impl<'signal, S: SignalMut<T> + ?Sized, T: Sample> Iterator for FrameIteratorMut<'signal, S, T> {
+    type Item = &'signal mut T;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.channel_index >= self.signal.channels() {
             return None;
         }
 
-        let ptr = self.signal.channel_ptr_mut(self.channel_index) as *mut f32;
+        let ptr = self.signal.channel_ptr_mut(self.channel_index) as *mut T;
         let ptr = unsafe { ptr.add(self.frame_index) };
         let result = unsafe { &mut *ptr };
 
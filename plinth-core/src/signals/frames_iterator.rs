@@ -1,16 +1,17 @@
-use std::mem::transmute;
+use std::{marker::PhantomData, mem::transmute};
 
-use super::{signal::{Signal, SignalMut}, signal_frame::{SignalFrame, SignalFrameMut}};
+use super::{sample::Sample, signal::{Signal, SignalMut}, signal_frame::{SignalFrame, SignalFrameMut}};
 
-pub struct FramesIterator<'signal, S: Signal + ?Sized> {
+pub struct FramesIterator<'signal, S: ?Sized, T: Sample = f32> {
     signal: &'signal S,
     frame_index_front: usize,
     frame_index_back: usize,
     finished: bool,
+    _phantom_sample: PhantomData<T>,
 }
 
-impl<S: Signal> FramesIterator<'_, S> {
-    pub fn new(signal: &S) -> FramesIterator<'_, S> {
+impl<S: Signal<T>, T: Sample> FramesIterator<'_, S, T> {
+    pub fn new(signal: &S) -> FramesIterator<'_, S, T> {
         let frame_index_back = if signal.is_empty() { 0 } else { signal.len() - 1 };
 
         FramesIterator {
@@ -18,11 +19,12 @@ impl<S: Signal> FramesIterator<'_, S> {
             frame_index_front: 0,
             frame_index_back,
             finished: false,
+            _phantom_sample: PhantomData,
         }
     }
 }
 
-impl<'signal, S: Signal> Iterator for FramesIterator<'signal, S> {
+impl<'signal, S: Signal<T>, T: Sample> Iterator for FramesIterator<'signal, S, T> {
     type Item = SignalFrame<'signal, S>;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -41,7 +43,7 @@ impl<'signal, S: Signal> Iterator for FramesIterator<'signal, S> {
     }
 }
 
-impl<'signal, S: Signal> DoubleEndedIterator for FramesIterator<'signal, S> {
+impl<'signal, S: Signal<T>, T: Sample> DoubleEndedIterator for FramesIterator<'signal, S, T> {
     fn next_back(&mut self) -> Option<Self::Item> {
         if self.finished {
             return None;
@@ -58,15 +60,16 @@ impl<'signal, S: Signal> DoubleEndedIterator for FramesIterator<'signal, S> {
     }
 }
 
-pub struct FramesIteratorMut<'signal, S: SignalMut + ?Sized> {
+pub struct FramesIteratorMut<'signal, S: ?Sized, T: Sample = f32> {
     signal: &'signal mut S,
     frame_index_front: usize,
     frame_index_back: usize,
     finished: bool,
+    _phantom_sample: PhantomData<T>,
 }
 
-impl<S: SignalMut> FramesIteratorMut<'_, S> {
-    pub fn new(signal: &mut S) -> FramesIteratorMut<'_, S> {
+impl<S: SignalMut<T>, T: Sample> FramesIteratorMut<'_, S, T> {
+    pub fn new(signal: &mut S) -> FramesIteratorMut<'_, S, T> {
         let frame_index_back = if signal.is_empty() { 0 } else { signal.len() - 1 };
 
         FramesIteratorMut {
@@ -74,11 +77,12 @@ impl<S: SignalMut> FramesIteratorMut<'_, S> {
             frame_index_front: 0,
             frame_index_back,
             finished: false,
+            _phantom_sample: PhantomData,
         }
     }
 }
 
-impl<'signal, S: SignalMut> Iterator for FramesIteratorMut<'signal, S> {
+impl<'signal, S: SignalMut<T>, T: Sample> Iterator for FramesIteratorMut<'signal, S, T> {
     type Item = SignalFrameMut<'signal, S>;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -99,7 +103,7 @@ impl<'signal, S: SignalMut> Iterator for FramesIteratorMut<'signal, S> {
     }
 }
 
-impl<'signal, S: SignalMut> DoubleEndedIterator for FramesIteratorMut<'signal, S> {
+impl<'signal, S: SignalMut<T>, T: Sample> DoubleEndedIterator for FramesIteratorMut<'signal, S, T> {
     fn next_back(&mut self) -> Option<Self::Item> {
         if self.finished {
             return None;
@@ -0,0 +1,202 @@
+use std::slice::{ChunksExact, ChunksExactMut};
+
+use super::{sample::Sample, signal::{Signal, SignalMut}};
+
+/// Frame oriented view into interleaved samples, as used by many audio libraries and file formats
+pub struct InterleavedSignal<'samples, T: Sample = f32> {
+    samples: &'samples [T],
+    channels: usize,
+}
+
+impl<'samples, T: Sample> InterleavedSignal<'samples, T> {
+    pub fn new(samples: &'samples [T], channels: usize) -> Self {
+        assert!(channels > 0);
+        assert_eq!(samples.len() % channels, 0, "Interleaved sample count {} isn't divisible by channel count {channels}", samples.len());
+
+        Self {
+            samples,
+            channels,
+        }
+    }
+
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Length in frames
+    pub fn len(&self) -> usize {
+        self.samples.len() / self.channels
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        self.samples
+    }
+
+    pub fn sample(&self, channel: usize, frame: usize) -> T {
+        self.samples[frame * self.channels + channel]
+    }
+
+    pub fn frame(&self, index: usize) -> &[T] {
+        &self.samples[index * self.channels..(index + 1) * self.channels]
+    }
+
+    pub fn iter_frames(&self) -> ChunksExact<'_, T> {
+        self.samples.chunks_exact(self.channels)
+    }
+
+    /// Deinterleaves into a planar signal of the same size
+    pub fn copy_to_signal(&self, target: &mut impl SignalMut<T>) {
+        deinterleave(self.samples, self.channels, target);
+    }
+}
+
+pub struct InterleavedSignalMut<'samples, T: Sample = f32> {
+    samples: &'samples mut [T],
+    channels: usize,
+}
+
+impl<'samples, T: Sample> InterleavedSignalMut<'samples, T> {
+    pub fn new(samples: &'samples mut [T], channels: usize) -> Self {
+        assert!(channels > 0);
+        assert_eq!(samples.len() % channels, 0, "Interleaved sample count {} isn't divisible by channel count {channels}", samples.len());
+
+        Self {
+            samples,
+            channels,
+        }
+    }
+
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Length in frames
+    pub fn len(&self) -> usize {
+        self.samples.len() / self.channels
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        self.samples
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.samples
+    }
+
+    pub fn as_signal(&self) -> InterleavedSignal<'_, T> {
+        InterleavedSignal::new(self.samples, self.channels)
+    }
+
+    pub fn sample(&self, channel: usize, frame: usize) -> T {
+        self.samples[frame * self.channels + channel]
+    }
+
+    pub fn sample_mut(&mut self, channel: usize, frame: usize) -> &mut T {
+        &mut self.samples[frame * self.channels + channel]
+    }
+
+    pub fn frame(&self, index: usize) -> &[T] {
+        &self.samples[index * self.channels..(index + 1) * self.channels]
+    }
+
+    pub fn frame_mut(&mut self, index: usize) -> &mut [T] {
+        &mut self.samples[index * self.channels..(index + 1) * self.channels]
+    }
+
+    pub fn iter_frames(&self) -> ChunksExact<'_, T> {
+        self.samples.chunks_exact(self.channels)
+    }
+
+    pub fn iter_frames_mut(&mut self) -> ChunksExactMut<'_, T> {
+        self.samples.chunks_exact_mut(self.channels)
+    }
+
+    pub fn fill(&mut self, value: T) {
+        self.samples.fill(value);
+    }
+
+    pub fn copy_to_signal(&self, target: &mut impl SignalMut<T>) {
+        deinterleave(self.samples, self.channels, target);
+    }
+
+    /// Interleaves a planar signal of the same size
+    pub fn copy_from_signal(&mut self, source: &impl Signal<T>) {
+        assert_eq!(self.channels, source.channels());
+        assert_eq!(self.len(), source.len(), "Attempting to copy a signal of length {} into an interleaved signal of length {}", source.len(), self.len());
+
+        match self.channels {
+            1 => self.samples.copy_from_slice(source.channel(0)),
+            2 => {
+                for ((frame, &left), &right) in self.samples.chunks_exact_mut(2).zip(source.channel(0)).zip(source.channel(1)) {
+                    frame[0] = left;
+                    frame[1] = right;
+                }
+            }
+            _ => {
+                for (channel_index, channel) in source.iter_channels().enumerate() {
+                    for (target, &sample) in self.samples[channel_index..].iter_mut().step_by(self.channels).zip(channel) {
+                        *target = sample;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn deinterleave<T: Sample>(samples: &[T], channels: usize, target: &mut impl SignalMut<T>) {
+    assert_eq!(channels, target.channels());
+    assert_eq!(samples.len() / channels, target.len(), "Attempting to copy an interleaved signal of length {} into a signal of length {}", samples.len() / channels, target.len());
+
+    match channels {
+        1 => target.channel_mut(0).copy_from_slice(samples),
+        _ => {
+            for (channel_index, channel) in target.iter_channels_mut().enumerate() {
+                for (target, &sample) in channel.iter_mut().zip(samples[channel_index..].iter().step_by(channels)) {
+                    *target = sample;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{buffers::buffer::Buffer, signals::signal::Signal};
+
+    use super::{InterleavedSignal, InterleavedSignalMut};
+
+    #[test]
+    fn frames() {
+        let samples = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let signal = InterleavedSignal::new(&samples, 3);
+
+        assert_eq!(signal.len(), 2);
+        assert_eq!(signal.frame(1), [4.0, 5.0, 6.0]);
+        assert_eq!(signal.sample(2, 0), 3.0);
+        assert_eq!(signal.iter_frames().count(), 2);
+    }
+
+    #[test]
+    fn round_trip() {
+        for channels in 1..=3 {
+            let planar = Buffer::from((0..channels).map(|channel| (0..4).map(|index| (channel * 10 + index) as f32).collect()).collect::<Vec<_>>());
+
+            let mut samples = vec![0.0; channels * 4];
+            let mut interleaved = InterleavedSignalMut::new(&mut samples, channels);
+            interleaved.copy_from_signal(&planar);
+            assert_eq!(interleaved.sample(channels - 1, 3), planar.channel(channels - 1)[3]);
+
+            let mut target = Buffer::new(channels, 4);
+            interleaved.copy_to_signal(&mut target);
+            assert_eq!(target, planar);
+        }
+    }
+}
@@ -1,19 +1,19 @@
 use crate::util::ptr::any_null;
 
-use super::signal_base::{SignalBase, SignalMutBase};
+use super::{sample::Sample, signal_base::{SignalBase, SignalMutBase}};
 
-pub struct PtrSignal {
+pub struct PtrSignal<T: Sample = f32> {
     channels: usize,
     length: usize,
-    channels_pointers: *const *const f32,
+    channels_pointers: *const *const T,
 }
 
-impl PtrSignal {
+impl<T: Sample> PtrSignal<T> {
     /// # Safety
     /// 
     /// Caller is responsible for channels and length matching the pointers,
     /// and for taking care the pointers live long enough
-    pub unsafe fn from_pointers(channels: usize, length: usize, channels_pointers: *const *const f32) -> Self {
+    pub unsafe fn from_pointers(channels: usize, length: usize, channels_pointers: *const *const T) -> Self {
         assert!(!channels_pointers.is_null());
         assert!(unsafe { !any_null(channels_pointers, channels) });
 
@@ -24,12 +24,12 @@ impl PtrSignal {
         }
     }
 
-    pub fn pointers(&self) -> &[*const f32] {
+    pub fn pointers(&self) -> &[*const T] {
         unsafe { std::slice::from_raw_parts(self.channels_pointers, self.channels) }
     }
 }
 
-impl SignalBase for PtrSignal {
+impl<T: Sample> SignalBase<T> for PtrSignal<T> {
     fn len(&self) -> usize {
         self.length
     }
@@ -38,7 +38,7 @@ impl SignalBase for PtrSignal {
         self.channels
     }
 
-    fn channel_ptr(&self, channel: usize) -> *const [f32] {
+    fn channel_ptr(&self, channel: usize) -> *const [T] {
         unsafe {
             let channel_pointers = std::slice::from_raw_parts(self.channels_pointers, self.channels);
             let channel_pointer = std::slice::from_raw_parts(channel_pointers[channel], self.length);
@@ -47,18 +47,18 @@ impl SignalBase for PtrSignal {
     }
 }
 
-pub struct PtrSignalMut {
+pub struct PtrSignalMut<T: Sample = f32> {
     channels: usize,
     length: usize,
-    channels_pointers: *mut *mut f32,
+    channels_pointers: *mut *mut T,
 }
 
-impl PtrSignalMut {
+impl<T: Sample> PtrSignalMut<T> {
     /// # Safety
     /// 
     /// Caller is responsible for channels and length matching the pointers,
     /// and for taking care the pointers live long enough
-    pub unsafe fn from_pointers(channels: usize, length: usize, channels_pointers: *mut *mut f32) -> Self {
+    pub unsafe fn from_pointers(channels: usize, length: usize, channels_pointers: *mut *mut T) -> Self {
         Self {
             channels,
             length,
@@ -66,12 +66,12 @@ impl PtrSignalMut {
         }
     }
 
-    pub fn pointers(&self) -> &[*mut f32] {
+    pub fn pointers(&self) -> &[*mut T] {
         unsafe { std::slice::from_raw_parts(self.channels_pointers, self.channels) }
     }
 }
 
-impl SignalBase for PtrSignalMut {
+impl<T: Sample> SignalBase<T> for PtrSignalMut<T> {
     fn len(&self) -> usize {
         self.length
     }
@@ -80,7 +80,7 @@ impl SignalBase for PtrSignalMut {
         self.channels
     }
 
-    fn channel_ptr(&self, channel: usize) -> *const [f32] {
+    fn channel_ptr(&self, channel: usize) -> *const [T] {
         unsafe {
             let channel_pointers = std::slice::from_raw_parts(self.channels_pointers, self.channels);
             let channel_pointer = std::slice::from_raw_parts(channel_pointers[channel], self.length);
@@ -89,8 +89,8 @@ impl SignalBase for PtrSignalMut {
     }
 }
 
-impl SignalMutBase for PtrSignalMut {
-    fn channel_ptr_mut(&mut self, channel: usize) -> *mut [f32] {
+impl<T: Sample> SignalMutBase<T> for PtrSignalMut<T> {
+    fn channel_ptr_mut(&mut self, channel: usize) -> *mut [T] {
         unsafe {
             let channel_pointers = std::slice::from_raw_parts_mut(self.channels_pointers, self.channels);
             let channel_pointer = std::slice::from_raw_parts_mut(channel_pointers[channel], self.length);
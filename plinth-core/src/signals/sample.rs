@@ -0,0 +1,107 @@
+use std::{fmt::Debug, iter::Sum, ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub}};
+
+/// Anything that can be stored in a signal channel
+pub trait Sample: Copy + PartialEq + Debug + Send + Sync + 'static {
+    /// The value of silence
+    const EQUILIBRIUM: Self;
+}
+
+/// Single value samples that can be converted between formats, with full scale at +-1.0
+pub trait ScalarSample: Sample {
+    fn to_f64(self) -> f64;
+    /// Integer formats round and clip
+    fn from_f64(value: f64) -> Self;
+
+    fn from_sample<T: ScalarSample>(sample: T) -> Self {
+        Self::from_f64(sample.to_f64())
+    }
+}
+
+/// Floating point samples that signal math works on
+pub trait FloatSample: ScalarSample + PartialOrd + Sum
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Neg<Output = Self>
+    + AddAssign + MulAssign
+{
+    const ZERO: Self;
+    const ONE: Self;
+    /// Smallest positive normal value
+    const MIN_POSITIVE: Self;
+
+    fn abs(self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn clamp(self, min: Self, max: Self) -> Self;
+}
+
+macro_rules! float_sample {
+    ($type:ty) => {
+        impl Sample for $type {
+            const EQUILIBRIUM: Self = 0.0;
+        }
+
+        impl ScalarSample for $type {
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn from_f64(value: f64) -> Self {
+                value as $type
+            }
+        }
+
+        impl FloatSample for $type {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+            const MIN_POSITIVE: Self = <$type>::MIN_POSITIVE;
+
+            fn abs(self) -> Self {
+                <$type>::abs(self)
+            }
+
+            fn max(self, other: Self) -> Self {
+                <$type>::max(self, other)
+            }
+
+            fn clamp(self, min: Self, max: Self) -> Self {
+                <$type>::clamp(self, min, max)
+            }
+        }
+    };
+}
+
+macro_rules! integer_sample {
+    ($type:ty) => {
+        impl Sample for $type {
+            const EQUILIBRIUM: Self = 0;
+        }
+
+        impl ScalarSample for $type {
+            fn to_f64(self) -> f64 {
+                self as f64 / -(<$type>::MIN as f64)
+            }
+
+            fn from_f64(value: f64) -> Self {
+                let scale = -(<$type>::MIN as f64);
+                (value * scale).round().clamp(-scale, scale - 1.0) as $type
+            }
+        }
+    };
+}
+
+float_sample!(f32);
+float_sample!(f64);
+integer_sample!(i16);
+integer_sample!(i32);
+
+#[cfg(test)]
+mod tests {
+    use super::ScalarSample;
+
+    #[test]
+    fn convert() {
+        assert_eq!(i16::from_sample(0.5f32), 16384);
+        assert_eq!(i16::from_sample(1.0f64), i16::MAX);
+        assert_eq!(f32::from_sample(i16::MIN), -1.0);
+        assert_eq!(i32::from_sample(-1.0f32), i32::MIN);
+        assert_eq!(f64::from_sample(0.25f32), 0.25);
+    }
+}
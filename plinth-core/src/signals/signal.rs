@@ -2,35 +2,46 @@ use std::{iter::zip, ops::{RangeBounds, Range}};
 
 use itertools::izip;
 
-use crate::collections::{copy_from_slice::CopyFromSlice, interleave_iterator::InterleaveIterator};
+use crate::{collections::interleave_iterator::InterleaveIterator, simd};
 
-use super::{channels::{ChannelsIterator, ChannelsIteratorMut}, frames_iterator::{FramesIterator, FramesIteratorMut}, signal_base::{SignalBase, SignalMutBase}, signal_frame::{SignalFrame, SignalFrameMut}, slice::{SignalSlice, SignalSliceMut}};
+use super::{channels::{ChannelsIterator, ChannelsIteratorMut}, frames_iterator::{FramesIterator, FramesIteratorMut}, sample::{FloatSample, Sample, ScalarSample}, signal_base::{SignalBase, SignalMutBase}, signal_frame::{SignalFrame, SignalFrameMut}, slice::{SignalSlice, SignalSliceMut}};
 
-pub trait Signal : SignalBase {
-    fn iter_channels(&self) -> ChannelsIterator<'_, Self>;
+pub trait Signal<T: Sample = f32> : SignalBase<T> {
+    fn iter_channels(&self) -> ChannelsIterator<'_, Self, T>;
     fn frame(&self, index: usize) -> SignalFrame<'_, Self>;
-    fn iter_frames(&self) -> FramesIterator<'_, Self>;
+    fn iter_frames(&self) -> FramesIterator<'_, Self, T>;
 
-    fn channel(&self, channel: usize) -> &[f32] {
+    fn channel(&self, channel: usize) -> &[T] {
         unsafe { &*self.channel_ptr(channel) }
     }
 
-    fn slice<T: RangeBounds<usize>>(&self, range: T) -> SignalSlice<'_, Self> {
+    fn slice<R: RangeBounds<usize>>(&self, range: R) -> SignalSlice<'_, Self> {
         SignalSlice::new(self, range)
     }
 
-    fn iter_interleaved(&self) -> InterleaveIterator<&f32, std::slice::Iter<'_, f32>> {
+    fn iter_interleaved(&self) -> InterleaveIterator<&T, std::slice::Iter<'_, T>> {
         InterleaveIterator::new(self.iter_channels().map(|channel| channel.iter()))
     }
 
-    fn mix_to(&self, self_gain: f32, other: &impl Signal, other_gain: f32, target: &mut impl SignalMut) {
+    fn mix_to(&self, self_gain: T, other: &impl Signal<T>, other_gain: T, target: &mut impl SignalMut<T>) where T: FloatSample {
         for (self_channel, other_channel, target_channel) in izip!(self.iter_channels(), other.iter_channels(), target.iter_channels_mut()) {
-            for (self_sample, other_sample, target_sample) in izip!(self_channel, other_channel, target_channel) {
-                *target_sample = self_sample * self_gain + other_sample * other_gain;
-            }
+            simd::mix_to(self_channel, self_gain, other_channel, other_gain, target_channel);
         }
     }
 
+    /// Highest absolute sample value over all channels
+    fn abs_max(&self) -> T where T: FloatSample {
+        self.iter_channels()
+            .map(simd::abs_max)
+            .fold(T::ZERO, T::max)
+    }
+
+    fn sum_of_squares(&self) -> T where T: FloatSample {
+        self.iter_channels()
+            .map(simd::sum_of_squares)
+            .sum()
+    }
+
     fn apply_wrap(&self, index: usize, length: usize, mut function: impl FnMut(&SignalSlice<'_, Self>, Range<usize>)) {
         assert!(index < self.len(), "index out of bounds: {index}");
 
@@ -44,38 +55,56 @@ pub trait Signal : SignalBase {
     }
 }
 
-pub trait SignalMut: Signal + SignalMutBase {
-    fn iter_channels_mut(&mut self) -> ChannelsIteratorMut<'_, Self>;
+pub trait SignalMut<T: Sample = f32>: Signal<T> + SignalMutBase<T> {
+    fn iter_channels_mut(&mut self) -> ChannelsIteratorMut<'_, Self, T>;
     fn frame_mut(&mut self, index: usize) -> SignalFrameMut<'_, Self>;
-    fn iter_frames_mut(&mut self) -> FramesIteratorMut<'_, Self>;
+    fn iter_frames_mut(&mut self) -> FramesIteratorMut<'_, Self, T>;
 
-    fn channel_mut(&mut self, channel: usize) -> &mut [f32] {
+    fn channel_mut(&mut self, channel: usize) -> &mut [T] {
         unsafe { &mut *self.channel_ptr_mut(channel) }
     }   
 
-    fn slice_mut<T: RangeBounds<usize>>(&mut self, range: T) -> SignalSliceMut<'_, Self> {
+    fn slice_mut<R: RangeBounds<usize>>(&mut self, range: R) -> SignalSliceMut<'_, Self> {
         SignalSliceMut::new(self, range)
     }
 
-    fn iter_interleaved_mut(&mut self) -> InterleaveIterator<&mut f32, std::slice::IterMut<'_, f32>> {
+    fn iter_interleaved_mut(&mut self) -> InterleaveIterator<&mut T, std::slice::IterMut<'_, T>> {
         InterleaveIterator::new(self.iter_channels_mut().map(|channel| channel.iter_mut()))
     }
 
-    fn fill(&mut self, value: f32) {
+    fn fill(&mut self, value: T) {
         for channel in self.iter_channels_mut() {
             channel.fill(value);
         }
     }
 
-    fn scale(&mut self, scale: f32) {
+    fn scale(&mut self, scale: T) where T: FloatSample {
         for channel in self.iter_channels_mut() {
-            for sample in channel.iter_mut() {
-                *sample *= scale;
-            }
+            simd::scale(channel, scale);
+        }
+    }
+
+    /// Applies a gain moving linearly from `start_gain` towards `end_gain` over the signal
+    fn gain_ramp(&mut self, start_gain: T, end_gain: T) where T: FloatSample {
+        for channel in self.iter_channels_mut() {
+            simd::gain_ramp(channel, start_gain, end_gain);
+        }
+    }
+
+    /// Panics if `min > max`
+    fn clamp(&mut self, min: T, max: T) where T: FloatSample {
+        for channel in self.iter_channels_mut() {
+            simd::clamp(channel, min, max);
         }
     }
 
-    fn copy_from_signal(&mut self, source: &impl Signal) {
+    fn flush_denormals(&mut self) where T: FloatSample {
+        for channel in self.iter_channels_mut() {
+            simd::flush_denormals(channel);
+        }
+    }
+
+    fn copy_from_signal(&mut self, source: &impl Signal<T>) {
         assert_eq!(self.channels(), source.channels());
         assert_eq!(self.len(), source.len(), "Attempting to copy a signal of length {} into a signal of length {}", source.len(), self.len());
 
@@ -84,29 +113,37 @@ pub trait SignalMut: Signal + SignalMutBase {
         }
     }
 
-    fn copy_from_signal_and_fill(&mut self, source: &impl Signal, value: f32) {
+    fn copy_from_signal_and_fill(&mut self, source: &impl Signal<T>, value: T) {
         assert!(self.channels() == source.channels());
 
         for (target_channel, source_channel) in zip(self.iter_channels_mut(), source.iter_channels()) {
-            target_channel.copy_from_slice_and_fill(source_channel, value);
+            simd::copy_and_fill(target_channel, source_channel, value);
         }
     }
 
-    fn add_from_signal(&mut self, source: &impl Signal) {
-        assert!(self.channels() == source.channels());
+    /// Copies a signal with a different sample type, for example 16-bit integers into f32
+    fn convert_from_signal<U: ScalarSample>(&mut self, source: &impl Signal<U>) where T: ScalarSample {
+        assert_eq!(self.channels(), source.channels());
+        assert_eq!(self.len(), source.len(), "Attempting to convert a signal of length {} into a signal of length {}", source.len(), self.len());
 
         for (target_channel, source_channel) in zip(self.iter_channels_mut(), source.iter_channels()) {
-            for (target_sample, source_sample) in zip(target_channel, source_channel) {
-                *target_sample += source_sample;
+            for (target, &source) in zip(target_channel, source_channel) {
+                *target = T::from_sample(source);
             }
         }
     }
 
-    fn mix_signal(&mut self, gain_self: f32, source: &impl Signal, gain_source: f32) {
+    fn add_from_signal(&mut self, source: &impl Signal<T>) where T: FloatSample {
+        assert!(self.channels() == source.channels());
+
+        for (target_channel, source_channel) in zip(self.iter_channels_mut(), source.iter_channels()) {
+            simd::add(target_channel, source_channel);
+        }
+    }
+
+    fn mix_signal(&mut self, gain_self: T, source: &impl Signal<T>, gain_source: T) where T: FloatSample {
         for (self_channel, source_channel) in izip!(self.iter_channels_mut(), source.iter_channels()) {
-            for (self_sample, source_sample) in zip(self_channel, source_channel) {
-                *self_sample = *self_sample * gain_self + source_sample * gain_source;
-            }
+            simd::mix(self_channel, gain_self, source_channel, gain_source);
         }
     }
 
@@ -123,8 +160,8 @@ pub trait SignalMut: Signal + SignalMutBase {
     }
 }
 
-impl<T: SignalBase> Signal for T {
-    fn iter_channels(&self) -> ChannelsIterator<'_, Self> {
+impl<T: Sample, S: SignalBase<T>> Signal<T> for S {
+    fn iter_channels(&self) -> ChannelsIterator<'_, Self, T> {
         ChannelsIterator::new(self)
     }
 
@@ -132,13 +169,13 @@ impl<T: SignalBase> Signal for T {
         SignalFrame::new(self, index)
     }
 
-    fn iter_frames(&self) -> FramesIterator<'_, Self> {
+    fn iter_frames(&self) -> FramesIterator<'_, Self, T> {
         FramesIterator::new(self)
     }
 }
 
-impl<T: Signal + SignalMutBase> SignalMut for T {
-    fn iter_channels_mut(&mut self) -> ChannelsIteratorMut<'_, Self> {
+impl<T: Sample, S: Signal<T> + SignalMutBase<T>> SignalMut<T> for S {
+    fn iter_channels_mut(&mut self) -> ChannelsIteratorMut<'_, Self, T> {
         ChannelsIteratorMut::new(self)
     }
 
@@ -146,12 +183,12 @@ impl<T: Signal + SignalMutBase> SignalMut for T {
         SignalFrameMut::new(self, index)
     }
 
-    fn iter_frames_mut(&mut self) -> FramesIteratorMut<'_, Self> {
+    fn iter_frames_mut(&mut self) -> FramesIteratorMut<'_, Self, T> {
         FramesIteratorMut::new(self)
     }
 }
 
-impl<T: AsRef<[f32]>> SignalBase for T {
+impl<T: Sample, S: AsRef<[T]>> SignalBase<T> for S {
     fn len(&self) -> usize {
         self.as_ref().len()
     }
@@ -160,15 +197,15 @@ impl<T: AsRef<[f32]>> SignalBase for T {
         1
     }
 
-    fn channel_ptr(&self, channel: usize) -> *const [f32] {
+    fn channel_ptr(&self, channel: usize) -> *const [T] {
         assert_eq!(channel, 0);
-        self.as_ref() as *const [f32]
+        self.as_ref() as *const [T]
     }
 }
 
-impl<T: AsMut<[f32]> + AsRef<[f32]>> SignalMutBase for T {
-    fn channel_ptr_mut(&mut self, channel: usize) -> *mut [f32] {
+impl<T: Sample, S: AsMut<[T]> + AsRef<[T]>> SignalMutBase<T> for S {
+    fn channel_ptr_mut(&mut self, channel: usize) -> *mut [T] {
         assert_eq!(channel, 0);
-        self.as_mut() as *mut [f32]
+        self.as_mut() as *mut [T]
     }
 }
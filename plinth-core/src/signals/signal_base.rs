@@ -1,13 +1,15 @@
-pub trait SignalBase {
+use super::sample::Sample;
+
+pub trait SignalBase<T: Sample = f32> {
     fn len(&self) -> usize;
     fn channels(&self) -> usize;
-    fn channel_ptr(&self, channel: usize) -> *const [f32];
+    fn channel_ptr(&self, channel: usize) -> *const [T];
 
     fn is_empty(&self) -> bool {
         self.len() == 0
     }
 }
 
-pub trait SignalMutBase {
-    fn channel_ptr_mut(&mut self, channel: usize) -> *mut [f32];
+pub trait SignalMutBase<T: Sample = f32> {
+    fn channel_ptr_mut(&mut self, channel: usize) -> *mut [T];
 }
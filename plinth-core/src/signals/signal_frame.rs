@@ -1,11 +1,11 @@
-use super::{frame::{Frame, FrameMut}, frame_iterator::{FrameIterator, FrameIteratorMut}, signal::{Signal, SignalMut}};
+use super::{frame::{Frame, FrameMut}, frame_iterator::{FrameIterator, FrameIteratorMut}, sample::Sample, signal::{Signal, SignalMut}};
 
-pub struct SignalFrame<'signal, S: Signal + ?Sized> {
+pub struct SignalFrame<'signal, S: ?Sized> {
     signal: &'signal S,
     frame_index: usize,
 }
 
-impl<S: Signal> SignalFrame<'_, S> {
+impl<S: ?Sized> SignalFrame<'_, S> {
     pub fn new(signal: &S, frame_index: usize) -> SignalFrame<'_, S> {
         SignalFrame {
             signal,
@@ -14,31 +14,32 @@ impl<S: Signal> SignalFrame<'_, S> {
     }
 }
 
-impl<'frame, S> Frame<'frame> for SignalFrame<'frame, S>
+impl<'frame, S, T> Frame<'frame, T> for SignalFrame<'frame, S>
 where
-    S: Signal,
+    S: Signal<T>,
+    T: Sample,
 {
-    type Iterator = FrameIterator<'frame, S>;
+    type Iterator = FrameIterator<'frame, S, T>;
 
     fn channels(&self) -> usize {
         self.signal.channels()
     }
 
-    fn channel(&self, index: usize) -> &f32 {
+    fn channel(&self, index: usize) -> &T {
         &self.signal.channel(index)[self.frame_index]
     }
 
-    fn iter(&self) -> FrameIterator<'frame, S> {
+    fn iter(&self) -> FrameIterator<'frame, S, T> {
         FrameIterator::new(self.signal, self.frame_index)
     }
 }
 
-pub struct SignalFrameMut<'signal, S: SignalMut + ?Sized> {
+pub struct SignalFrameMut<'signal, S: ?Sized> {
     signal: &'signal mut S,
     frame_index: usize,
 }
 
-impl<S: SignalMut> SignalFrameMut<'_, S> {
+impl<S: ?Sized> SignalFrameMut<'_, S> {
     pub fn new(signal: &mut S, frame_index: usize) -> SignalFrameMut<'_, S> {
         SignalFrameMut {
             signal,
@@ -47,30 +48,30 @@ impl<S: SignalMut> SignalFrameMut<'_, S> {
     }
 }
 
-impl<'frame, S: SignalMut + 'frame> Frame<'frame> for SignalFrameMut<'_, S> {
-    type Iterator = FrameIterator<'frame, S>;
+impl<'frame, S: SignalMut<T> + 'frame, T: Sample> Frame<'frame, T> for SignalFrameMut<'_, S> {
+    type Iterator = FrameIterator<'frame, S, T>;
 
     fn channels(&self) -> usize {
         self.signal.channels()
     }
 
-    fn channel(&self, index: usize) -> &f32 {
+    fn channel(&self, index: usize) -> &T {
         &self.signal.channel(index)[self.frame_index]
     }
 
-    fn iter(&'frame self) -> FrameIterator<'frame, S> {
+    fn iter(&'frame self) -> FrameIterator<'frame, S, T> {
         FrameIterator::new(self.signal, self.frame_index)
     }
 }
 
-impl<'frame, S: SignalMut + 'frame> FrameMut<'frame> for SignalFrameMut<'_, S> {
-    type IteratorMut = FrameIteratorMut<'frame, S>;
+impl<'frame, S: SignalMut<T> + 'frame, T: Sample> FrameMut<'frame, T> for SignalFrameMut<'_, S> {
+    type IteratorMut = FrameIteratorMut<'frame, S, T>;
 
-    fn channel_mut(&mut self, index: usize) -> &mut f32 {
+    fn channel_mut(&mut self, index: usize) -> &mut T {
         &mut self.signal.channel_mut(index)[self.frame_index]
     }
 
-    fn iter_mut(&'frame mut self) -> FrameIteratorMut<'frame, S> {
+    fn iter_mut(&'frame mut self) -> FrameIteratorMut<'frame, S, T> {
         FrameIteratorMut::new(self.signal, self.frame_index)
     }
 }
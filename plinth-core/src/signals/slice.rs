@@ -2,15 +2,18 @@ use std::ops::{RangeBounds, Range};
 
 use crate::util::range::range_from_bounds;
 
-use super::{signal::{Signal, SignalMut}, signal_base::{SignalBase, SignalMutBase}};
+use super::{sample::Sample, signal::{Signal, SignalMut}, signal_base::{SignalBase, SignalMutBase}};
 
-pub struct SignalSlice<'signal, S: Signal + ?Sized> {
+pub struct SignalSlice<'signal, S: ?Sized> {
     signal: &'signal S,
     range: Range<usize>,
 }
 
-impl<S: Signal + ?Sized> SignalSlice<'_, S> {
-    pub fn new<T: RangeBounds<usize>>(signal: &S, range: T) -> SignalSlice<'_, S> {
+impl<S: ?Sized> SignalSlice<'_, S> {
+    pub fn new<T: Sample, R: RangeBounds<usize>>(signal: &S, range: R) -> SignalSlice<'_, S>
+    where
+        S: Signal<T>,
+    {
         SignalSlice {
             signal,
             range: range_from_bounds(range, signal.len()),
@@ -18,7 +21,7 @@ impl<S: Signal + ?Sized> SignalSlice<'_, S> {
     }
 }
 
-impl<S: Signal + ?Sized> SignalBase for SignalSlice<'_, S> {
+impl<T: Sample, S: Signal<T> + ?Sized> SignalBase<T> for SignalSlice<'_, S> {
     fn len(&self) -> usize {
         assert!(self.range.end >= self.range.start, "Can't use reverse ranges for SignalSlice, got {}..{}", self.range.start, self.range.end);
         self.range.end - self.range.start
@@ -28,19 +31,22 @@ impl<S: Signal + ?Sized> SignalBase for SignalSlice<'_, S> {
         self.signal.channels()
     }
 
-    fn channel_ptr(&self, channel: usize) -> *const [f32] {
+    fn channel_ptr(&self, channel: usize) -> *const [T] {
         let channel_ref = unsafe { &*self.signal.channel_ptr(channel) };
         &channel_ref[self.range.start..self.range.end]
     }
 }
 
-pub struct SignalSliceMut<'signal, S: SignalMut + ?Sized> {
+pub struct SignalSliceMut<'signal, S: ?Sized> {
     signal: &'signal mut S,
     range: Range<usize>,
 }
 
-impl<S: SignalMut + ?Sized> SignalSliceMut<'_, S> {
-    pub fn new<T: RangeBounds<usize>>(signal: &mut S, range: T) -> SignalSliceMut<'_, S> {
+impl<S: ?Sized> SignalSliceMut<'_, S> {
+    pub fn new<T: Sample, R: RangeBounds<usize>>(signal: &mut S, range: R) -> SignalSliceMut<'_, S>
+    where
+        S: SignalMut<T>,
+    {
         let signal_len = signal.len();
 
         SignalSliceMut {
@@ -50,7 +56,7 @@ impl<S: SignalMut + ?Sized> SignalSliceMut<'_, S> {
     }
 }
 
-impl<S: SignalMut + ?Sized> SignalBase for SignalSliceMut<'_, S> {
+impl<T: Sample, S: SignalMut<T> + ?Sized> SignalBase<T> for SignalSliceMut<'_, S> {
     fn len(&self) -> usize {
         assert!(self.range.end >= self.range.start, "Can't use reverse ranges for SignalSliceMut, got {}..{}", self.range.start, self.range.end);
         self.range.end - self.range.start
@@ -60,14 +66,14 @@ impl<S: SignalMut + ?Sized> SignalBase for SignalSliceMut<'_, S> {
         self.signal.channels()
     }
 
-    fn channel_ptr(&self, channel: usize) -> *const [f32] {
+    fn channel_ptr(&self, channel: usize) -> *const [T] {
         let channel_ref = unsafe { &*self.signal.channel_ptr(channel) };
         &channel_ref[self.range.start..self.range.end]
     }
 }
 
-impl<S: SignalMut + ?Sized> SignalMutBase for SignalSliceMut<'_, S> {
-    fn channel_ptr_mut(&mut self, channel: usize) -> *mut [f32] {
+impl<T: Sample, S: SignalMut<T> + ?Sized> SignalMutBase<T> for SignalSliceMut<'_, S> {
+    fn channel_ptr_mut(&mut self, channel: usize) -> *mut [T] {
         let channel_ref = unsafe { &mut *self.signal.channel_ptr_mut(channel) };
         &mut channel_ref[self.range.start..self.range.end]
     }
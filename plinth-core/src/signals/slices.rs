@@ -0,0 +1,112 @@
+use super::{sample::Sample, signal_base::{SignalBase, SignalMutBase}};
+
+/// Signal over borrowed channel slices, for using external buffers without raw pointers
+pub struct SlicesSignal<'channels, 'samples, T: Sample = f32> {
+    channels: &'channels [&'samples [T]],
+    length: usize,
+}
+
+impl<'channels, 'samples, T: Sample> SlicesSignal<'channels, 'samples, T> {
+    pub fn new(channels: &'channels [&'samples [T]]) -> Self {
+        Self {
+            length: common_length(channels.iter().map(|channel| channel.len())),
+            channels,
+        }
+    }
+}
+
+impl<T: Sample> SignalBase<T> for SlicesSignal<'_, '_, T> {
+    fn len(&self) -> usize {
+        self.length
+    }
+
+    fn channels(&self) -> usize {
+        self.channels.len()
+    }
+
+    fn channel_ptr(&self, channel: usize) -> *const [T] {
+        self.channels[channel]
+    }
+}
+
+pub struct SlicesSignalMut<'channels, 'samples, T: Sample = f32> {
+    channels: &'channels mut [&'samples mut [T]],
+    length: usize,
+}
+
+impl<'channels, 'samples, T: Sample> SlicesSignalMut<'channels, 'samples, T> {
+    pub fn new(channels: &'channels mut [&'samples mut [T]]) -> Self {
+        Self {
+            length: common_length(channels.iter().map(|channel| channel.len())),
+            channels,
+        }
+    }
+}
+
+impl<T: Sample> SignalBase<T> for SlicesSignalMut<'_, '_, T> {
+    fn len(&self) -> usize {
+        self.length
+    }
+
+    fn channels(&self) -> usize {
+        self.channels.len()
+    }
+
+    fn channel_ptr(&self, channel: usize) -> *const [T] {
+        &*self.channels[channel]
+    }
+}
+
+impl<T: Sample> SignalMutBase<T> for SlicesSignalMut<'_, '_, T> {
+    fn channel_ptr_mut(&mut self, channel: usize) -> *mut [T] {
+        &mut *self.channels[channel]
+    }
+}
+
+fn common_length(mut lengths: impl Iterator<Item = usize>) -> usize {
+    let length = lengths.next().unwrap_or(0);
+    assert!(lengths.all(|other| other == length), "All channels need to have the same length");
+
+    length
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::signals::{signal::{Signal, SignalMut}, signal_base::SignalBase};
+
+    use super::{SlicesSignal, SlicesSignalMut};
+
+    #[test]
+    fn read() {
+        let left = [1.0, 2.0];
+        let right = [3.0, 4.0];
+        let channels = [&left[..], &right[..]];
+        let signal = SlicesSignal::new(&channels);
+
+        assert_eq!(signal.len(), 2);
+        assert_eq!(signal.channel(1), [3.0, 4.0]);
+    }
+
+    #[test]
+    fn write() {
+        let mut left = [1.0, 2.0];
+        let mut right = [3.0, 4.0];
+
+        {
+            let mut channels = [&mut left[..], &mut right[..]];
+            let mut signal = SlicesSignalMut::new(&mut channels);
+            signal.scale(2.0);
+        }
+
+        assert_eq!(left, [2.0, 4.0]);
+        assert_eq!(right, [6.0, 8.0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn mismatched_lengths() {
+        let left = [1.0, 2.0];
+        let right = [3.0];
+        SlicesSignal::new(&[&left[..], &right[..]]);
+    }
+}
@@ -0,0 +1,326 @@
+use std::iter::zip;
+
+use crate::signals::sample::{FloatSample, Sample};
+
+#[cfg(feature = "portable-simd")]
+mod portable;
+
+/// The slice operations here work in chunks of this many samples so the compiler can vectorize them on any target
+///
+/// With the `portable-simd` feature, which needs nightly Rust, f32 slices use `std::simd` vectors explicitly.
+pub const LANES: usize = 8;
+
+pub fn scale<T: FloatSample>(samples: &mut [T], gain: T) {
+    #[cfg(feature = "portable-simd")]
+    if let Some(samples) = portable::as_f32_mut(samples) {
+        return portable::scale(samples, portable::to_f32(gain));
+    }
+
+    let mut chunks = samples.chunks_exact_mut(LANES);
+
+    for chunk in chunks.by_ref() {
+        for sample in chunk {
+            *sample *= gain;
+        }
+    }
+
+    for sample in chunks.into_remainder() {
+        *sample *= gain;
+    }
+}
+
+pub fn add<T: FloatSample>(target: &mut [T], source: &[T]) {
+    let length = usize::min(target.len(), source.len());
+    let (target, source) = (&mut target[..length], &source[..length]);
+
+    #[cfg(feature = "portable-simd")]
+    if let (Some(target), Some(source)) = (portable::as_f32_mut(target), portable::as_f32(source)) {
+        return portable::mix(target, 1.0, source, 1.0);
+    }
+
+    let mut target_chunks = target.chunks_exact_mut(LANES);
+    let mut source_chunks = source.chunks_exact(LANES);
+
+    for (target_chunk, source_chunk) in zip(target_chunks.by_ref(), source_chunks.by_ref()) {
+        for (target_sample, source_sample) in zip(target_chunk, source_chunk) {
+            *target_sample += *source_sample;
+        }
+    }
+
+    for (target_sample, source_sample) in zip(target_chunks.into_remainder(), source_chunks.remainder()) {
+        *target_sample += *source_sample;
+    }
+}
+
+/// `target = target * target_gain + source * source_gain`
+pub fn mix<T: FloatSample>(target: &mut [T], target_gain: T, source: &[T], source_gain: T) {
+    let length = usize::min(target.len(), source.len());
+    let (target, source) = (&mut target[..length], &source[..length]);
+
+    #[cfg(feature = "portable-simd")]
+    if let (Some(target), Some(source)) = (portable::as_f32_mut(target), portable::as_f32(source)) {
+        return portable::mix(target, portable::to_f32(target_gain), source, portable::to_f32(source_gain));
+    }
+
+    let mut target_chunks = target.chunks_exact_mut(LANES);
+    let mut source_chunks = source.chunks_exact(LANES);
+
+    for (target_chunk, source_chunk) in zip(target_chunks.by_ref(), source_chunks.by_ref()) {
+        for (target_sample, source_sample) in zip(target_chunk, source_chunk) {
+            *target_sample = *target_sample * target_gain + *source_sample * source_gain;
+        }
+    }
+
+    for (target_sample, source_sample) in zip(target_chunks.into_remainder(), source_chunks.remainder()) {
+        *target_sample = *target_sample * target_gain + *source_sample * source_gain;
+    }
+}
+
+/// `target = a * a_gain + b * b_gain`
+pub fn mix_to<T: FloatSample>(a: &[T], a_gain: T, b: &[T], b_gain: T, target: &mut [T]) {
+    let length = usize::min(usize::min(a.len(), b.len()), target.len());
+    let (a, b, target) = (&a[..length], &b[..length], &mut target[..length]);
+
+    #[cfg(feature = "portable-simd")]
+    if let (Some(a), Some(b), Some(target)) = (portable::as_f32(a), portable::as_f32(b), portable::as_f32_mut(target)) {
+        return portable::mix_to(a, portable::to_f32(a_gain), b, portable::to_f32(b_gain), target);
+    }
+
+    let mut a_chunks = a.chunks_exact(LANES);
+    let mut b_chunks = b.chunks_exact(LANES);
+    let mut target_chunks = target.chunks_exact_mut(LANES);
+
+    for ((a_chunk, b_chunk), target_chunk) in zip(zip(a_chunks.by_ref(), b_chunks.by_ref()), target_chunks.by_ref()) {
+        for ((a_sample, b_sample), target_sample) in zip(zip(a_chunk, b_chunk), target_chunk) {
+            *target_sample = *a_sample * a_gain + *b_sample * b_gain;
+        }
+    }
+
+    for ((a_sample, b_sample), target_sample) in zip(zip(a_chunks.remainder(), b_chunks.remainder()), target_chunks.into_remainder()) {
+        *target_sample = *a_sample * a_gain + *b_sample * b_gain;
+    }
+}
+
+/// Multiplies by a gain moving linearly from `start_gain` towards `end_gain`, which is reached after the last sample
+pub fn gain_ramp<T: FloatSample>(samples: &mut [T], start_gain: T, end_gain: T) {
+    if samples.is_empty() {
+        return;
+    }
+
+    let step = (end_gain - start_gain) / T::from_f64(samples.len() as f64);
+    let offsets: [T; LANES] = std::array::from_fn(|lane| T::from_f64(lane as f64) * step);
+
+    let mut chunks = samples.chunks_exact_mut(LANES);
+    let mut chunk_gain = start_gain;
+
+    for chunk in chunks.by_ref() {
+        for (sample, offset) in zip(chunk, offsets) {
+            *sample *= chunk_gain + offset;
+        }
+
+        chunk_gain += step * T::from_f64(LANES as f64);
+    }
+
+    for (sample, offset) in zip(chunks.into_remainder(), offsets) {
+        *sample *= chunk_gain + offset;
+    }
+}
+
+pub fn abs_max<T: FloatSample>(samples: &[T]) -> T {
+    #[cfg(feature = "portable-simd")]
+    if let Some(samples) = portable::as_f32(samples) {
+        return portable::from_f32(portable::abs_max(samples));
+    }
+
+    let mut maxima = [T::ZERO; LANES];
+    let mut chunks = samples.chunks_exact(LANES);
+
+    for chunk in chunks.by_ref() {
+        for (max, sample) in zip(maxima.iter_mut(), chunk) {
+            *max = max.max(sample.abs());
+        }
+    }
+
+    chunks.remainder().iter()
+        .chain(maxima.iter())
+        .fold(T::ZERO, |max, sample| T::max(max, sample.abs()))
+}
+
+pub fn sum_of_squares<T: FloatSample>(samples: &[T]) -> T {
+    #[cfg(feature = "portable-simd")]
+    if let Some(samples) = portable::as_f32(samples) {
+        return portable::from_f32(portable::sum_of_squares(samples));
+    }
+
+    let mut sums = [T::ZERO; LANES];
+    let mut chunks = samples.chunks_exact(LANES);
+
+    for chunk in chunks.by_ref() {
+        for (sum, sample) in zip(sums.iter_mut(), chunk) {
+            *sum += *sample * *sample;
+        }
+    }
+
+    let remainder: T = chunks.remainder().iter().map(|&sample| sample * sample).sum();
+    sums.into_iter().sum::<T>() + remainder
+}
+
+/// Replaces `a` with `(a + b) * gain` and `b` with `(a - b) * gain`
+///
+/// With a gain of 0.5 this converts left/right to mid/side, with a gain of 1.0 it converts back.
+pub fn sum_difference<T: FloatSample>(a: &mut [T], b: &mut [T], gain: T) {
+    let length = usize::min(a.len(), b.len());
+    let (a, b) = (&mut a[..length], &mut b[..length]);
+
+    #[cfg(feature = "portable-simd")]
+    if let (Some(a), Some(b)) = (portable::as_f32_mut(a), portable::as_f32_mut(b)) {
+        return portable::sum_difference(a, b, portable::to_f32(gain));
+    }
+
+    let mut a_chunks = a.chunks_exact_mut(LANES);
+    let mut b_chunks = b.chunks_exact_mut(LANES);
+
+    for (a_chunk, b_chunk) in zip(a_chunks.by_ref(), b_chunks.by_ref()) {
+        for (a_sample, b_sample) in zip(a_chunk, b_chunk) {
+            (*a_sample, *b_sample) = ((*a_sample + *b_sample) * gain, (*a_sample - *b_sample) * gain);
+        }
+    }
+
+    for (a_sample, b_sample) in zip(a_chunks.into_remainder(), b_chunks.into_remainder()) {
+        (*a_sample, *b_sample) = ((*a_sample + *b_sample) * gain, (*a_sample - *b_sample) * gain);
+    }
+}
+
+/// # Panics
+///
+/// If `min > max`, or either is NaN, like `f32::clamp()`. This is checked up front, so it panics even for an empty slice.
+pub fn clamp<T: FloatSample>(samples: &mut [T], min: T, max: T) {
+    assert!(min <= max, "Invalid clamp range {min:?}..={max:?}");
+
+    #[cfg(feature = "portable-simd")]
+    if let Some(samples) = portable::as_f32_mut(samples) {
+        return portable::clamp(samples, portable::to_f32(min), portable::to_f32(max));
+    }
+
+    let mut chunks = samples.chunks_exact_mut(LANES);
+
+    for chunk in chunks.by_ref() {
+        for sample in chunk {
+            *sample = sample.clamp(min, max);
+        }
+    }
+
+    for sample in chunks.into_remainder() {
+        *sample = sample.clamp(min, max);
+    }
+}
+
+/// Replaces subnormal values with zero, since they're very slow to process on some CPUs
+pub fn flush_denormals<T: FloatSample>(samples: &mut [T]) {
+    #[cfg(feature = "portable-simd")]
+    if let Some(samples) = portable::as_f32_mut(samples) {
+        return portable::flush_denormals(samples);
+    }
+
+    let mut chunks = samples.chunks_exact_mut(LANES);
+
+    for chunk in chunks.by_ref() {
+        for sample in chunk {
+            *sample = if sample.abs() < T::MIN_POSITIVE { T::ZERO } else { *sample };
+        }
+    }
+
+    for sample in chunks.into_remainder() {
+        *sample = if sample.abs() < T::MIN_POSITIVE { T::ZERO } else { *sample };
+    }
+}
+
+/// Copies as much of `source` as fits into `target` and fills the rest of `target` with `value`
+pub fn copy_and_fill<T: Sample>(target: &mut [T], source: &[T], value: T) {
+    let copy_length = usize::min(target.len(), source.len());
+    let (copied, filled) = target.split_at_mut(copy_length);
+
+    copied.copy_from_slice(&source[..copy_length]);
+
+    #[cfg(feature = "portable-simd")]
+    if let (Some(filled), Some(&[value])) = (portable::as_f32_mut(filled), portable::as_f32(std::slice::from_ref(&value))) {
+        return portable::fill(filled, value);
+    }
+
+    let mut chunks = filled.chunks_exact_mut(LANES);
+
+    for chunk in chunks.by_ref() {
+        chunk.fill(value);
+    }
+
+    chunks.into_remainder().fill(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{abs_max, clamp, copy_and_fill, gain_ramp, mix_to, sum_difference, sum_of_squares};
+
+    // Longer than one chunk with a remainder
+    fn samples() -> Vec<f32> {
+        (0..19).map(|index| index as f32 - 9.5).collect()
+    }
+
+    #[test]
+    fn reductions() {
+        let samples = samples();
+
+        assert_eq!(abs_max(&samples), 9.5);
+        assert_eq!(sum_of_squares(&samples), samples.iter().map(|sample| sample * sample).sum::<f32>());
+    }
+
+    #[test]
+    fn ramp() {
+        let mut samples = vec![1.0; 19];
+        gain_ramp(&mut samples, 0.0, 19.0);
+
+        for (index, sample) in samples.iter().enumerate() {
+            assert!((sample - index as f32).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn mid_side_round_trip() {
+        let mut left = samples();
+        let mut right: Vec<f32> = samples().iter().map(|sample| sample * 0.5 + 1.0).collect();
+        let (original_left, original_right) = (left.clone(), right.clone());
+
+        sum_difference(&mut left, &mut right, 0.5);
+        assert_eq!(left[3], (original_left[3] + original_right[3]) * 0.5);
+
+        sum_difference(&mut left, &mut right, 1.0);
+        for (actual, expected) in left.iter().chain(right.iter()).zip(original_left.iter().chain(original_right.iter())) {
+            assert!((actual - expected).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn mix_to_remainder() {
+        let a = samples();
+        let b = vec![1.0; 19];
+        let mut target = vec![0.0; 19];
+
+        mix_to(&a, 2.0, &b, 3.0, &mut target);
+        assert_eq!(target[18], a[18] * 2.0 + 3.0);
+    }
+
+    #[test]
+    fn copy_and_fill_remainder() {
+        let source = samples();
+        let mut target = vec![0.0; 27];
+
+        copy_and_fill(&mut target, &source, 1.0);
+        assert_eq!(&target[..19], &source[..]);
+        assert!(target[19..].iter().all(|&sample| sample == 1.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn clamp_inverted_range() {
+        clamp(&mut [0.0f32; 0], 1.0, -1.0);
+    }
+}
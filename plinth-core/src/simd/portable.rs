@@ -0,0 +1,135 @@
+use std::{any::TypeId, iter::zip, simd::{cmp::SimdPartialOrd, num::SimdFloat, Select, Simd}};
+
+use crate::signals::sample::{FloatSample, Sample};
+
+use super::LANES;
+
+type Vector = Simd<f32, LANES>;
+
+pub(super) fn as_f32<T: Sample>(samples: &[T]) -> Option<&[f32]> {
+    // SAFETY: T is f32
+    (TypeId::of::<T>() == TypeId::of::<f32>())
+        .then(|| unsafe { std::slice::from_raw_parts(samples.as_ptr() as *const f32, samples.len()) })
+}
+
+pub(super) fn as_f32_mut<T: Sample>(samples: &mut [T]) -> Option<&mut [f32]> {
+    // SAFETY: T is f32
+    (TypeId::of::<T>() == TypeId::of::<f32>())
+        .then(|| unsafe { std::slice::from_raw_parts_mut(samples.as_mut_ptr() as *mut f32, samples.len()) })
+}
+
+pub(super) fn to_f32<T: FloatSample>(value: T) -> f32 {
+    value.to_f64() as f32
+}
+
+pub(super) fn from_f32<T: FloatSample>(value: f32) -> T {
+    T::from_f64(value as f64)
+}
+
+/// Applies `f` to every full vector of `samples` and `scalar` to the rest
+fn map(samples: &mut [f32], f: impl Fn(Vector) -> Vector, scalar: impl Fn(f32) -> f32) {
+    let (chunks, remainder) = samples.as_chunks_mut::<LANES>();
+
+    for chunk in chunks {
+        *chunk = f(Vector::from_array(*chunk)).to_array();
+    }
+
+    for sample in remainder {
+        *sample = scalar(*sample);
+    }
+}
+
+pub(super) fn scale(samples: &mut [f32], gain: f32) {
+    let gains = Vector::splat(gain);
+    map(samples, |vector| vector * gains, |sample| sample * gain);
+}
+
+pub(super) fn mix(target: &mut [f32], target_gain: f32, source: &[f32], source_gain: f32) {
+    let (target_gains, source_gains) = (Vector::splat(target_gain), Vector::splat(source_gain));
+
+    let (target_chunks, target_remainder) = target.as_chunks_mut::<LANES>();
+    let (source_chunks, source_remainder) = source.as_chunks::<LANES>();
+
+    for (target_chunk, source_chunk) in zip(target_chunks, source_chunks) {
+        let mixed = Vector::from_array(*target_chunk) * target_gains + Vector::from_array(*source_chunk) * source_gains;
+        *target_chunk = mixed.to_array();
+    }
+
+    for (target_sample, source_sample) in zip(target_remainder, source_remainder) {
+        *target_sample = *target_sample * target_gain + *source_sample * source_gain;
+    }
+}
+
+pub(super) fn mix_to(a: &[f32], a_gain: f32, b: &[f32], b_gain: f32, target: &mut [f32]) {
+    let (a_gains, b_gains) = (Vector::splat(a_gain), Vector::splat(b_gain));
+
+    let (a_chunks, a_remainder) = a.as_chunks::<LANES>();
+    let (b_chunks, b_remainder) = b.as_chunks::<LANES>();
+    let (target_chunks, target_remainder) = target.as_chunks_mut::<LANES>();
+
+    for ((a_chunk, b_chunk), target_chunk) in zip(zip(a_chunks, b_chunks), target_chunks) {
+        *target_chunk = (Vector::from_array(*a_chunk) * a_gains + Vector::from_array(*b_chunk) * b_gains).to_array();
+    }
+
+    for ((a_sample, b_sample), target_sample) in zip(zip(a_remainder, b_remainder), target_remainder) {
+        *target_sample = *a_sample * a_gain + *b_sample * b_gain;
+    }
+}
+
+pub(super) fn abs_max(samples: &[f32]) -> f32 {
+    let (chunks, remainder) = samples.as_chunks::<LANES>();
+
+    let maxima = chunks.iter()
+        .fold(Vector::splat(0.0), |maxima, chunk| maxima.simd_max(Vector::from_array(*chunk).abs()));
+
+    remainder.iter().fold(maxima.reduce_max(), |max, sample| max.max(sample.abs()))
+}
+
+pub(super) fn sum_of_squares(samples: &[f32]) -> f32 {
+    let (chunks, remainder) = samples.as_chunks::<LANES>();
+
+    let sums = chunks.iter()
+        .fold(Vector::splat(0.0), |sums, chunk| {
+            let vector = Vector::from_array(*chunk);
+            sums + vector * vector
+        });
+
+    sums.reduce_sum() + remainder.iter().map(|sample| sample * sample).sum::<f32>()
+}
+
+pub(super) fn sum_difference(a: &mut [f32], b: &mut [f32], gain: f32) {
+    let gains = Vector::splat(gain);
+
+    let (a_chunks, a_remainder) = a.as_chunks_mut::<LANES>();
+    let (b_chunks, b_remainder) = b.as_chunks_mut::<LANES>();
+
+    for (a_chunk, b_chunk) in zip(a_chunks, b_chunks) {
+        let (a_vector, b_vector) = (Vector::from_array(*a_chunk), Vector::from_array(*b_chunk));
+        *a_chunk = ((a_vector + b_vector) * gains).to_array();
+        *b_chunk = ((a_vector - b_vector) * gains).to_array();
+    }
+
+    for (a_sample, b_sample) in zip(a_remainder, b_remainder) {
+        (*a_sample, *b_sample) = ((*a_sample + *b_sample) * gain, (*a_sample - *b_sample) * gain);
+    }
+}
+
+pub(super) fn clamp(samples: &mut [f32], min: f32, max: f32) {
+    let (mins, maxs) = (Vector::splat(min), Vector::splat(max));
+    map(samples, |vector| vector.simd_clamp(mins, maxs), |sample| sample.clamp(min, max));
+}
+
+pub(super) fn flush_denormals(samples: &mut [f32]) {
+    let min_positive = Vector::splat(f32::MIN_POSITIVE);
+
+    map(
+        samples,
+        |vector| vector.abs().simd_lt(min_positive).select(Vector::splat(0.0), vector),
+        |sample| if sample.abs() < f32::MIN_POSITIVE { 0.0 } else { sample },
+    );
+}
+
+pub(super) fn fill(samples: &mut [f32], value: f32) {
+    let values = Vector::splat(value);
+    map(samples, |_| values, |_| value);
+}
@@ -0,0 +1,4 @@
+pub mod complex;
+pub mod fft;
+pub mod stft;
+pub mod window;
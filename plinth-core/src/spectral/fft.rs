@@ -0,0 +1,136 @@
+use std::f64::consts::TAU;
+
+use super::complex::Complex;
+
+/// In-place radix-2 FFT, all tables are allocated up front so transforms don't allocate
+pub struct Fft {
+    size: usize,
+    twiddles: Vec<Complex>,
+    bit_reverse: Vec<usize>,
+}
+
+impl Fft {
+    pub fn new(size: usize) -> Self {
+        assert!(size.is_power_of_two(), "FFT size must be a power of two, got {size}");
+
+        let twiddles = (0..size / 2)
+            .map(|index| {
+                let phase = -TAU * index as f64 / size as f64;
+                Complex::new(phase.cos() as _, phase.sin() as _)
+            })
+            .collect();
+
+        let bits = size.trailing_zeros();
+        let bit_reverse = (0..size)
+            .map(|index| if bits == 0 { 0 } else { index.reverse_bits() >> (usize::BITS - bits) })
+            .collect();
+
+        Self {
+            size,
+            twiddles,
+            bit_reverse,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn forward(&self, data: &mut [Complex]) {
+        self.transform(data, false);
+    }
+
+    /// Scaled by `1 / size`, so `inverse()` undoes `forward()`
+    pub fn inverse(&self, data: &mut [Complex]) {
+        self.transform(data, true);
+
+        let scale = 1.0 / self.size as f32;
+        for value in data.iter_mut() {
+            *value = value.scale(scale);
+        }
+    }
+
+    fn transform(&self, data: &mut [Complex], inverse: bool) {
+        assert_eq!(data.len(), self.size);
+
+        for (index, &reversed_index) in self.bit_reverse.iter().enumerate() {
+            if reversed_index > index {
+                data.swap(index, reversed_index);
+            }
+        }
+
+        let mut length = 2;
+        while length <= self.size {
+            let half_length = length / 2;
+            let twiddle_step = self.size / length;
+
+            for chunk in data.chunks_exact_mut(length) {
+                let (first, second) = chunk.split_at_mut(half_length);
+
+                for (index, (a, b)) in first.iter_mut().zip(second.iter_mut()).enumerate() {
+                    let twiddle = self.twiddles[index * twiddle_step];
+                    let twiddle = if inverse { twiddle.conj() } else { twiddle };
+
+                    let product = *b * twiddle;
+                    *b = *a - product;
+                    *a += product;
+                }
+            }
+
+            length *= 2;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spectral::complex::Complex;
+
+    use super::Fft;
+
+    const EPSILON: f32 = 1e-5;
+
+    #[test]
+    fn impulse() {
+        let fft = Fft::new(8);
+        let mut data = [Complex::ZERO; 8];
+        data[0] = Complex::new(1.0, 0.0);
+
+        fft.forward(&mut data);
+
+        for value in data {
+            assert!((value - Complex::new(1.0, 0.0)).magnitude() < EPSILON);
+        }
+    }
+
+    #[test]
+    fn sine_lands_in_its_bin() {
+        let fft = Fft::new(16);
+        let mut data: Vec<_> = (0..16)
+            .map(|index| Complex::new((std::f32::consts::TAU * 2.0 * index as f32 / 16.0).cos(), 0.0))
+            .collect();
+
+        fft.forward(&mut data);
+
+        for (bin, value) in data.iter().enumerate() {
+            let expected = if bin == 2 || bin == 14 { 8.0 } else { 0.0 };
+            assert!((value.magnitude() - expected).abs() < EPSILON, "bin {bin}: {value:?}");
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let fft = Fft::new(32);
+        let original: Vec<_> = (0..32)
+            .map(|index| Complex::new((index as f32 * 0.37).sin(), (index as f32 * 0.11).cos()))
+            .collect();
+
+        let mut data = original.clone();
+        fft.forward(&mut data);
+        fft.inverse(&mut data);
+
+        for (value, original) in data.iter().zip(original.iter()) {
+            assert!((*value - *original).magnitude() < EPSILON);
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use crate::signals::signal::SignalMut;
+
+use super::{complex::Complex, fft::Fft, window::Window};
+
+struct ChannelState {
+    input: Vec<f32>,
+    output: Vec<f32>,
+}
+
+/// Overlap-add short-time Fourier transform
+///
+/// The signal is split into windowed frames of `fft_size` samples every `hop_size` samples. Each frame's spectrum
+/// is handed to a callback for modification, then transformed back and overlap-added to the output, which is
+/// delayed by `latency()` samples. Everything is allocated up front so `process()` is real-time safe.
+pub struct Stft {
+    fft: Fft,
+    hop_size: usize,
+    window: Vec<f32>,
+    // Per frame position, the inverse of the squared windows overlapping there
+    normalization: Vec<f32>,
+    // Some position isn't covered by any window, so the input can't be reconstructed
+    uncovered: bool,
+
+    channels: Vec<ChannelState>,
+    // Samples collected towards the next frame
+    hop_position: usize,
+
+    frame: Vec<Complex>,
+}
+
+impl Stft {
+    /// Uses a Hann window, `process()` panics if that doesn't overlap enough for the hop size
+    pub fn new(channels: usize, fft_size: usize, hop_size: usize) -> Self {
+        assert!(hop_size > 0 && hop_size <= fft_size, "Hop size must be between 1 and the FFT size, got {hop_size}");
+
+        let mut stft = Self {
+            fft: Fft::new(fft_size),
+            hop_size,
+            window: vec![0.0; fft_size],
+            normalization: vec![0.0; fft_size],
+            uncovered: false,
+
+            channels: (0..channels)
+                .map(|_| ChannelState {
+                    input: vec![0.0; fft_size],
+                    output: vec![0.0; fft_size],
+                })
+                .collect(),
+            hop_position: 0,
+
+            frame: vec![Complex::ZERO; fft_size],
+        };
+
+        stft.set_window(Window::default());
+        stft
+    }
+
+    /// Panics if some position isn't covered by any window, like Hann without overlap
+    pub fn with_window(mut self, window: Window) -> Self {
+        self.set_window(window);
+        self.assert_covered();
+        self
+    }
+
+    pub fn fft_size(&self) -> usize {
+        self.fft.size()
+    }
+
+    pub fn hop_size(&self) -> usize {
+        self.hop_size
+    }
+
+    /// Number of bins in the spectra passed to the callback, from DC to Nyquist
+    pub fn bin_count(&self) -> usize {
+        self.fft_size() / 2 + 1
+    }
+
+    pub fn latency(&self) -> usize {
+        self.fft_size()
+    }
+
+    pub fn reset(&mut self) {
+        for channel in self.channels.iter_mut() {
+            channel.input.fill(0.0);
+            channel.output.fill(0.0);
+        }
+
+        self.hop_position = 0;
+    }
+
+    /// Processes `signal` in place, calling `callback` with the channel index and spectrum of every frame
+    pub fn process(&mut self, signal: &mut impl SignalMut, mut callback: impl FnMut(usize, &mut [Complex])) {
+        assert!(signal.channels() <= self.channels.len());
+        self.assert_covered();
+
+        let fft_size = self.fft_size();
+        let hop_size = self.hop_size;
+        let start_hop_position = self.hop_position;
+
+        for channel_index in 0..signal.channels() {
+            let samples = signal.channel_mut(channel_index);
+            let channel = &mut self.channels[channel_index];
+            let mut hop_position = start_hop_position;
+
+            for sample in samples.iter_mut() {
+                channel.input[fft_size - hop_size + hop_position] = *sample;
+                *sample = channel.output[hop_position];
+                hop_position += 1;
+
+                if hop_position == hop_size {
+                    hop_position = 0;
+
+                    // Analysis
+                    for ((frame_value, &input), &window) in self.frame.iter_mut().zip(channel.input.iter()).zip(self.window.iter()) {
+                        *frame_value = Complex::new(input * window, 0.0);
+                    }
+
+                    self.fft.forward(&mut self.frame);
+                    callback(channel_index, &mut self.frame[..fft_size / 2 + 1]);
+
+                    // The upper half mirrors the lower half for a real signal
+                    for bin in 1..fft_size / 2 {
+                        self.frame[fft_size - bin] = self.frame[bin].conj();
+                    }
+
+                    self.fft.inverse(&mut self.frame);
+
+                    // Synthesis
+                    channel.input.copy_within(hop_size.., 0);
+                    channel.output.copy_within(hop_size.., 0);
+                    channel.output[fft_size - hop_size..].fill(0.0);
+
+                    for (((output, frame_value), &window), &normalization) in channel.output.iter_mut()
+                        .zip(self.frame.iter())
+                        .zip(self.window.iter())
+                        .zip(self.normalization.iter())
+                    {
+                        *output += frame_value.re * window * normalization;
+                    }
+                }
+            }
+        }
+
+        self.hop_position = (start_hop_position + signal.len()) % hop_size;
+    }
+
+    fn set_window(&mut self, window: Window) {
+        window.fill(&mut self.window);
+
+        // The window is applied twice, so divide by the squared windows overlapping at each position.
+        // Frames start every hop, so that sum only depends on the position modulo the hop size.
+        let hop_size = self.hop_size;
+        let mut window_sums = vec![0.0; hop_size];
+        for (index, value) in self.window.iter().enumerate() {
+            window_sums[index % hop_size] += value * value;
+        }
+
+        let max_sum = window_sums.iter().copied().fold(0.0, f32::max);
+        self.uncovered = window_sums.iter().any(|&sum| sum <= max_sum * 1e-6);
+
+        for (index, normalization) in self.normalization.iter_mut().enumerate() {
+            *normalization = 1.0 / window_sums[index % hop_size];
+        }
+    }
+
+    fn assert_covered(&self) {
+        assert!(!self.uncovered, "The window doesn't overlap enough for FFT size {} and hop size {}", self.fft_size(), self.hop_size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{buffers::buffer::Buffer, signals::{signal::{Signal, SignalMut}, signal_base::SignalBase}};
+
+    use super::{Stft, Window};
+
+    const EPSILON: f32 = 1e-4;
+
+    fn test_signal(length: usize) -> Buffer {
+        let mut buffer = Buffer::new(2, length);
+        for (index, sample) in buffer.channel_mut(0).iter_mut().enumerate() {
+            *sample = (index as f32 * 0.05).sin();
+        }
+        for (index, sample) in buffer.channel_mut(1).iter_mut().enumerate() {
+            *sample = (index as f32 * 0.13).cos() * 0.5;
+        }
+
+        buffer
+    }
+
+    fn assert_delayed(output: &Buffer, input: &Buffer, latency: usize) {
+        for channel in 0..2 {
+            let output = &output.channel(channel)[latency..];
+            let input = &input.channel(channel)[..output.len()];
+
+            for (output, input) in output.iter().zip(input.iter()) {
+                assert!((output - input).abs() < EPSILON, "{output} != {input}");
+            }
+        }
+    }
+
+    #[test]
+    fn reconstructs_delayed_input() {
+        let configurations = [
+            (Window::Hann, 64, 16),
+            (Window::Hann, 32, 8),
+            (Window::Blackman, 64, 8),
+            (Window::Rectangular, 16, 16),
+            // Not constant overlap-add
+            (Window::Hamming, 64, 24),
+            (Window::Hann, 64, 32),
+        ];
+
+        for (window, fft_size, hop_size) in configurations {
+            let input = test_signal(400);
+            let mut output = input.clone();
+
+            let mut stft = Stft::new(2, fft_size, hop_size).with_window(window);
+
+            // Odd block sizes to exercise frames spanning blocks
+            let mut offset = 0;
+            for block_size in [7, 100, 1, 33].into_iter().cycle() {
+                if offset >= output.len() {
+                    break;
+                }
+
+                let end = usize::min(offset + block_size, output.len());
+                stft.process(&mut output.slice_mut(offset..end), |_, _| {});
+                offset = end;
+            }
+
+            assert_delayed(&output, &input, stft.latency());
+        }
+    }
+
+    #[test]
+    fn spectra_per_channel() {
+        let mut stft = Stft::new(2, 16, 8);
+        let mut buffer = test_signal(32);
+
+        let mut frames = [0; 2];
+        stft.process(&mut buffer, |channel, spectrum| {
+            assert_eq!(spectrum.len(), 9);
+            frames[channel] += 1;
+        });
+
+        assert_eq!(frames, [4, 4]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn window_without_overlap() {
+        Stft::new(1, 16, 16).with_window(Window::Hann);
+    }
+}
@@ -0,0 +1,28 @@
+use std::f64::consts::TAU;
+
+/// Periodic window functions, as used for overlapping spectral frames
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Window {
+    Rectangular,
+    #[default]
+    Hann,
+    Hamming,
+    Blackman,
+}
+
+impl Window {
+    pub fn fill(&self, window: &mut [f32]) {
+        let length = window.len() as f64;
+
+        for (index, value) in window.iter_mut().enumerate() {
+            let phase = TAU * index as f64 / length;
+
+            *value = match self {
+                Window::Rectangular => 1.0,
+                Window::Hann => 0.5 - 0.5 * phase.cos(),
+                Window::Hamming => 0.54 - 0.46 * phase.cos(),
+                Window::Blackman => 0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos(),
+            } as f32;
+        }
+    }
+}
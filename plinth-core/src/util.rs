@@ -1,2 +1,3 @@
 pub mod ptr;
 pub mod range;
+pub(crate) mod window;
@@ -0,0 +1,29 @@
+use std::f64::consts::PI;
+
+/// Normalized sinc, `sin(πx) / πx`
+pub(crate) fn sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Kaiser window at `position` in `-1.0..=1.0`
+pub(crate) fn kaiser(position: f64, beta: f64) -> f64 {
+    bessel_i0(beta * (1.0 - position * position).sqrt()) / bessel_i0(beta)
+}
+
+// Zeroth order modified Bessel function of the first kind
+fn bessel_i0(x: f64) -> f64 {
+    let half_x = x / 2.0;
+    let mut term = 1.0;
+    let mut sum = 1.0;
+
+    for k in 1..50 {
+        term *= half_x / k as f64;
+        sum += term * term;
+    }
+
+    sum
+}
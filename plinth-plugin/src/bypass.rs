@@ -0,0 +1,255 @@
+use plinth_core::{buffers::buffer::Buffer, signals::{signal::{Signal, SignalMut}, signal_base::SignalBase}};
+
+use crate::{plugin::reported_latency, Event, ParameterId, Parameters, Plugin, ProcessState, Processor, ProcessorConfig, Transport};
+
+const FADE_LENGTH_SECONDS: f64 = 0.01;
+
+/// Crossfades between the processed signal and the dry signal delayed by the plugin latency,
+/// used by the format wrappers and plinth-render when `Plugin::AUTOMATIC_BYPASS` is set
+pub struct Bypass {
+    parameter_id: ParameterId,
+    bypassed: bool,
+    // Where the bypass state first changed in the current block, and what it was before
+    change: Option<(usize, bool)>,
+
+    delay_line: Buffer,
+    delay_position: usize,
+    dry: Buffer,
+
+    fade_length: usize,
+    // Zero when fully processed, fade_length when fully bypassed
+    fade_position: usize,
+}
+
+impl Bypass {
+    pub fn new(parameter_id: ParameterId, bypassed: bool, channels: usize, latency: usize, max_block_size: usize, sample_rate: f64) -> Self {
+        let fade_length = usize::max(1, (FADE_LENGTH_SECONDS * sample_rate) as usize);
+
+        Self {
+            parameter_id,
+            bypassed,
+            change: None,
+
+            delay_line: Buffer::new(channels, latency),
+            delay_position: 0,
+            dry: Buffer::with_capacity(channels, max_block_size),
+
+            fade_length,
+            fade_position: if bypassed { fade_length } else { 0 },
+        }
+    }
+
+    /// Returns `None` unless the plugin uses `AUTOMATIC_BYPASS` and has a bypass parameter
+    pub fn for_plugin<P: Plugin>(plugin: &P, config: &ProcessorConfig, channels: usize) -> Option<Self> {
+        if !P::AUTOMATIC_BYPASS {
+            return None;
+        }
+
+        let (parameter_id, bypassed) = plugin.with_parameters(|parameters| {
+            parameters.ids().iter()
+                .map(|&id| parameters.get(id).unwrap())
+                .find(|parameter| parameter.info().is_bypass())
+                .map(|parameter| (parameter.info().id(), parameter.normalized_value() >= 0.5))
+        })?;
+
+        Some(Self::new(parameter_id, bypassed, channels, reported_latency(plugin) as _, config.max_block_size, config.sample_rate))
+    }
+
+    pub fn reset(&mut self) {
+        self.delay_line.fill(0.0);
+        self.delay_position = 0;
+        self.change = None;
+        self.fade_position = if self.bypassed { self.fade_length } else { 0 };
+    }
+
+    /// Call for every event of the block before `mix()`, the fade starts at the event's sample offset
+    pub fn process_event(&mut self, event: &Event) {
+        if let &Event::ParameterValue { sample_offset, id, value } = event && id == self.parameter_id {
+            let bypassed = value >= 0.5;
+
+            if bypassed != self.bypassed && self.change.is_none() {
+                self.change = Some((sample_offset, self.bypassed));
+            }
+
+            self.bypassed = bypassed;
+        }
+    }
+
+    /// The processor output isn't heard at all, so it doesn't need to run
+    pub fn is_fully_bypassed(&self) -> bool {
+        self.bypassed && self.fade_position == self.fade_length
+    }
+
+    /// Whether the output differs from what the processor produced
+    pub fn is_engaged(&self) -> bool {
+        self.bypassed || self.fade_position > 0
+    }
+
+    /// Runs the processor unless it's fully bypassed and `process_while_bypassed` is off,
+    /// then crossfades its output with the dry signal
+    pub fn process(
+        &mut self,
+        processor: &mut impl Processor,
+        process_while_bypassed: bool,
+        buffer: &mut impl SignalMut,
+        aux: Option<&impl Signal>,
+        transport: Option<Transport>,
+        mut events: impl Iterator<Item = Event>,
+    ) -> ProcessState
+    {
+        self.store_dry(buffer);
+
+        let state = if self.is_fully_bypassed() && !process_while_bypassed {
+            processor.process_events(events.by_ref().inspect(|event| self.process_event(event)));
+            events.for_each(|event| self.process_event(&event));
+
+            if self.bypassed {
+                ProcessState::Normal
+            } else {
+                // The events switched bypass off, so the processor is needed again for the fade in
+                processor.process(buffer, aux, transport, std::iter::empty())
+            }
+        } else {
+            let state = processor.process(buffer, aux, transport, events.by_ref().inspect(|event| self.process_event(event)));
+
+            // The processor doesn't have to consume every event
+            events.for_each(|event| self.process_event(&event));
+
+            state
+        };
+
+        self.mix(buffer);
+
+        state
+    }
+
+    /// Call with the unprocessed input before processing the block
+    pub fn store_dry(&mut self, input: &impl Signal) {
+        self.dry.resize(input.len());
+
+        let latency = self.delay_line.len();
+        if latency == 0 {
+            self.dry.copy_from_signal(input);
+            return;
+        }
+
+        for channel in 0..self.dry.channels() {
+            let input_channel = input.channel(channel);
+            let delay_channel = self.delay_line.channel_mut(channel);
+            let dry_channel = self.dry.channel_mut(channel);
+
+            let mut position = self.delay_position;
+            for (&input_sample, dry_sample) in input_channel.iter().zip(dry_channel.iter_mut()) {
+                *dry_sample = delay_channel[position];
+                delay_channel[position] = input_sample;
+                position = (position + 1) % latency;
+            }
+        }
+
+        self.delay_position = (self.delay_position + input.len()) % latency;
+    }
+
+    /// Crossfades the processed block in `output` with the dry signal stored by `store_dry()`
+    pub fn mix(&mut self, output: &mut impl SignalMut) {
+        let fade_length = self.fade_length;
+        let target_position = |bypassed| if bypassed { fade_length } else { 0 };
+        let end_target = target_position(self.bypassed);
+
+        let Some((change_offset, bypassed_before)) = self.change.take() else {
+            if self.fade_position == end_target {
+                if self.bypassed {
+                    output.copy_from_signal(&self.dry);
+                }
+
+                return;
+            }
+
+            self.fade(output, 0, output.len(), end_target);
+            return;
+        };
+
+        let change_offset = usize::min(change_offset, output.len());
+        self.fade(output, 0, change_offset, target_position(bypassed_before));
+        self.fade(output, change_offset, output.len(), end_target);
+    }
+
+    /// Moves the fade towards `target_position` over `start..end` of the block
+    fn fade(&mut self, output: &mut impl SignalMut, start: usize, end: usize, target_position: usize) {
+        let start_position = self.fade_position;
+
+        for (output_channel, dry_channel) in output.iter_channels_mut().zip(self.dry.iter_channels()) {
+            let mut position = start_position;
+
+            for (output_sample, &dry_sample) in output_channel[start..end].iter_mut().zip(dry_channel[start..end].iter()) {
+                if position < target_position {
+                    position += 1;
+                } else if position > target_position {
+                    position -= 1;
+                }
+
+                let dry_gain = position as f32 / self.fade_length as f32;
+                *output_sample = dry_gain * dry_sample + (1.0 - dry_gain) * *output_sample;
+            }
+        }
+
+        let samples = end - start;
+        self.fade_position = if start_position < target_position {
+            usize::min(start_position + samples, target_position)
+        } else {
+            usize::max(start_position.saturating_sub(samples), target_position)
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plinth_core::{buffers::buffer::Buffer, signals::signal::{Signal, SignalMut}};
+
+    use crate::Event;
+
+    use super::Bypass;
+
+    #[test]
+    fn delayed_dry_signal() {
+        // 100 Hz gives a fade length of one sample
+        let mut bypass = Bypass::new(0, true, 1, 2, 4, 100.0);
+        let mut buffer = Buffer::from(vec![vec![1.0, 2.0, 3.0, 4.0]]);
+
+        bypass.store_dry(&buffer);
+        buffer.fill(0.0);
+        bypass.mix(&mut buffer);
+
+        assert_eq!(buffer.channel(0), [0.0, 0.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn crossfade() {
+        let mut bypass = Bypass::new(0, false, 1, 0, 8, 400.0);
+        assert!(!bypass.is_fully_bypassed());
+
+        bypass.process_event(&Event::ParameterValue { sample_offset: 0, id: 0, value: 1.0 });
+
+        let mut buffer = Buffer::from(vec![vec![1.0; 8]]);
+        bypass.store_dry(&buffer);
+        buffer.fill(0.0);
+        bypass.mix(&mut buffer);
+
+        assert_eq!(buffer.channel(0), [0.25, 0.5, 0.75, 1.0, 1.0, 1.0, 1.0, 1.0]);
+        assert!(bypass.is_fully_bypassed());
+    }
+
+    #[test]
+    fn crossfade_starts_at_event_offset() {
+        let mut bypass = Bypass::new(0, false, 1, 0, 8, 400.0);
+
+        bypass.process_event(&Event::ParameterValue { sample_offset: 4, id: 0, value: 1.0 });
+
+        let mut buffer = Buffer::from(vec![vec![1.0; 8]]);
+        bypass.store_dry(&buffer);
+        buffer.fill(0.0);
+        bypass.mix(&mut buffer);
+
+        assert_eq!(buffer.channel(0), [0.0, 0.0, 0.0, 0.0, 0.25, 0.5, 0.75, 1.0]);
+        assert!(bypass.is_fully_bypassed());
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{clap::Feature, vst3::Subcategory};
+
+/// Format independent plugin category, mapped to CLAP features and VST3 subcategories
+///
+/// Every plugin needs at least one main category: `Effect`, `Instrument`, `NoteEffect` or `Analyzer`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    Effect,
+    Instrument,
+    NoteEffect,
+    Analyzer,
+
+    Chorus,
+    Compressor,
+    DeEsser,
+    Delay,
+    Distortion,
+    Drum,
+    Equalizer,
+    Expander,
+    Filter,
+    Flanger,
+    Gate,
+    Limiter,
+    Mastering,
+    Phaser,
+    PitchCorrection,
+    PitchShifter,
+    Restoration,
+    Reverb,
+    Sampler,
+    Synthesizer,
+    TransientShaper,
+    Tremolo,
+    Utility,
+
+    Ambisonic,
+    Mono,
+    Stereo,
+    Surround,
+}
+
+impl Category {
+    pub const fn is_main(&self) -> bool {
+        matches!(self, Category::Effect | Category::Instrument | Category::NoteEffect | Category::Analyzer)
+    }
+
+    pub const fn feature(&self) -> Feature {
+        match self {
+            Category::Effect => Feature::AudioEffect,
+            Category::Instrument => Feature::Instrument,
+            Category::NoteEffect => Feature::NoteEffect,
+            Category::Analyzer => Feature::Analyzer,
+
+            Category::Chorus => Feature::Chorus,
+            Category::Compressor => Feature::Compressor,
+            Category::DeEsser => Feature::DeEsser,
+            Category::Delay => Feature::Delay,
+            Category::Distortion => Feature::Distortion,
+            Category::Drum => Feature::Drum,
+            Category::Equalizer => Feature::Equalizer,
+            Category::Expander => Feature::Expander,
+            Category::Filter => Feature::Filter,
+            Category::Flanger => Feature::Flanger,
+            Category::Gate => Feature::Gate,
+            Category::Limiter => Feature::Limiter,
+            Category::Mastering => Feature::Mastering,
+            Category::Phaser => Feature::Phaser,
+            Category::PitchCorrection => Feature::PitchCorrection,
+            Category::PitchShifter => Feature::PitchShifter,
+            Category::Restoration => Feature::Restoration,
+            Category::Reverb => Feature::Reverb,
+            Category::Sampler => Feature::Sampler,
+            Category::Synthesizer => Feature::Synthesizer,
+            Category::TransientShaper => Feature::TransientShaper,
+            Category::Tremolo => Feature::Tremolo,
+            Category::Utility => Feature::Utility,
+
+            Category::Ambisonic => Feature::Ambisonic,
+            Category::Mono => Feature::Mono,
+            Category::Stereo => Feature::Stereo,
+            Category::Surround => Feature::Surround,
+        }
+    }
+
+    /// VST3 has no note effects or standalone analyzers, so those are reported as effects
+    pub const fn subcategories(&self) -> &'static [Subcategory] {
+        match self {
+            Category::Effect => &[Subcategory::Fx],
+            Category::Instrument => &[Subcategory::Instrument],
+            Category::NoteEffect => &[Subcategory::Fx],
+            Category::Analyzer => &[Subcategory::Fx, Subcategory::Analyzer],
+
+            Category::Chorus
+            | Category::Flanger
+            | Category::Phaser
+            | Category::Tremolo => &[Subcategory::Modulation],
+
+            Category::Compressor
+            | Category::DeEsser
+            | Category::Expander
+            | Category::Gate
+            | Category::Limiter
+            | Category::TransientShaper => &[Subcategory::Dynamics],
+
+            Category::Delay => &[Subcategory::Delay],
+            Category::Distortion => &[Subcategory::Distortion],
+            Category::Drum => &[Subcategory::Drum],
+            Category::Equalizer => &[Subcategory::Eq],
+            Category::Filter => &[Subcategory::Filter],
+            Category::Mastering => &[Subcategory::Mastering],
+            Category::PitchCorrection | Category::PitchShifter => &[Subcategory::PitchShift],
+            Category::Restoration => &[Subcategory::Restoration],
+            Category::Reverb => &[Subcategory::Reverb],
+            Category::Sampler => &[Subcategory::Sampler],
+            Category::Synthesizer => &[Subcategory::Synth],
+            Category::Utility => &[Subcategory::Tools],
+
+            Category::Ambisonic => &[Subcategory::Ambisonics],
+            Category::Mono => &[Subcategory::Mono],
+            Category::Stereo => &[Subcategory::Stereo],
+            Category::Surround => &[Subcategory::Surround],
+        }
+    }
+}
@@ -4,6 +4,7 @@ pub enum Error {
     ParameterRangeError,
     SerializationError,
     IoError(std::io::Error),
+    LoggerError,
 }
 
 impl From<std::io::Error> for Error {
@@ -11,3 +12,9 @@ impl From<std::io::Error> for Error {
         Self::IoError(error)
     }
 }
+
+impl From<log::SetLoggerError> for Error {
+    fn from(_: log::SetLoggerError) -> Self {
+        Self::LoggerError
+    }
+}
@@ -1,5 +1,4 @@
-use std::marker::PhantomData;
-
+use std::{iter::Peekable, marker::PhantomData};
 
 use plinth_core::signals::{signal::SignalMut, slice::SignalSliceMut};
 
@@ -10,6 +9,7 @@ use crate::parameters::ParameterId;
 pub enum Event {
     // Note events
     NoteOn {
+        sample_offset: usize,
         channel: i16,
         key: i16,
         note: i32,
@@ -17,6 +17,7 @@ pub enum Event {
     },
 
     NoteOff {
+        sample_offset: usize,
         channel: i16,
         key: i16,
         note: i32,
@@ -24,6 +25,7 @@ pub enum Event {
     },
 
     PitchBend {
+        sample_offset: usize,
         channel: i16,
         key: i16,
         note: i32,
@@ -61,12 +63,67 @@ impl Event {
         SignalSplitter::new(signal, events)
     }
 
+    /// Merges two event streams that are each ordered by sample offset, preferring `first` on ties
+    pub fn merge_in_time_order<A, B>(first: A, second: B) -> EventMerger<A, B>
+    where
+        A: Iterator<Item = Event>,
+        B: Iterator<Item = Event>,
+    {
+        EventMerger {
+            first: first.peekable(),
+            second: second.peekable(),
+        }
+    }
+
+    /// Has no effect on parameter gestures, which aren't tied to a sample
+    pub fn set_sample_offset(&mut self, offset: usize) {
+        match self {
+            Event::NoteOn { sample_offset, .. } |
+            Event::NoteOff { sample_offset, .. } |
+            Event::PitchBend { sample_offset, .. } |
+            Event::ParameterValue { sample_offset, .. } |
+            Event::ParameterModulation { sample_offset, .. } => *sample_offset = offset,
+
+            Event::StartParameterChange { .. } |
+            Event::EndParameterChange { .. } => {},
+        }
+    }
+
     pub fn sample_offset(&self) -> usize {
         match self {
+            Event::NoteOn { sample_offset, .. } => *sample_offset,
+            Event::NoteOff { sample_offset, .. } => *sample_offset,
+            Event::PitchBend { sample_offset, .. } => *sample_offset,
             Event::ParameterValue { sample_offset, .. } => *sample_offset,
             Event::ParameterModulation { sample_offset, .. } => *sample_offset,
 
-            _ => 0
+            Event::StartParameterChange { .. } |
+            Event::EndParameterChange { .. } => 0,
+        }
+    }
+}
+
+pub struct EventMerger<A, B>
+where
+    A: Iterator<Item = Event>,
+    B: Iterator<Item = Event>,
+{
+    first: Peekable<A>,
+    second: Peekable<B>,
+}
+
+impl<A, B> Iterator for EventMerger<A, B>
+where
+    A: Iterator<Item = Event>,
+    B: Iterator<Item = Event>,
+{
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.first.peek(), self.second.peek()) {
+            (Some(first), Some(second)) if second.sample_offset() < first.sample_offset() => self.second.next(),
+            (Some(_), _) => self.first.next(),
+            (None, _) => self.second.next(),
         }
     }
 }
@@ -123,17 +180,74 @@ where
             };
     
             match next_event {
-                Event::ParameterValue { sample_offset, .. } |
-                Event::ParameterModulation { sample_offset, .. } => {
-                    let sample_offset = usize::min(sample_offset, signal.len());
+                // Gestures don't affect the audio
+                Event::StartParameterChange { .. } |
+                Event::EndParameterChange { .. } => { continue; },
+
+                _ => {
+                    // Events arriving out of order are applied at the current position
+                    let sample_offset = next_event.sample_offset().clamp(self.offset, signal.len());
 
                     let result = (signal.slice_mut(self.offset..sample_offset), Some(next_event));
                     self.offset = sample_offset;
                     return Some(result);
                 },
-    
-                _ => { continue; },
             }    
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use plinth_core::{buffers::buffer::Buffer, signals::signal_base::SignalBase};
+
+    use super::Event;
+
+    fn note_on(sample_offset: usize) -> Event {
+        Event::NoteOn { sample_offset, channel: 0, key: 60, note: -1, velocity: 1.0 }
+    }
+
+    fn parameter_value(sample_offset: usize) -> Event {
+        Event::ParameterValue { sample_offset, id: 0, value: 0.5 }
+    }
+
+    #[test]
+    fn split_at_note_and_parameter_events() {
+        let mut buffer = Buffer::new(2, 10);
+        let events = [
+            note_on(3),
+            Event::StartParameterChange { id: 0 },
+            parameter_value(6),
+        ];
+
+        let splits: Vec<_> = Event::split_signal_at_events(&mut buffer, events.into_iter())
+            .map(|(slice, event)| (slice.len(), event.map(|event| event.sample_offset())))
+            .collect();
+
+        assert_eq!(splits, [(3, Some(3)), (3, Some(6)), (4, None)]);
+    }
+
+    #[test]
+    fn split_clamps_out_of_order_events() {
+        let mut buffer = Buffer::new(1, 10);
+        let events = [note_on(5), note_on(2), note_on(20)];
+
+        let splits: Vec<_> = Event::split_signal_at_events(&mut buffer, events.into_iter())
+            .map(|(slice, _)| slice.len())
+            .collect();
+
+        assert_eq!(splits, [5, 0, 5]);
+    }
+
+    #[test]
+    fn merge_in_time_order() {
+        let host_events = [note_on(0), note_on(4), parameter_value(8)];
+        let editor_events = [parameter_value(0), parameter_value(6)];
+
+        let merged: Vec<_> = Event::merge_in_time_order(host_events.into_iter(), editor_events.into_iter())
+            .map(|event| (event.sample_offset(), matches!(event, Event::NoteOn { .. })))
+            .collect();
+
+        assert_eq!(merged, [(0, true), (0, false), (4, true), (6, false), (8, false)]);
+    }
+}
@@ -10,6 +10,8 @@ pub enum PluginFormat {
     Auv3,
     Clap,
     Vst3,
+    /// Plugin type driven directly from Rust without a format wrapper, for example by plinth-render
+    Native,
 }
 
 impl Display for PluginFormat {
@@ -18,6 +20,7 @@ impl Display for PluginFormat {
             PluginFormat::Auv3 => f.write_str("AUv3"),
             PluginFormat::Clap => f.write_str("CLAP"),
             PluginFormat::Vst3 => f.write_str("VST3"),
+            PluginFormat::Native => f.write_str("Native"),
         }
     }
 }
@@ -5,7 +5,9 @@
 mod au_render_event;
 mod event;
 mod host;
+mod host_bundle;
 mod macros;
+mod main_queue;
 mod parameters;
 mod plugin;
 mod reader;
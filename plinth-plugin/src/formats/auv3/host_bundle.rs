@@ -0,0 +1,76 @@
+use std::ffi::{c_char, c_void, CStr};
+
+type CFStringRef = *const c_void;
+
+const K_CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+const MAX_STRING_LENGTH: usize = 256;
+
+#[link(name = "CoreFoundation", kind = "framework")]
+unsafe extern "C" {
+    static kCFBundleNameKey: CFStringRef;
+
+    fn CFBundleCopyBundleURL(bundle: *const c_void) -> *const c_void;
+    fn CFBundleGetMainBundle() -> *const c_void;
+    fn CFBundleGetValueForInfoDictionaryKey(bundle: *const c_void, key: CFStringRef) -> *const c_void;
+    fn CFGetTypeID(cf: *const c_void) -> usize;
+    fn CFRelease(cf: *const c_void);
+    fn CFStringCreateWithCString(allocator: *const c_void, string: *const c_char, encoding: u32) -> CFStringRef;
+    fn CFStringGetCString(string: CFStringRef, buffer: *mut c_char, size: isize, encoding: u32) -> u8;
+    fn CFStringGetTypeID() -> usize;
+    fn CFURLCopyPathExtension(url: *const c_void) -> CFStringRef;
+}
+
+/// Name and version of the app the plugin is loaded into
+///
+/// `None` when the plugin runs out of process, where the main bundle is the plugin's own app extension.
+pub(super) fn host_name_and_version() -> Option<(Option<String>, Option<String>)> {
+    unsafe {
+        let bundle = CFBundleGetMainBundle();
+        if bundle.is_null() {
+            return None;
+        }
+
+        let url = CFBundleCopyBundleURL(bundle);
+        if !url.is_null() {
+            let extension = CFURLCopyPathExtension(url);
+            let is_app_extension = !extension.is_null() && string_from_cf(extension).as_deref() == Some("appex");
+
+            if !extension.is_null() {
+                CFRelease(extension);
+            }
+            CFRelease(url);
+
+            if is_app_extension {
+                return None;
+            }
+        }
+
+        let name = info_string(bundle, kCFBundleNameKey);
+
+        let version_key = CFStringCreateWithCString(std::ptr::null(), c"CFBundleShortVersionString".as_ptr(), K_CF_STRING_ENCODING_UTF8);
+        let version = info_string(bundle, version_key);
+        CFRelease(version_key);
+
+        Some((name, version))
+    }
+}
+
+unsafe fn info_string(bundle: *const c_void, key: CFStringRef) -> Option<String> {
+    // Not owned, so not released
+    let value = unsafe { CFBundleGetValueForInfoDictionaryKey(bundle, key) };
+    if value.is_null() || unsafe { CFGetTypeID(value) != CFStringGetTypeID() } {
+        return None;
+    }
+
+    unsafe { string_from_cf(value) }
+}
+
+unsafe fn string_from_cf(string: CFStringRef) -> Option<String> {
+    let mut buffer = [0 as c_char; MAX_STRING_LENGTH];
+    if unsafe { CFStringGetCString(string, buffer.as_mut_ptr(), buffer.len() as _, K_CF_STRING_ENCODING_UTF8) } == 0 {
+        return None;
+    }
+
+    let string = unsafe { CStr::from_ptr(buffer.as_ptr()) };
+    Some(string.to_string_lossy().into_owned())
+}
@@ -0,0 +1,126 @@
+use std::{ffi::c_void, sync::{atomic::{AtomicBool, Ordering}, Arc, Mutex, Weak}};
+
+use crate::{main_thread::MAIN_THREAD_TIMER_MILLISECONDS, MainThreadWaker, Plugin};
+
+#[repr(C)]
+struct Opaque {
+    _data: [u8; 0],
+}
+
+const DISPATCH_TIME_NOW: u64 = 0;
+
+// Part of libSystem
+unsafe extern "C" {
+    static _dispatch_main_q: Opaque;
+    static _dispatch_source_type_timer: Opaque;
+
+    fn dispatch_async_f(queue: *mut c_void, context: *mut c_void, work: unsafe extern "C" fn(*mut c_void));
+    fn dispatch_release(object: *mut c_void);
+    fn dispatch_resume(object: *mut c_void);
+    fn dispatch_set_context(object: *mut c_void, context: *mut c_void);
+    fn dispatch_source_cancel(source: *mut c_void);
+    fn dispatch_source_create(source_type: *const c_void, handle: usize, mask: usize, queue: *mut c_void) -> *mut c_void;
+    fn dispatch_source_set_cancel_handler_f(source: *mut c_void, handler: unsafe extern "C" fn(*mut c_void));
+    fn dispatch_source_set_event_handler_f(source: *mut c_void, handler: unsafe extern "C" fn(*mut c_void));
+    fn dispatch_source_set_timer(source: *mut c_void, start: u64, interval: u64, leeway: u64);
+    fn dispatch_time(when: u64, delta: i64) -> u64;
+}
+
+fn main_queue() -> *mut c_void {
+    &raw const _dispatch_main_q as *mut c_void
+}
+
+/// The plugin as seen from the main queue
+struct Target<P: Plugin> {
+    plugin: Weak<Mutex<P>>,
+    wake_pending: AtomicBool,
+}
+
+// SAFETY: the plugin is only reached on the main queue, and through its mutex like every other wrapper call
+unsafe impl<P: Plugin> Send for Target<P> {}
+unsafe impl<P: Plugin> Sync for Target<P> {}
+
+impl<P: Plugin> Target<P> {
+    fn on_main_thread(&self) {
+        // Skip this round if another thread is using the plugin
+        if let Some(plugin) = self.plugin.upgrade() && let Ok(mut plugin) = plugin.try_lock() {
+            plugin.on_main_thread();
+        }
+    }
+
+    unsafe extern "C" fn on_timer(context: *mut c_void) {
+        let target = unsafe { &*(context as *const Self) };
+        target.on_main_thread();
+    }
+
+    unsafe extern "C" fn on_wake(context: *mut c_void) {
+        let target = unsafe { Arc::from_raw(context as *const Self) };
+        target.wake_pending.store(false, Ordering::Release);
+        target.on_main_thread();
+    }
+
+    unsafe extern "C" fn on_cancel(context: *mut c_void) {
+        drop(unsafe { Box::from_raw(context as *mut Self) });
+    }
+}
+
+/// Calls `Plugin::on_main_thread()` on the main dispatch queue, regularly and whenever the waker wakes
+///
+/// Can be created and dropped on any thread.
+pub(super) struct MainQueueTimer {
+    source: *mut c_void,
+}
+
+impl MainQueueTimer {
+    pub(super) fn new<P: Plugin + 'static>(plugin: Weak<Mutex<P>>) -> Self {
+        let target = Box::new(Target {
+            plugin,
+            wake_pending: Default::default(),
+        });
+
+        let interval = MAIN_THREAD_TIMER_MILLISECONDS * 1_000_000;
+
+        unsafe {
+            let source = dispatch_source_create(&raw const _dispatch_source_type_timer as _, 0, 0, main_queue());
+            assert!(!source.is_null());
+
+            dispatch_set_context(source, Box::into_raw(target) as _);
+            dispatch_source_set_event_handler_f(source, Target::<P>::on_timer);
+            dispatch_source_set_cancel_handler_f(source, Target::<P>::on_cancel);
+            dispatch_source_set_timer(source, dispatch_time(DISPATCH_TIME_NOW, interval as _), interval, interval / 10);
+            dispatch_resume(source);
+
+            Self {
+                source,
+            }
+        }
+    }
+
+    pub(super) fn waker<P: Plugin + 'static>(plugin: Weak<Mutex<P>>) -> MainThreadWaker {
+        let target = Arc::new(Target {
+            plugin,
+            wake_pending: Default::default(),
+        });
+
+        MainThreadWaker::new(move || {
+            // Only one wake is queued at a time
+            if !target.wake_pending.swap(true, Ordering::AcqRel) {
+                unsafe { dispatch_async_f(main_queue(), Arc::into_raw(target.clone()) as _, Target::<P>::on_wake) };
+            }
+        })
+    }
+}
+
+impl Drop for MainQueueTimer {
+    fn drop(&mut self) {
+        // The cancel handler frees the target once the queue is done with it
+        unsafe {
+            dispatch_source_cancel(self.source);
+            dispatch_release(self.source);
+        }
+    }
+}
+
+// SAFETY: dispatch sources are thread safe
+unsafe impl Send for MainQueueTimer {}
+unsafe impl Sync for MainQueueTimer {}
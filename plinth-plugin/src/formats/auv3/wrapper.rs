@@ -5,18 +5,20 @@ use plinth_core::{signals::ptr_signal::{PtrSignal, PtrSignalMut}, util::ptr::{an
 use portable_atomic::AtomicF64;
 use raw_window_handle::{AppKitWindowHandle, RawWindowHandle};
 
-use crate::{formats::PluginFormat, host::HostInfo, Editor, Event, ParameterId, Parameters, ProcessMode, ProcessState, Processor, ProcessorConfig, Transport};
+use crate::{bypass::Bypass, formats::PluginFormat, host::HostInfo, Editor, Event, ParameterId, Parameters, ProcessMode, ProcessState, Processor, ProcessorConfig, Transport};
 use crate::auv3::{plugin::Auv3Plugin, Auv3Host, EventIterator, PLINTH_AUV3_MAX_STRING_LENGTH};
 use crate::parameters::{self, group::ParameterGroupRef, has_duplicates};
 use crate::string::copy_str_to_char8;
 
-use super::{parameter_multiplier, parameters::CachedParameter, AURenderEvent, Auv3Reader, Auv3Writer, ParameterGroupInfo};
+use super::{host_bundle, main_queue::MainQueueTimer, parameter_multiplier, parameters::CachedParameter, AURenderEvent, Auv3Reader, Auv3Writer, ParameterGroupInfo};
 
 const MAX_EVENTS: usize = 1024 * 10;
 
 pub struct Auv3Wrapper<P: Auv3Plugin> {
-    plugin: Mutex<P>,
+    plugin: Arc<Mutex<P>>,
+    _main_queue_timer: MainQueueTimer,
     processor: Option<P::Processor>,
+    bypass: Option<Bypass>,
     editor: Option<P::Editor>,
 
     parameter_ids: Vec<ParameterId>,
@@ -33,17 +35,30 @@ pub struct Auv3Wrapper<P: Auv3Plugin> {
     events_to_processor_receiver: rtrb::Consumer<Event>,
 }
 
-impl<P: Auv3Plugin> Auv3Wrapper<P> {
+impl<P: Auv3Plugin + 'static> Auv3Wrapper<P> {
     pub fn new() -> Self {
         let (events_to_processor_sender, events_to_processor_receiver) = rtrb::RingBuffer::new(MAX_EVENTS);
 
-        let host_info = HostInfo {
-            name: None,
-            format: PluginFormat::Auv3,
-        };
-        let plugin = P::new(host_info);
+        // Info.plist has no standard vendor key
+        let (host_name, host_version) = host_bundle::host_name_and_version().unwrap_or_default();
+
+        let plugin = Arc::new_cyclic(|plugin| {
+            let host_info = HostInfo {
+                name: host_name,
+                vendor: None,
+                version: host_version,
+                url: None,
+                format: PluginFormat::Auv3,
+                main_thread_waker: MainQueueTimer::waker(plugin.clone()),
+                capabilities: Default::default(),
+            };
+
+            Mutex::new(P::new(host_info))
+        });
+
+        let main_queue_timer = MainQueueTimer::new(Arc::downgrade(&plugin));
 
-        let (parameter_groups, cached_parameters) = plugin.with_parameters(|parameters| {
+        let (parameter_groups, cached_parameters) = plugin.lock().unwrap().with_parameters(|parameters| {
             let parameter_groups = parameters::group::from_parameters(parameters);
 
             let cached_parameters: Vec<_> = parameters.ids()
@@ -74,8 +89,10 @@ impl<P: Auv3Plugin> Auv3Wrapper<P> {
         assert!(!has_duplicates(&parameter_ids));
 
         Self {
-            plugin: plugin.into(),
+            plugin,
+            _main_queue_timer: main_queue_timer,
             processor: None,
+            bypass: None,
             editor: None,
 
             parameter_ids,
@@ -109,16 +126,20 @@ impl<P: Auv3Plugin> Auv3Wrapper<P> {
             min_block_size: 0,
             max_block_size: max_block_size as _,
             process_mode: ProcessMode::Realtime, // TODO
+            parallel: Default::default(),
         };
 
         self.sample_rate.store(sample_rate, Ordering::Release);
 
         let mut plugin = self.plugin.lock().unwrap();
-        self.processor = Some(plugin.create_processor(processor_config));
+        self.processor = Some(plugin.create_processor(processor_config.clone()));
+        // Creating the processor can change the latency the bypass delays by
+        self.bypass = Bypass::for_plugin(&*plugin, &processor_config, 2);
     }
 
     pub fn deactivate(&mut self) {
         self.processor = None;
+        self.bypass = None;
     }
 
     pub fn tail_length(&self) -> f64 {
@@ -373,7 +394,17 @@ impl<P: Auv3Plugin> Auv3Wrapper<P> {
 
         let event_count = self.events_to_processor_receiver.slots();
         if event_count > 0 {
-            processor.process_events(self.events_to_processor_receiver.read_chunk(event_count).unwrap().into_iter());
+            let events = self.events_to_processor_receiver.read_chunk(event_count).unwrap().into_iter();
+
+            match self.bypass.as_mut() {
+                Some(bypass) => {
+                    let mut events = events.inspect(|event| bypass.process_event(event));
+                    processor.process_events(events.by_ref());
+                    events.for_each(drop);
+                },
+
+                None => processor.process_events(events),
+            }
         }
 
         let transport = Transport::new(playing, tempo, position_samples);
@@ -394,11 +425,11 @@ impl<P: Auv3Plugin> Auv3Wrapper<P> {
                 output.copy_from_signal(input);
             }
             
-            let state = processor.process(
-                output,
-                aux.as_ref(),
-                Some(transport),
-                &mut EventIterator::new(first_event, &self.parameter_ids));
+            let events = EventIterator::new(first_event, &self.parameter_ids);
+            let state = match self.bypass.as_mut() {
+                Some(bypass) => bypass.process(processor, P::PROCESS_WHILE_BYPASSED, output, aux.as_ref(), Some(transport), events),
+                None => processor.process(output, aux.as_ref(), Some(transport), events),
+            };
 
                 let tail_length_samples = match state {
                     ProcessState::Error => {
@@ -406,7 +437,7 @@ impl<P: Auv3Plugin> Auv3Wrapper<P> {
                         0
                     },
 
-                    ProcessState::Normal => 0,
+                    ProcessState::Normal | ProcessState::Sleep => 0,
                     ProcessState::Tail(tail) => tail,
                     ProcessState::KeepAlive => usize::MAX,
                 };
@@ -415,12 +446,22 @@ impl<P: Auv3Plugin> Auv3Wrapper<P> {
                 let tail_length_seconds = tail_length_samples as f64 / sample_rate;
                 self.tail_length_seconds.store(tail_length_seconds, ::std::sync::atomic::Ordering::Release);
         } else {
-            processor.process_events(&mut EventIterator::new(first_event, &self.parameter_ids));
+            let mut events = EventIterator::new(first_event, &self.parameter_ids);
+
+            match self.bypass.as_mut() {
+                Some(bypass) => {
+                    let mut events = events.inspect(|event| bypass.process_event(event));
+                    processor.process_events(events.by_ref());
+                    events.for_each(drop);
+                },
+
+                None => processor.process_events(&mut events),
+            }
         };
     }
 }
 
-impl<P: Auv3Plugin> Default for Auv3Wrapper<P> {
+impl<P: Auv3Plugin + 'static> Default for Auv3Wrapper<P> {
     fn default() -> Self {
         Self::new()
     }
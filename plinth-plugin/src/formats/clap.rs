@@ -10,7 +10,10 @@ mod parameters;
 mod plugin;
 mod plugin_instance;
 mod stream;
+#[cfg(all(test, feature = "rt-check"))]
+mod test_host;
 mod transport;
+mod validation;
 
 pub use entry_point::EntryPoint;
 pub use factory::Factory;
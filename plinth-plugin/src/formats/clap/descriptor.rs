@@ -2,7 +2,7 @@ use std::{ffi::{c_char, CStr, CString}, ptr::null};
 
 use clap_sys::{plugin::clap_plugin_descriptor, version::CLAP_VERSION};
 
-use super::plugin::ClapPlugin;
+use super::{features::plugin_features, plugin::ClapPlugin, validation::validate};
 
 struct DescriptorData {
     id: CString,
@@ -29,13 +29,15 @@ pub struct Descriptor {
 
 impl Descriptor {
     pub fn new<P: ClapPlugin>() -> Self {
+        const { validate::<P>() };
+
         let mut data = Box::new(DescriptorData {
             id: CString::new(P::CLAP_ID).unwrap(),
             name: CString::new(P::NAME).unwrap(),
             vendor: CString::new(P::VENDOR).unwrap(),
             version: CString::new(P::VERSION).unwrap(),
 
-            features: P::FEATURES.iter().map(|feature| CString::new(feature.to_str()).unwrap()).collect(),
+            features: plugin_features::<P>().iter().map(|feature| CString::new(feature.to_str()).unwrap()).collect(),
             feature_pointers: Vec::new(),
 
             url: CString::new(P::URL.unwrap_or_default()).unwrap(),
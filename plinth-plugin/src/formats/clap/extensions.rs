@@ -6,4 +6,5 @@ pub mod params;
 pub mod render;
 pub mod state;
 pub mod tail;
+pub mod thread_pool;
 pub mod timer_support;
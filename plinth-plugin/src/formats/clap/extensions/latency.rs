@@ -3,6 +3,7 @@ use std::marker::PhantomData;
 use clap_sys::{ext::latency::clap_plugin_latency, plugin::clap_plugin};
 
 use crate::clap::{plugin_instance::PluginInstance, ClapPlugin};
+use crate::plugin::reported_latency;
 
 #[repr(transparent)]
 pub struct Latency<P: ClapPlugin> {
@@ -28,7 +29,7 @@ impl<P: ClapPlugin> Latency<P> {
    
     unsafe extern "C" fn get(plugin: *const clap_plugin) -> u32 {
         PluginInstance::with_plugin_instance(plugin, |instance: &mut PluginInstance<P>| {
-            instance.plugin.as_ref().unwrap().latency() as _
+            reported_latency(instance.plugin.as_ref().unwrap()) as _
         })
     }
 }
@@ -2,7 +2,7 @@ use std::{ffi::{c_char, CStr}, marker::PhantomData, sync::atomic::Ordering};
 
 use clap_sys::{events::{clap_input_events, clap_output_events}, ext::params::{CLAP_PARAM_IS_AUTOMATABLE, CLAP_PARAM_IS_BYPASS, CLAP_PARAM_IS_HIDDEN, CLAP_PARAM_IS_MODULATABLE, CLAP_PARAM_IS_STEPPED, CLAP_PARAM_REQUIRES_PROCESS, clap_param_info, clap_plugin_params}, id::clap_id, plugin::clap_plugin};
 
-use crate::{clap::{event::EventIterator, parameters::{map_parameter_value_from_clap, map_parameter_value_to_clap}, plugin_instance::PluginInstance, ClapPlugin}, processor::Processor, string::copy_str_to_char8, Parameters};
+use crate::{clap::{event::EventIterator, parameters::{map_parameter_value_from_clap, map_parameter_value_to_clap}, plugin_instance::PluginInstance, ClapPlugin}, processor::Processor, realtime::RealtimeRegion, string::copy_str_to_char8, Parameters};
 
 #[repr(transparent)]
 pub struct Params<P: ClapPlugin> {
@@ -138,6 +138,9 @@ impl<P: ClapPlugin> Params<P> {
 
     unsafe extern "C" fn flush(plugin: *const clap_plugin, in_events: *const clap_input_events, out_events: *const clap_output_events) {
         PluginInstance::with_plugin_instance(plugin, |instance: &mut PluginInstance<P>| {
+            // Only called from the audio thread when active
+            let _realtime = instance.audio_thread_state.active.load(Ordering::Acquire).then(RealtimeRegion::enter);
+
             instance.process_events_to_plugin();
 
             let host_events = EventIterator::new(&instance.parameter_info, unsafe { &*in_events });    
@@ -0,0 +1,82 @@
+use std::{marker::PhantomData, ptr::{addr_of, null_mut}, sync::atomic::{AtomicPtr, Ordering}};
+
+use clap_sys::{ext::thread_pool::{clap_host_thread_pool, clap_plugin_thread_pool}, host::clap_host, plugin::clap_plugin};
+
+use crate::{clap::{plugin_instance::PluginInstance, ClapPlugin}, parallel, realtime::RealtimeRegion};
+
+#[repr(transparent)]
+pub struct ThreadPool<P: ClapPlugin> {
+    raw: clap_plugin_thread_pool,
+
+    _phantom_plugin: PhantomData<P>,
+}
+
+impl<P: ClapPlugin> ThreadPool<P> {
+    pub const fn new() -> Self {
+        Self {
+            raw: clap_plugin_thread_pool {
+                exec: Some(Self::exec),
+            },
+
+            _phantom_plugin: PhantomData,
+        }
+    }
+
+    pub fn as_raw(&self) -> *const clap_plugin_thread_pool {
+        &self.raw
+    }
+
+    unsafe extern "C" fn exec(plugin: *const clap_plugin, task_index: u32) {
+        let _realtime = RealtimeRegion::enter();
+
+        // Called from several threads at once while process() holds the instance mutably,
+        // so only reference the thread pool field, which process() leaves alone
+        let instance = plugin as *const PluginInstance<P>;
+        let host_thread_pool = unsafe { &*addr_of!((*instance).host_thread_pool) };
+
+        if let Some(thread_pool) = host_thread_pool.as_ref() {
+            thread_pool.exec(task_index as _);
+        }
+    }
+}
+
+/// The host's thread pool, used by `Parallel`
+pub struct HostThreadPool {
+    host: *const clap_host,
+    host_ext: *const clap_host_thread_pool,
+    // Points to the task of the running `execute()` call
+    task: AtomicPtr<()>,
+}
+
+impl HostThreadPool {
+    pub fn new(host: *const clap_host, host_ext: *const clap_host_thread_pool) -> Self {
+        Self {
+            host,
+            host_ext,
+            task: AtomicPtr::new(null_mut()),
+        }
+    }
+
+    fn exec(&self, task_index: usize) {
+        let task = self.task.load(Ordering::Acquire) as *const &(dyn Fn(usize) + Sync);
+
+        if !task.is_null() {
+            unsafe { (*task)(task_index) };
+        }
+    }
+}
+
+impl parallel::ThreadPool for HostThreadPool {
+    fn execute(&self, task_count: usize, task: &(dyn Fn(usize) + Sync)) -> bool {
+        // request_exec() blocks until all tasks have finished, so the task outlives every exec() call
+        self.task.store(&task as *const &(dyn Fn(usize) + Sync) as _, Ordering::Release);
+        let executed = unsafe { ((*self.host_ext).request_exec.unwrap())(self.host, task_count as _) };
+        self.task.store(null_mut(), Ordering::Release);
+
+        executed
+    }
+}
+
+/// SAFETY: clap_host_thread_pool::request_exec() can be called from the audio thread, and exec() only reads the task pointer
+unsafe impl Send for HostThreadPool {}
+unsafe impl Sync for HostThreadPool {}
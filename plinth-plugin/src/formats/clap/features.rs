@@ -1,3 +1,8 @@
+use crate::Category;
+
+use super::plugin::ClapPlugin;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Feature {
     Analyzer,
     AudioEffect,
@@ -43,7 +48,12 @@ pub enum Feature {
 }
 
 impl Feature {
-    pub fn to_str(&self) -> &str {
+    /// Hosts expect at least one main feature
+    pub const fn is_main(&self) -> bool {
+        matches!(self, Feature::Analyzer | Feature::AudioEffect | Feature::Instrument | Feature::NoteDetector | Feature::NoteEffect)
+    }
+
+    pub const fn to_str(&self) -> &'static str {
         match self {
             Feature::Analyzer => "analyzer",
             Feature::AudioEffect => "audio-effect",
@@ -89,3 +99,14 @@ impl Feature {
         }
     }
 }
+
+/// `ClapPlugin::FEATURES` if set, otherwise the features of `Plugin::CATEGORIES`
+pub(crate) fn plugin_features<P: ClapPlugin>() -> Vec<Feature> {
+    if !P::FEATURES.is_empty() {
+        return P::FEATURES.to_vec();
+    }
+
+    P::CATEGORIES.iter()
+        .map(Category::feature)
+        .collect()
+}
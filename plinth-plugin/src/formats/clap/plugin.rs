@@ -5,7 +5,8 @@ use super::features::Feature;
 pub trait ClapPlugin : Plugin {
     const CLAP_ID: &'static str;
 
-    const FEATURES: &'static [Feature];
+    /// Overrides the features mapped from `Plugin::CATEGORIES`
+    const FEATURES: &'static [Feature] = &[];
 
     const MANUAL_URL: Option<&'static str> = None;
     const SUPPORT_URL: Option<&'static str> = None;
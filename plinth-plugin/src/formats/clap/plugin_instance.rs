@@ -1,18 +1,18 @@
 use std::{collections::BTreeMap, ffi::{c_char, c_void, CStr}, iter::zip, ptr::{null, null_mut}, sync::{atomic::{AtomicUsize, Ordering}, Arc}};
 
 use atomic_refcell::AtomicRefCell;
-use clap_sys::{events::clap_input_events, ext::{audio_ports::CLAP_EXT_AUDIO_PORTS, gui::{clap_host_gui, CLAP_EXT_GUI}, latency::CLAP_EXT_LATENCY, note_ports::CLAP_EXT_NOTE_PORTS, params::{clap_host_params, CLAP_EXT_PARAMS}, render::CLAP_EXT_RENDER, state::{clap_host_state, CLAP_EXT_STATE}, tail::{clap_host_tail, CLAP_EXT_TAIL}, timer_support::{clap_host_timer_support, CLAP_EXT_TIMER_SUPPORT}}, host::clap_host, plugin::clap_plugin, process::{clap_process, clap_process_status, CLAP_PROCESS_CONTINUE, CLAP_PROCESS_CONTINUE_IF_NOT_QUIET, CLAP_PROCESS_ERROR, CLAP_PROCESS_TAIL}};
+use clap_sys::{events::clap_input_events, ext::{audio_ports::CLAP_EXT_AUDIO_PORTS, gui::{clap_host_gui, CLAP_EXT_GUI}, latency::CLAP_EXT_LATENCY, log::{clap_host_log, clap_log_severity, CLAP_EXT_LOG, CLAP_LOG_DEBUG, CLAP_LOG_ERROR, CLAP_LOG_INFO, CLAP_LOG_WARNING}, note_ports::{clap_host_note_ports, CLAP_EXT_NOTE_PORTS, CLAP_NOTE_DIALECT_CLAP}, remote_controls::{CLAP_EXT_REMOTE_CONTROLS, CLAP_EXT_REMOTE_CONTROLS_COMPAT}, params::{clap_host_params, CLAP_EXT_PARAMS}, render::CLAP_EXT_RENDER, state::{clap_host_state, CLAP_EXT_STATE}, tail::{clap_host_tail, CLAP_EXT_TAIL}, thread_pool::{clap_host_thread_pool, CLAP_EXT_THREAD_POOL}, timer_support::{clap_host_timer_support, CLAP_EXT_TIMER_SUPPORT}, track_info::{CLAP_EXT_TRACK_INFO, CLAP_EXT_TRACK_INFO_COMPAT}}, host::clap_host, plugin::clap_plugin, process::{clap_process, clap_process_status, CLAP_PROCESS_CONTINUE, CLAP_PROCESS_CONTINUE_IF_NOT_QUIET, CLAP_PROCESS_ERROR, CLAP_PROCESS_SLEEP, CLAP_PROCESS_TAIL}};
 use log::error;
 use plinth_core::signals::{ptr_signal::{PtrSignal, PtrSignalMut}, signal::SignalMut};
 use portable_atomic::AtomicBool;
 use raw_window_handle::RawWindowHandle;
 
-use crate::{formats::PluginFormat, host::HostInfo, Event, ParameterId, ProcessMode, ProcessState, Processor, ProcessorConfig};
+use crate::{bypass::Bypass, formats::PluginFormat, host::{HostCapabilities, HostCapability, HostInfo}, logging, parallel::Parallel, realtime::RealtimeRegion, tasks::MainThreadWaker, Event, ParameterId, ProcessMode, ProcessState, Processor, ProcessorConfig};
 use crate::clap::{event::EventIterator, transport::convert_transport};
 use crate::parameters::{info::ParameterInfo, has_duplicates, Parameters};
 
 use super::descriptor::Descriptor;
-use super::extensions::{audio_ports::AudioPorts, gui::Gui, latency::Latency, note_ports::NotePorts, params::Params, render::Render, state::State, tail::Tail, timer_support::TimerSupport};
+use super::extensions::{audio_ports::AudioPorts, gui::Gui, latency::Latency, note_ports::NotePorts, params::Params, render::Render, state::State, tail::Tail, thread_pool::{HostThreadPool, ThreadPool}, timer_support::TimerSupport};
 use super::parameters::ParameterEventMap;
 use super::plugin::ClapPlugin;
 
@@ -20,6 +20,7 @@ pub struct AudioThreadState<P: ClapPlugin> {
     // When active is true, we have a processor
     pub(super) active: AtomicBool,
     pub(super) processor: AtomicRefCell<Option<P::Processor>>,
+    bypass: AtomicRefCell<Option<Bypass>>,
     pub(super) tail: AtomicUsize,
 }
 
@@ -28,6 +29,7 @@ impl<P: ClapPlugin> Default for AudioThreadState<P> {
         Self {
             active: false.into(),
             processor: Default::default(),
+            bypass: Default::default(),
             tail: 0.into(),
         }
     }
@@ -60,6 +62,8 @@ pub struct PluginInstance<P: ClapPlugin> {
     pub(super) host_ext_state: *const clap_host_state,
     host_ext_tail: *const clap_host_tail,
     pub(super) host_ext_timer_support: *const clap_host_timer_support,
+    pub(super) host_thread_pool: Option<Arc<HostThreadPool>>,
+    host_capabilities: HostCapabilities,
 }
 
 impl<P: ClapPlugin> PluginInstance<P> {
@@ -72,18 +76,23 @@ impl<P: ClapPlugin> PluginInstance<P> {
     const EXT_RENDER: Render<P> = Render::new();
     const EXT_STATE: State<P> = State::new();
     const EXT_TAIL: Tail<P> = Tail::new();
+    const EXT_THREAD_POOL: ThreadPool<P> = ThreadPool::new();
     const EXT_TIMER_SUPPORT: TimerSupport<P> = TimerSupport::new();
 
     pub fn new(descriptor: &Descriptor, host: *const clap_host) -> Self {
-        let host_name = unsafe { CStr::from_ptr((*host).name)
-            .to_str()
-            .ok()
-            .map(|str| str.to_string())
-        };
+        let host_capabilities = HostCapabilities::default();
 
         let host_info = HostInfo {
-            name: host_name,
+            name: unsafe { host_string((*host).name) },
+            vendor: unsafe { host_string((*host).vendor) },
+            version: unsafe { host_string((*host).version) },
+            url: unsafe { host_string((*host).url) },
             format: PluginFormat::Clap,
+            main_thread_waker: MainThreadWaker::new({
+                let host = RequestCallbackHost(host);
+                move || host.request_callback()
+            }),
+            capabilities: host_capabilities.clone(),
         };
 
         let plugin = P::new(host_info);
@@ -149,12 +158,15 @@ impl<P: ClapPlugin> PluginInstance<P> {
             host_ext_state: null(),
             host_ext_tail: null(),
             host_ext_timer_support: null(),
+            host_thread_pool: None,
+            host_capabilities,
         }
     }
 
     pub(super) fn with_plugin_instance<T>(plugin: *const clap_plugin, mut f: impl FnMut(&mut PluginInstance<P>) -> T) -> T {
         assert!(!plugin.is_null());
 
+        let _log_scope = logging::InstanceScope::enter(plugin as usize);
         let mut plugin_instance = unsafe { Box::from_raw(plugin as *mut PluginInstance<P>) };
         let result = f(&mut plugin_instance);
         Box::leak(plugin_instance);
@@ -194,6 +206,34 @@ impl<P: ClapPlugin> PluginInstance<P> {
             instance.host_ext_state = unsafe { ((*instance.host).get_extension.unwrap())(instance.host, CLAP_EXT_STATE.as_ptr()) as _ };
             instance.host_ext_tail = unsafe { ((*instance.host).get_extension.unwrap())(instance.host, CLAP_EXT_TAIL.as_ptr()) as _ };
             instance.host_ext_timer_support = unsafe { ((*instance.host).get_extension.unwrap())(instance.host, CLAP_EXT_TIMER_SUPPORT.as_ptr()) as _ };
+
+            let host_ext_thread_pool: *const clap_host_thread_pool = unsafe { ((*instance.host).get_extension.unwrap())(instance.host, CLAP_EXT_THREAD_POOL.as_ptr()) as _ };
+            if !host_ext_thread_pool.is_null() {
+                instance.host_thread_pool = Some(Arc::new(HostThreadPool::new(instance.host, host_ext_thread_pool)));
+            }
+
+            let host_ext_log: *const clap_host_log = unsafe { ((*instance.host).get_extension.unwrap())(instance.host, CLAP_EXT_LOG.as_ptr()) as _ };
+            if !host_ext_log.is_null() {
+                let host_log = HostLog(instance.host, host_ext_log);
+                logging::add_host_sink(plugin as usize, move |level, message| host_log.log(level, message));
+            }
+
+            let host_ext_note_ports: *const clap_host_note_ports = unsafe { ((*instance.host).get_extension.unwrap())(instance.host, CLAP_EXT_NOTE_PORTS.as_ptr()) as _ };
+            let note_dialects = if host_ext_note_ports.is_null() {
+                0
+            } else {
+                unsafe { ((*host_ext_note_ports).supported_dialects.unwrap())(instance.host) }
+            };
+
+            let has_extension = |id: &CStr| unsafe { !((*instance.host).get_extension.unwrap())(instance.host, id.as_ptr()).is_null() };
+
+            let capabilities = &instance.host_capabilities;
+            capabilities.set(HostCapability::Resize, !instance.host_ext_gui.is_null());
+            capabilities.set(HostCapability::NoteExpressions, note_dialects & CLAP_NOTE_DIALECT_CLAP != 0);
+            capabilities.set(HostCapability::RemoteControls, has_extension(CLAP_EXT_REMOTE_CONTROLS) || has_extension(CLAP_EXT_REMOTE_CONTROLS_COMPAT));
+            capabilities.set(HostCapability::TrackInfo, has_extension(CLAP_EXT_TRACK_INFO) || has_extension(CLAP_EXT_TRACK_INFO_COMPAT));
+            capabilities.set(HostCapability::ThreadPool, instance.host_thread_pool.is_some());
+            capabilities.set(HostCapability::Log, !host_ext_log.is_null());
         });
 
         true
@@ -202,6 +242,8 @@ impl<P: ClapPlugin> PluginInstance<P> {
     unsafe extern "C" fn destroy(plugin: *const clap_plugin) {
         log::trace!("plugin::destroy");
 
+        logging::remove_host_sink(plugin as usize);
+
         Self::with_plugin_instance(plugin, |instance| {
             instance.plugin = None;
         })
@@ -222,12 +264,20 @@ impl<P: ClapPlugin> PluginInstance<P> {
                 min_block_size: min_frames_count as _,
                 max_block_size: max_frames_count as _,
                 process_mode: instance.process_mode,
+                parallel: match instance.host_thread_pool.as_ref() {
+                    Some(thread_pool) => Parallel::new(thread_pool.clone()),
+                    None => Parallel::default(),
+                },
             };
 
             instance.sample_rate = sample_rate;
 
+            let plugin = instance.plugin.as_mut().unwrap();
             let mut processor = instance.audio_thread_state.processor.borrow_mut();
-            *processor = Some(instance.plugin.as_mut().unwrap().create_processor(config));
+            *processor = Some(plugin.create_processor(config.clone()));
+
+            // Creating the processor can change the latency the bypass delays by
+            *instance.audio_thread_state.bypass.borrow_mut() = Bypass::for_plugin(plugin, &config, 2);
 
             instance.audio_thread_state.active.store(true, Ordering::Release);
         });
@@ -240,21 +290,25 @@ impl<P: ClapPlugin> PluginInstance<P> {
 
         Self::with_plugin_instance(plugin, |instance| {
             *instance.audio_thread_state.processor.borrow_mut() = None;
+            *instance.audio_thread_state.bypass.borrow_mut() = None;
             instance.audio_thread_state.active.store(false, Ordering::Release);
         });
     }
 
     unsafe extern "C" fn start_processing(_plugin: *const clap_plugin) -> bool {
+        let _realtime = RealtimeRegion::enter();
         log::trace!("plugin::start_processing");
 
         true
     }
 
     unsafe extern "C" fn stop_processing(_plugin: *const clap_plugin) {
+        let _realtime = RealtimeRegion::enter();
         log::trace!("plugin::stop_processing");
     }
 
     unsafe extern "C" fn reset(plugin: *const clap_plugin) {
+        let _realtime = RealtimeRegion::enter();
         log::trace!("plugin::reset");
 
         Self::with_plugin_instance(plugin, |instance| {
@@ -262,10 +316,15 @@ impl<P: ClapPlugin> PluginInstance<P> {
             if let Some(processor) = processor.as_mut() {
                 processor.reset();
             }
+
+            if let Some(bypass) = instance.audio_thread_state.bypass.borrow_mut().as_mut() {
+                bypass.reset();
+            }
         });
     }
 
     unsafe extern "C" fn process(plugin: *const clap_plugin, process: *const clap_process) -> clap_process_status {
+        let _realtime = RealtimeRegion::enter();
         log::trace!("plugin::process");
 
         let process = unsafe { &*process };
@@ -331,9 +390,27 @@ impl<P: ClapPlugin> PluginInstance<P> {
 
             // Process events coming from the host and events coming from the editor
             let host_events = EventIterator::new(&instance.parameter_info, unsafe { &*process.in_events });
-            let events = host_events.chain(editor_events);
+            let events = Event::merge_in_time_order(host_events, editor_events);
+
+            let aux_constant_mask = if P::HAS_AUX_INPUT { input_buffers[1].constant_mask } else { 0 };
+            processor.set_input_constant_mask(input_buffer.constant_mask, aux_constant_mask);
+
+            let mut bypass = instance.audio_thread_state.bypass.borrow_mut();
+            let process_state = match bypass.as_mut() {
+                Some(bypass) => bypass.process(processor, P::PROCESS_WHILE_BYPASSED, &mut output, aux.as_ref(), transport, events),
+                None => processor.process(&mut output, aux.as_ref(), transport, events),
+            };
 
-            let result = match processor.process(&mut output, aux.as_ref(), transport, events) {
+            let output_constant_mask = match bypass.as_ref() {
+                Some(bypass) if bypass.is_engaged() => 0,
+                _ => processor.output_constant_mask(),
+            };
+            drop(bypass);
+
+            // SAFETY: the host owns the output buffers for the duration of process()
+            unsafe { (*process.audio_outputs).constant_mask = output_constant_mask };
+
+            let result = match process_state {
                 ProcessState::Error => CLAP_PROCESS_ERROR,
                 ProcessState::Normal => CLAP_PROCESS_CONTINUE_IF_NOT_QUIET,
                 ProcessState::Tail(tail) => {
@@ -347,6 +424,7 @@ impl<P: ClapPlugin> PluginInstance<P> {
                     CLAP_PROCESS_TAIL
                 },
                 ProcessState::KeepAlive => CLAP_PROCESS_CONTINUE,
+                ProcessState::Sleep => CLAP_PROCESS_SLEEP,
             };
 
             drop(processor_ref);
@@ -379,6 +457,8 @@ impl<P: ClapPlugin> PluginInstance<P> {
             Self::EXT_STATE.as_raw() as _
         } else if id == CLAP_EXT_TAIL {
             Self::EXT_TAIL.as_raw() as _
+        } else if id == CLAP_EXT_THREAD_POOL {
+            Self::EXT_THREAD_POOL.as_raw() as _
         } else if id == CLAP_EXT_TIMER_SUPPORT {
             Self::EXT_TIMER_SUPPORT.as_raw() as _
         } else {
@@ -391,6 +471,88 @@ impl<P: ClapPlugin> PluginInstance<P> {
 
         Self::with_plugin_instance(plugin, |instance| {
             instance.process_events_to_plugin();
+            instance.plugin.as_mut().unwrap().on_main_thread();
+            logging::flush();
         })        
     }
 }
+
+struct RequestCallbackHost(*const clap_host);
+
+impl RequestCallbackHost {
+    fn request_callback(&self) {
+        unsafe { ((*self.0).request_callback.unwrap())(self.0) };
+    }
+}
+
+/// SAFETY: request_callback is thread-safe, and the host outlives the plugin and its worker threads
+unsafe impl Send for RequestCallbackHost {}
+unsafe impl Sync for RequestCallbackHost {}
+
+struct HostLog(*const clap_host, *const clap_host_log);
+
+impl HostLog {
+    fn log(&self, level: log::Level, message: &CStr) {
+        unsafe { ((*self.1).log.unwrap())(self.0, log_severity(level), message.as_ptr()) };
+    }
+}
+
+/// SAFETY: clap_host_log is thread-safe, and the sink is removed before the host goes away
+unsafe impl Send for HostLog {}
+unsafe impl Sync for HostLog {}
+
+fn log_severity(level: log::Level) -> clap_log_severity {
+    match level {
+        log::Level::Error => CLAP_LOG_ERROR,
+        log::Level::Warn => CLAP_LOG_WARNING,
+        log::Level::Info => CLAP_LOG_INFO,
+        log::Level::Debug | log::Level::Trace => CLAP_LOG_DEBUG,
+    }
+}
+
+/// # Safety
+///
+/// `string` must be null or point to a valid C string
+unsafe fn host_string(string: *const c_char) -> Option<String> {
+    if string.is_null() {
+        return None;
+    }
+
+    unsafe { CStr::from_ptr(string) }.to_str()
+        .ok()
+        .map(|str| str.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap_sys::ext::log::{CLAP_LOG_DEBUG, CLAP_LOG_ERROR, CLAP_LOG_INFO, CLAP_LOG_WARNING};
+    use log::Level;
+
+    use super::log_severity;
+
+    #[test]
+    fn log_severities() {
+        assert_eq!(log_severity(Level::Error), CLAP_LOG_ERROR);
+        assert_eq!(log_severity(Level::Warn), CLAP_LOG_WARNING);
+        assert_eq!(log_severity(Level::Info), CLAP_LOG_INFO);
+        assert_eq!(log_severity(Level::Debug), CLAP_LOG_DEBUG);
+        assert_eq!(log_severity(Level::Trace), CLAP_LOG_DEBUG);
+    }
+
+    #[cfg(feature = "rt-check")]
+    #[test]
+    fn process_is_checked() {
+        use crate::{clap::test_host::{TestHost, TestPlugin}, realtime::violation_count};
+
+        let host = TestHost::<TestPlugin>::new(Vec::new());
+        host.activate(48000.0, 16);
+
+        let count = violation_count();
+        host.process(&mut [vec![0.0; 16], vec![0.0; 16]]);
+        assert_eq!(violation_count(), count);
+
+        // The test processor allocates when the input isn't silent
+        host.process(&mut [vec![1.0; 16], vec![1.0; 16]]);
+        assert!(violation_count() > count);
+    }
+}
@@ -0,0 +1,196 @@
+use std::{ffi::{c_char, c_void, CStr}, io::{Read, Write}, ptr::{null, null_mut}, rc::Rc};
+
+use clap_sys::{events::{clap_event_header, clap_input_events, clap_output_events}, host::clap_host, plugin::clap_plugin, process::{clap_process, clap_process_status}, audio_buffer::clap_audio_buffer, version::CLAP_VERSION};
+use plinth_core::signals::signal::{Signal, SignalMut};
+
+use crate::{error::Error, Category, Event, Host, HostInfo, NoEditor, ParameterMap, Plugin, ProcessState, Processor, ProcessorConfig, Transport};
+
+use super::{descriptor::Descriptor, plugin::ClapPlugin, plugin_instance::PluginInstance};
+
+/// Minimal plugin for driving the CLAP wrapper from tests
+pub(crate) struct TestPlugin {
+    parameters: ParameterMap,
+}
+
+/// Allocates when the first input sample isn't zero, so tests can trigger realtime violations
+pub(crate) struct TestProcessor;
+
+impl Processor for TestProcessor {
+    fn reset(&mut self) {}
+
+    fn process(&mut self, buffer: &mut impl SignalMut, _aux: Option<&impl Signal>, _transport: Option<Transport>, _events: impl Iterator<Item = Event>) -> ProcessState {
+        if buffer.channel(0)[0] != 0.0 {
+            std::hint::black_box(vec![0u8; 16]);
+        }
+
+        ProcessState::Normal
+    }
+
+    fn process_events(&mut self, _events: impl Iterator<Item = Event>) {}
+}
+
+impl Plugin for TestPlugin {
+    const NAME: &'static str = "Test";
+    const VENDOR: &'static str = "Test";
+    const VERSION: &'static str = "0.1.0";
+
+    const CATEGORIES: &'static [Category] = &[Category::Effect];
+
+    type Processor = TestProcessor;
+    type Editor = NoEditor;
+    type Parameters = ParameterMap;
+
+    fn new(_host_info: HostInfo) -> Self {
+        Self {
+            parameters: ParameterMap::new(),
+        }
+    }
+
+    fn with_parameters<T>(&self, mut f: impl FnMut(&Self::Parameters) -> T) -> T {
+        f(&self.parameters)
+    }
+
+    fn process_event(&mut self, _event: &Event) {}
+
+    fn create_processor(&mut self, _config: ProcessorConfig) -> Self::Processor {
+        TestProcessor
+    }
+
+    fn create_editor(&mut self, _host: Rc<dyn Host>) -> Self::Editor {
+        NoEditor
+    }
+
+    fn save_state(&self, _writer: &mut impl Write) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn load_state(&mut self, _reader: &mut impl Read) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl ClapPlugin for TestPlugin {
+    const CLAP_ID: &'static str = "com.plinth.test";
+}
+
+struct Extensions(Vec<(&'static CStr, *const c_void)>);
+
+/// A host with the given extensions, driving one plugin instance through the raw CLAP entry points
+pub(crate) struct TestHost<P: ClapPlugin> {
+    // The plugin keeps pointers to these
+    _raw: Box<clap_host>,
+    _extensions: Box<Extensions>,
+    _descriptor: Descriptor,
+    plugin: *const clap_plugin,
+    _phantom_plugin: std::marker::PhantomData<P>,
+}
+
+impl<P: ClapPlugin> TestHost<P> {
+    pub(crate) fn new(extensions: Vec<(&'static CStr, *const c_void)>) -> Self {
+        let mut extensions = Box::new(Extensions(extensions));
+
+        let raw = Box::new(clap_host {
+            clap_version: CLAP_VERSION,
+            host_data: extensions.as_mut() as *mut Extensions as _,
+            name: c"Test Host".as_ptr(),
+            vendor: c"Test Vendor".as_ptr(),
+            url: null(),
+            version: c"1.2.3".as_ptr(),
+            get_extension: Some(Self::get_extension),
+            request_restart: Some(Self::request),
+            request_process: Some(Self::request),
+            request_callback: Some(Self::request),
+        });
+
+        let descriptor = Descriptor::new::<P>();
+        let instance = Box::new(PluginInstance::<P>::new(&descriptor, raw.as_ref()));
+        let plugin = Box::into_raw(instance) as *const clap_plugin;
+
+        unsafe { assert!(((*plugin).init.unwrap())(plugin)) };
+
+        Self {
+            _raw: raw,
+            _extensions: extensions,
+            _descriptor: descriptor,
+            plugin,
+            _phantom_plugin: std::marker::PhantomData,
+        }
+    }
+
+    pub(crate) fn activate(&self, sample_rate: f64, max_block_size: u32) {
+        unsafe { assert!(((*self.plugin).activate.unwrap())(self.plugin, sample_rate, 1, max_block_size)) };
+    }
+
+    /// Processes stereo audio in place
+    pub(crate) fn process(&self, channels: &mut [Vec<f32>; 2]) -> clap_process_status {
+        let frames_count = channels[0].len() as u32;
+        let mut pointers = [channels[0].as_mut_ptr(), channels[1].as_mut_ptr()];
+
+        let input = clap_audio_buffer {
+            data32: pointers.as_mut_ptr(),
+            data64: null_mut(),
+            channel_count: 2,
+            latency: 0,
+            constant_mask: 0,
+        };
+        let mut output = input;
+
+        let in_events = clap_input_events {
+            ctx: null_mut(),
+            size: Some(Self::input_events_size),
+            get: Some(Self::input_events_get),
+        };
+
+        let out_events = clap_output_events {
+            ctx: null_mut(),
+            try_push: Some(Self::output_events_try_push),
+        };
+
+        let process = clap_process {
+            steady_time: -1,
+            frames_count,
+            transport: null(),
+            audio_inputs: &input,
+            audio_outputs: &mut output,
+            audio_inputs_count: 1,
+            audio_outputs_count: 1,
+            in_events: &in_events,
+            out_events: &out_events,
+        };
+
+        unsafe { ((*self.plugin).process.unwrap())(self.plugin, &process) }
+    }
+
+    unsafe extern "C" fn get_extension(host: *const clap_host, extension_id: *const c_char) -> *const c_void {
+        let extensions = unsafe { &*((*host).host_data as *const Extensions) };
+        let extension_id = unsafe { CStr::from_ptr(extension_id) };
+
+        extensions.0.iter()
+            .find(|(id, _)| *id == extension_id)
+            .map_or(null(), |(_, extension)| *extension)
+    }
+
+    unsafe extern "C" fn request(_host: *const clap_host) {}
+
+    unsafe extern "C" fn input_events_size(_list: *const clap_input_events) -> u32 {
+        0
+    }
+
+    unsafe extern "C" fn input_events_get(_list: *const clap_input_events, _index: u32) -> *const clap_event_header {
+        null()
+    }
+
+    unsafe extern "C" fn output_events_try_push(_list: *const clap_output_events, _event: *const clap_event_header) -> bool {
+        true
+    }
+}
+
+impl<P: ClapPlugin> Drop for TestHost<P> {
+    fn drop(&mut self) {
+        unsafe {
+            ((*self.plugin).deactivate.unwrap())(self.plugin);
+            ((*self.plugin).destroy.unwrap())(self.plugin);
+            drop(Box::from_raw(self.plugin as *mut PluginInstance<P>));
+        }
+    }
+}
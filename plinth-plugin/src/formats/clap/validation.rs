@@ -0,0 +1,97 @@
+use crate::validation::{contains_nul, option_contains_nul, validate_plugin};
+
+use super::plugin::ClapPlugin;
+
+/// Evaluated at compile time, so mistakes fail the build instead of crashing inside a host
+pub(crate) const fn validate<P: ClapPlugin>() {
+    validate_plugin::<P>();
+
+    assert!(is_reverse_dns(P::CLAP_ID), "ClapPlugin::CLAP_ID must be in reverse domain name format, for example com.vendor.plugin");
+
+    assert!(!option_contains_nul(P::MANUAL_URL), "ClapPlugin::MANUAL_URL can't contain null characters");
+    assert!(!option_contains_nul(P::SUPPORT_URL), "ClapPlugin::SUPPORT_URL can't contain null characters");
+    assert!(!option_contains_nul(P::DESCRIPTION), "ClapPlugin::DESCRIPTION can't contain null characters");
+
+    assert!(has_main_feature::<P>(), "Plugin::CATEGORIES or ClapPlugin::FEATURES must include a main category");
+}
+
+const fn is_reverse_dns(id: &str) -> bool {
+    if contains_nul(id) {
+        return false;
+    }
+
+    let bytes = id.as_bytes();
+
+    let mut separator_count = 0;
+    let mut segment_len = 0;
+
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'.' => {
+                if segment_len == 0 {
+                    return false;
+                }
+
+                separator_count += 1;
+                segment_len = 0;
+            },
+
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => {
+                segment_len += 1;
+            },
+
+            _ => {
+                return false;
+            },
+        }
+
+        index += 1;
+    }
+
+    separator_count > 0 && segment_len > 0
+}
+
+const fn has_main_feature<P: ClapPlugin>() -> bool {
+    let mut index = 0;
+
+    if P::FEATURES.is_empty() {
+        while index < P::CATEGORIES.len() {
+            if P::CATEGORIES[index].feature().is_main() {
+                return true;
+            }
+
+            index += 1;
+        }
+    } else {
+        while index < P::FEATURES.len() {
+            if P::FEATURES[index].is_main() {
+                return true;
+            }
+
+            index += 1;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_reverse_dns;
+
+    #[test]
+    fn reverse_dns() {
+        assert!(is_reverse_dns("com.vendor.plugin"));
+        assert!(is_reverse_dns("viiri-audio.gain-example"));
+        assert!(is_reverse_dns("com.vendor.plugin_2"));
+
+        assert!(!is_reverse_dns(""));
+        assert!(!is_reverse_dns("plugin"));
+        assert!(!is_reverse_dns(".com.vendor"));
+        assert!(!is_reverse_dns("com.vendor."));
+        assert!(!is_reverse_dns("com..vendor"));
+        assert!(!is_reverse_dns("com.vendor plugin"));
+        assert!(!is_reverse_dns("com.vendor\0"));
+    }
+}
@@ -4,14 +4,19 @@ mod event;
 mod factory;
 mod host;
 mod macros;
+mod module_info;
 mod parameters;
 mod plugin;
+#[cfg(target_os="linux")]
+mod run_loop;
 mod stream;
 mod subcategories;
 mod transport;
+mod validation;
 mod view;
 
 pub use error::Error;
 pub use factory::Factory;
+pub use module_info::ModuleInfo;
 pub use plugin::Vst3Plugin;
 pub use subcategories::Subcategory;
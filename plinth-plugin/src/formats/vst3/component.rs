@@ -10,30 +10,40 @@ use atomic_refcell::AtomicRefCell;
 use plinth_core::signals::ptr_signal::{PtrSignal, PtrSignalMut};
 use plinth_core::signals::signal::SignalMut;
 use vst3::Steinberg::Vst::ControllerNumbers_::kPitchBend;
-use vst3::Steinberg::Vst::{CtrlNumber, IMidiMapping, IMidiMappingTrait};
-use vst3::{ComPtr, ComRef};
+use vst3::Steinberg::Vst::{CtrlNumber, IMidiMapping, IMidiMappingTrait, INoteExpressionController, IPlugInterfaceSupport, IPlugInterfaceSupportTrait};
+use vst3::Steinberg::Vst::ChannelContext::IInfoListener;
+use vst3::{ComPtr, ComRef, Guid, Interface};
 use vst3::Steinberg::{int16, int32, kInvalidArgument, kNoInterface, kResultFalse, kResultOk, kResultTrue, tresult, uint32, FIDString, FUnknown, IBStream, IPlugView, IPluginBaseTrait, TBool, TUID};
 use vst3::Steinberg::Vst::{kInfiniteTail, kNoParentUnitId, kNoProgramListId, kNoTail, BusDirection, BusDirections_, BusInfo, BusInfo_::BusFlags_, BusTypes_, CString, IAudioProcessor, IAudioProcessorTrait, IComponent, IComponentHandler, IComponentTrait, IEditController, IEditController2, IEditController2Trait, IEditControllerTrait, IHostApplication, IHostApplicationTrait, IProcessContextRequirements, IProcessContextRequirementsTrait, IProcessContextRequirements_, IUnitInfo, IUnitInfoTrait, IoMode, IoModes_, KnobMode, MediaType, MediaTypes_, ParamID, ParamValue, ParameterInfo_, ProcessData, ProcessSetup, ProgramListID, ProgramListInfo, RoutingInfo, SpeakerArr, SpeakerArrangement, String128, SymbolicSampleSizes_, TChar, UnitID, UnitInfo, ViewType::kEditor};
 use widestring::U16CStr;
 
+use crate::bypass::Bypass;
 use crate::formats::PluginFormat;
-use crate::host::HostInfo;
+use crate::host::{HostCapabilities, HostCapability, HostInfo};
 use crate::vst3::parameters::parameter_change_to_event;
-use crate::{ParameterId, Parameters, ProcessMode, ProcessState, Processor};
+use crate::{Event, ParameterId, Parameters, ProcessMode, ProcessState, Processor};
 use crate::editor::NoEditor;
 use crate::parameters::{group::{self, ParameterGroupRef}, has_duplicates, info::ParameterInfo};
+use crate::plugin::reported_latency;
 use crate::processor::ProcessorConfig;
+use crate::realtime::RealtimeRegion;
 use crate::string::{char16_to_string, copy_str_to_char16};
 use crate::vst3::{event::EventIterator, parameters::ParameterChangeIterator};
 
 use super::{plugin::Vst3Plugin, stream::Stream, view::View};
 
+#[cfg(target_os="linux")]
+type MainThreadTimer = super::run_loop::RunLoopTimer;
+#[cfg(not(target_os="linux"))]
+use crate::main_thread::MainThreadTimer;
+
 const ROOT_UNIT_NAME: &str  = "Root";
 const ROOT_UNIT_ID: i32     = 0;
 const FIRST_UNIT_ID: i32    = 1;
 
 pub struct AudioThreadState<P: Vst3Plugin> {
     processor: AtomicRefCell<Option<P::Processor>>,
+    bypass: AtomicRefCell<Option<Bypass>>,
     aux_active: AtomicBool,
 }
 
@@ -41,6 +51,7 @@ impl<P: Vst3Plugin> Default for AudioThreadState<P> {
     fn default() -> Self {
         Self {
             processor: Default::default(),
+            bypass: Default::default(),
             aux_active: true.into(),
         }
     }
@@ -57,6 +68,7 @@ pub struct PluginComponent<P: Vst3Plugin> {
     processing: AtomicBool,
     tail_length: AtomicU32,
     component_handler: Rc<RefCell<Option<ComPtr<IComponentHandler>>>>,
+    main_thread_timer: RefCell<Option<MainThreadTimer>>,
 
     audio_thread_state: AudioThreadState<P>,
 }
@@ -74,6 +86,7 @@ impl<P: Vst3Plugin + 'static> PluginComponent<P> {
             processing: AtomicBool::new(false),
             tail_length: AtomicU32::new(0),
             component_handler: Default::default(),
+            main_thread_timer: Default::default(),
 
             audio_thread_state: Default::default(),
         }
@@ -102,21 +115,64 @@ impl<P: Vst3Plugin> IPluginBaseTrait for PluginComponent<P> {
             return kResultOk;
         }
 
-        // Get plugin name if available
+        // Get host name and capabilities if available
         let mut host_name = None;
+        let host_capabilities = HostCapabilities::default();
+        // IPlugFrame always has resizeView()
+        host_capabilities.set(HostCapability::Resize, true);
+
+        let context = unsafe { ComRef::from_raw(context) };
 
-        if let Some(context) = unsafe { ComRef::from_raw(context) } && let Some(host_application) = context.cast::<IHostApplication>() {
+        if let Some(context) = &context && let Some(host_application) = context.cast::<IHostApplication>() {
             let mut name = [0; 128];
             
             if unsafe { host_application.getName(&mut name) == kResultOk } && let Some(name) = char16_to_string(&name) {
                 host_name = Some(name);
             }
+
+            if let Some(interface_support) = host_application.cast::<IPlugInterfaceSupport>() {
+                let supports = |iid: &Guid| unsafe { interface_support.isPlugInterfaceSupported(iid as *const Guid as *const TUID) == kResultOk };
+
+                host_capabilities.set(HostCapability::NoteExpressions, supports(&INoteExpressionController::IID));
+                host_capabilities.set(HostCapability::TrackInfo, supports(&IInfoListener::IID));
+            }
         }
 
+        // Calls on_main_thread() regularly and when woken, whether or not the editor is open
+        let on_main_thread = {
+            let plugin = self.plugin.clone();
+
+            move || {
+                // The plugin can be borrowed already if the host runs the loop from inside a plugin call
+                if let Ok(mut plugin) = plugin.try_borrow_mut() && let Some(plugin) = plugin.as_mut() {
+                    plugin.on_main_thread();
+                }
+            }
+        };
+
+        // Linux hosts provide a run loop through the host context, some only through the editor's frame
+        #[cfg(target_os="linux")]
+        let main_thread_timer = context
+            .and_then(|context| context.cast::<vst3::Steinberg::Linux::IRunLoop>())
+            .and_then(|run_loop| MainThreadTimer::new(run_loop, on_main_thread));
+        #[cfg(not(target_os="linux"))]
+        let main_thread_timer = MainThreadTimer::new(on_main_thread);
+
+        let main_thread_waker = main_thread_timer.as_ref()
+            .map(MainThreadTimer::waker)
+            .unwrap_or_default();
+        *self.main_thread_timer.borrow_mut() = main_thread_timer;
+
         // Create plugin and find parameter info
         let host_info = HostInfo {
             name: host_name,
+            // VST3 hosts don't report these
+            vendor: None,
+            version: None,
+            url: None,
             format: PluginFormat::Vst3,
+            main_thread_waker,
+            capabilities: host_capabilities,
         };
 
         let plugin = P::new(host_info);
@@ -171,6 +227,7 @@ impl<P: Vst3Plugin> IPluginBaseTrait for PluginComponent<P> {
     unsafe fn terminate(&self) -> tresult {
         log::trace!("IPluginBase::terminate");
 
+        *self.main_thread_timer.borrow_mut() = None;
         *self.plugin.borrow_mut() = None;
         self.parameter_info.borrow_mut().clear();        
         self.parameter_groups.borrow_mut().clear();        
@@ -233,7 +290,7 @@ impl<P: Vst3Plugin> IAudioProcessorTrait for PluginComponent<P> {
     unsafe fn getLatencySamples(&self) -> uint32 {
         log::trace!("IAudioProcessor::getLatencySamples");
         let plugin = self.plugin.borrow();
-        plugin.as_ref().map(|plugin| plugin.latency() as _).unwrap_or_default()
+        plugin.as_ref().map(|plugin| reported_latency(plugin) as _).unwrap_or_default()
     }
 
     unsafe fn setupProcessing(&self, setup: *mut ProcessSetup) -> tresult {
@@ -258,6 +315,10 @@ impl<P: Vst3Plugin> IAudioProcessorTrait for PluginComponent<P> {
         let mut processor = self.audio_thread_state.processor.borrow_mut();
         if let Some(processor) = processor.as_mut() && !processing {
             processor.reset();
+
+            if let Some(bypass) = self.audio_thread_state.bypass.borrow_mut().as_mut() {
+                bypass.reset();
+            }
         }
 
         kResultOk
@@ -265,11 +326,12 @@ impl<P: Vst3Plugin> IAudioProcessorTrait for PluginComponent<P> {
 
     // Called from the audio thread
     unsafe fn process(&self, data: *mut ProcessData) -> tresult {
+        let _realtime = RealtimeRegion::enter();
         let data = unsafe { &mut *data };
 
         let parameter_change_iterator = ParameterChangeIterator::new(data.inputParameterChanges, *self.pitch_bend_parameter_ids.borrow());
         let event_iterator = EventIterator::new(data.inputEvents);
-        let all_events = event_iterator.chain(parameter_change_iterator);
+        let all_events = Event::merge_in_time_order(event_iterator, parameter_change_iterator);
 
         let mut processor = self.audio_thread_state.processor.borrow_mut();
         let Some(processor) = processor.as_mut() else {
@@ -304,6 +366,10 @@ impl<P: Vst3Plugin> IAudioProcessorTrait for PluginComponent<P> {
             None
         };
 
+        // Silent channels are also constant
+        let aux_silence_flags = if P::HAS_AUX_INPUT && aux_active { inputs[1].silenceFlags } else { 0 };
+        processor.set_input_constant_mask(main_input.silenceFlags, aux_silence_flags);
+
         let main_input = unsafe { PtrSignal::from_pointers(main_input.numChannels as usize, data.numSamples as usize, main_input.__field0.channelBuffers32 as _) };
         let mut main_output = unsafe { PtrSignalMut::from_pointers(main_output.numChannels as usize, data.numSamples as usize, main_output.__field0.channelBuffers32) };
 
@@ -320,7 +386,10 @@ impl<P: Vst3Plugin> IAudioProcessorTrait for PluginComponent<P> {
             Some(unsafe { &*data.processContext }.into())
         };
 
-        let process_state = processor.process(&mut main_output, aux_input.as_ref(), transport, all_events);
+        let process_state = match self.audio_thread_state.bypass.borrow_mut().as_mut() {
+            Some(bypass) => bypass.process(processor, P::PROCESS_WHILE_BYPASSED, &mut main_output, aux_input.as_ref(), transport, all_events),
+            None => processor.process(&mut main_output, aux_input.as_ref(), transport, all_events),
+        };
 
         let tail_length = match process_state {
             ProcessState::Error => {
@@ -328,7 +397,7 @@ impl<P: Vst3Plugin> IAudioProcessorTrait for PluginComponent<P> {
                 return kResultFalse;
             },
 
-            ProcessState::Normal | ProcessState::Tail(0) => kNoTail,
+            ProcessState::Normal | ProcessState::Sleep | ProcessState::Tail(0) => kNoTail,
             ProcessState::Tail(tail) => tail as _,
             ProcessState::KeepAlive => kInfiniteTail,
         };
@@ -443,10 +512,16 @@ impl<P: Vst3Plugin> IComponentTrait for PluginComponent<P> {
         let active = state > 0;
         let mut processor = self.audio_thread_state.processor.borrow_mut();
 
+        let mut bypass = self.audio_thread_state.bypass.borrow_mut();
+
         if active {
-            *processor = Some(plugin.create_processor(self.processor_config.borrow().clone()));
+            let config = self.processor_config.borrow().clone();
+            *processor = Some(plugin.create_processor(config.clone()));
+            // Creating the processor can change the latency the bypass delays by
+            *bypass = Bypass::for_plugin(plugin, &config, 2);
         } else {
             *processor = None;
+            *bypass = None;
         }
 
         kResultOk
@@ -668,6 +743,7 @@ impl<P: Vst3Plugin + 'static> IEditControllerTrait for PluginComponent<P> {
         let view = View::<P>::new(
             self.plugin.clone(),
             self.component_handler.clone(),
+            self.main_thread_timer.borrow().is_none(),
         );
 
         view.to_com_ptr::<IPlugView>().unwrap().into_raw()
@@ -24,38 +24,47 @@ impl Iterator for EventIterator<'_> {
     fn next(&mut self) -> Option<Self::Item> {
         let event_list = self.event_list?;
 
-        if self.index >= unsafe { event_list.getEventCount() } as usize {
-            return None;
-        }
+        loop {
+            if self.index >= unsafe { event_list.getEventCount() } as usize {
+                return None;
+            }
 
-        let mut event: vst3::Steinberg::Vst::Event = unsafe { mem::zeroed() };
-        let result = unsafe { event_list.getEvent(self.index as _, &mut event) };
-        if result != kResultOk {
-            return None;
-        }
+            let mut event: vst3::Steinberg::Vst::Event = unsafe { mem::zeroed() };
+            let result = unsafe { event_list.getEvent(self.index as _, &mut event) };
+            if result != kResultOk {
+                return None;
+            }
+
+            self.index += 1;
+
+            let sample_offset = event.sampleOffset.max(0) as usize;
+
+            match event.r#type as _ {
+                Vst::Event_::EventTypes_::kNoteOnEvent => unsafe {
+                    return Some(Event::NoteOn {
+                        sample_offset,
+                        channel: event.__field0.noteOn.channel,
+                        key: event.__field0.noteOn.pitch,
+                        note: event.__field0.noteOn.noteId,
+                        velocity: event.__field0.noteOn.velocity as _,
+                    });
+                },
+
+                Vst::Event_::EventTypes_::kNoteOffEvent => unsafe {
+                    return Some(Event::NoteOff {
+                        sample_offset,
+                        channel: event.__field0.noteOff.channel,
+                        key: event.__field0.noteOff.pitch,
+                        note: event.__field0.noteOff.noteId,
+                        velocity: event.__field0.noteOff.velocity as _,
+                    });
+                },
 
-        self.index += 1;
-
-        match event.r#type as _ {
-            Vst::Event_::EventTypes_::kNoteOnEvent => unsafe {
-                Some(Event::NoteOn {
-                    channel: event.__field0.noteOn.channel,
-                    key: event.__field0.noteOn.pitch,
-                    note: event.__field0.noteOn.noteId,
-                    velocity: event.__field0.noteOn.velocity as _,
-                })
-            },
-
-            Vst::Event_::EventTypes_::kNoteOffEvent => unsafe {
-                Some(Event::NoteOff {
-                    channel: event.__field0.noteOff.channel,
-                    key: event.__field0.noteOff.pitch,
-                    note: event.__field0.noteOn.noteId,
-                    velocity: event.__field0.noteOff.velocity as _,
-                })
-            },
-
-            _ => None
+                // Skip event types we don't handle instead of ending the iteration
+                _ => {
+                    continue;
+                },
+            }
         }
     }
 }
@@ -4,7 +4,7 @@ use vst3::{ComWrapper, Steinberg::{int32, kInvalidArgument, kResultOk, tresult,
 
 use crate::string::{copy_str_to_char16, copy_str_to_char8, copy_u128_to_char8};
 
-use super::{plugin::Vst3Plugin, component::PluginComponent};
+use super::{plugin::Vst3Plugin, component::PluginComponent, subcategories::plugin_subcategories, validation::validate};
 
 pub struct Factory<P: Vst3Plugin> {
     _phantom_plugin: PhantomData<P>,
@@ -14,6 +14,8 @@ impl<P: Vst3Plugin + 'static> Factory<P> {
     // This is a bit special
     #[allow(clippy::new_ret_no_self)]
     pub fn new() -> *mut IPluginFactory {
+        const { validate::<P>() };
+
         let factory = Self {
             _phantom_plugin: PhantomData,
         };
@@ -23,6 +25,14 @@ impl<P: Vst3Plugin + 'static> Factory<P> {
             .unwrap()
             .into_raw() as _
     }    
+
+    fn subcategory_string() -> String {
+        plugin_subcategories::<P>()
+            .iter()
+            .map(|subcategory| subcategory.to_str())
+            .collect::<Vec<_>>()
+            .join("|")
+    }
 }
 
 impl<P: Vst3Plugin> vst3::Class for Factory<P> {
@@ -113,9 +123,11 @@ impl<P: Vst3Plugin + 'static> IPluginFactory2Trait for Factory<P> {
         copy_u128_to_char8(&P::CLASS_ID, &mut local_info.cid);
         copy_str_to_char8(P::NAME, &mut local_info.name);
         copy_str_to_char8(P::VERSION, &mut local_info.version);
+        copy_str_to_char8(P::VENDOR, &mut local_info.vendor);
 
         copy_str_to_char8("Audio Module Class", &mut local_info.category);
         copy_str_to_char8(unsafe { CStr::from_ptr(SDKVersionString).to_str().unwrap() }, &mut local_info.sdkVersion);
+        copy_str_to_char8(&Self::subcategory_string(), &mut local_info.subCategories);
 
         // We have to do a workaround like this for FL Studio which is giving us unaligned addresses
         unsafe { std::ptr::write_unaligned(info, local_info) };
@@ -141,12 +153,8 @@ impl<P: Vst3Plugin + 'static> IPluginFactory3Trait for Factory<P> {
         copy_str_to_char8("Audio Module Class", &mut local_info.category);
         copy_str_to_char16(unsafe { CStr::from_ptr(SDKVersionString).to_str().unwrap() }, &mut local_info.sdkVersion);
 
-        let subcategory_string = P::SUBCATEGORIES
-            .iter()
-            .map(|subcategory| subcategory.to_str())
-            .collect::<Vec<_>>()
-            .join("|");
-        copy_str_to_char8(&subcategory_string, &mut local_info.subCategories);
+        copy_str_to_char16(P::VENDOR, &mut local_info.vendor);
+        copy_str_to_char8(&Self::subcategory_string(), &mut local_info.subCategories);
 
         // We have to do a workaround like this for FL Studio which is giving us unaligned addresses
         unsafe { std::ptr::write_unaligned(info, local_info) };
@@ -5,6 +5,15 @@ macro_rules! export_vst3 {
         pub extern "system" fn GetPluginFactory() -> *mut ::std::ffi::c_void {
             ::plinth_plugin::vst3::Factory::<$plugin>::new() as _
         }
+
+        /// Used by the bundler to write moduleinfo.json
+        #[unsafe(no_mangle)]
+        pub extern "C" fn PlinthGetModuleInfo() -> *const ::std::ffi::c_char {
+            static MODULE_INFO: ::std::sync::OnceLock<::std::ffi::CString> = ::std::sync::OnceLock::new();
+
+            MODULE_INFO.get_or_init(|| ::std::ffi::CString::new(::plinth_plugin::vst3::ModuleInfo::new::<$plugin>().to_json()).unwrap())
+                .as_ptr()
+        }
         
         #[cfg(target_os="windows")]
         #[unsafe(no_mangle)]
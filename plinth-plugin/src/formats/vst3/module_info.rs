@@ -0,0 +1,260 @@
+use std::{ffi::CStr, fmt::Write, path::Path};
+
+use vst3::Steinberg::{PClassInfo_::ClassCardinality_::kManyInstances, Vst::SDKVersionString};
+
+use super::{plugin::Vst3Plugin, subcategories::plugin_subcategories, validation::validate};
+
+/// Contents of `moduleinfo.json`, which hosts read to scan plugins without loading them
+///
+/// `cargo xtask bundle` writes this automatically. To generate it in a custom build, call
+/// `ModuleInfo::new::<MyPlugin>().write(path)` from a build script or a small binary linked against the plugin.
+pub struct ModuleInfo {
+    name: &'static str,
+    version: &'static str,
+    vendor: &'static str,
+    url: &'static str,
+    email: &'static str,
+    class_id: u128,
+    subcategories: Vec<&'static str>,
+    compatibility_class_ids: Vec<u128>,
+}
+
+impl ModuleInfo {
+    pub fn new<P: Vst3Plugin>() -> Self {
+        const { validate::<P>() };
+
+        Self {
+            name: P::NAME,
+            version: P::VERSION,
+            vendor: P::VENDOR,
+            url: P::URL.unwrap_or_default(),
+            email: P::EMAIL.unwrap_or_default(),
+            class_id: P::CLASS_ID,
+            subcategories: plugin_subcategories::<P>().iter().map(|subcategory| subcategory.to_str()).collect(),
+            compatibility_class_ids: P::COMPATIBILITY_CLASS_IDS.to_vec(),
+        }
+    }
+
+    /// Add a class id that this plugin replaces, in addition to `Vst3Plugin::COMPATIBILITY_CLASS_IDS`
+    pub fn with_compatibility_class_id(mut self, class_id: u128) -> Self {
+        self.compatibility_class_ids.push(class_id);
+        self
+    }
+
+    pub fn to_json(&self) -> String {
+        let sdk_version = unsafe { CStr::from_ptr(SDKVersionString) }.to_str().unwrap();
+
+        let subcategories = self.subcategories.iter()
+            .map(|subcategory| json_string(subcategory))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let compatibility = if self.compatibility_class_ids.is_empty() {
+            String::new()
+        } else {
+            let old_class_ids = self.compatibility_class_ids.iter()
+                .map(|&class_id| json_string(&class_id_string(class_id)))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            format!(
+r#"
+    {{
+      "New": {class_id},
+      "Old": [{old_class_ids}]
+    }}
+  "#,
+                class_id = json_string(&class_id_string(self.class_id)),
+            )
+        };
+
+        format!(
+r#"{{
+  "Name": {name},
+  "Version": {version},
+  "Factory Info": {{
+    "Vendor": {vendor},
+    "URL": {url},
+    "E-Mail": {email},
+    "Flags": {{
+      "Unicode": true,
+      "Classes Discardable": false,
+      "Component Non Discardable": false
+    }}
+  }},
+  "Compatibility": [{compatibility}],
+  "Classes": [
+    {{
+      "CID": {class_id},
+      "Category": "Audio Module Class",
+      "Name": {name},
+      "Vendor": {vendor},
+      "Version": {version},
+      "SDKVersion": {sdk_version},
+      "Sub Categories": [{subcategories}],
+      "Class Flags": 0,
+      "Cardinality": {cardinality},
+      "Snapshots": []
+    }}
+  ]
+}}
+"#,
+            name = json_string(self.name),
+            version = json_string(self.version),
+            vendor = json_string(self.vendor),
+            url = json_string(self.url),
+            email = json_string(self.email),
+            class_id = json_string(&class_id_string(self.class_id)),
+            sdk_version = json_string(sdk_version),
+            cardinality = kManyInstances,
+        )
+    }
+
+    /// Usually written to `<bundle>.vst3/Contents/Resources/moduleinfo.json`
+    pub fn write(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_json())
+    }
+}
+
+/// Formats a class id the way the SDK's FUID string conversion reads it back
+fn class_id_string(class_id: u128) -> String {
+    format_class_id(class_id, cfg!(target_os="windows"))
+}
+
+fn format_class_id(class_id: u128, com_layout: bool) -> String {
+    let mut bytes = class_id.to_be_bytes();
+
+    // Windows UIDs use the COM layout, where the first 8 bytes are stored little endian
+    if com_layout {
+        bytes[0..4].reverse();
+        bytes[4..6].reverse();
+        bytes[6..8].reverse();
+    }
+
+    bytes.iter()
+        .fold(String::with_capacity(32), |mut string, byte| {
+            write!(string, "{byte:02X}").unwrap();
+            string
+        })
+}
+
+fn json_string(value: &str) -> String {
+    let mut string = String::with_capacity(value.len() + 2);
+    string.push('"');
+
+    for character in value.chars() {
+        match character {
+            '"' => string.push_str("\\\""),
+            '\\' => string.push_str("\\\\"),
+            '\n' => string.push_str("\\n"),
+            '\r' => string.push_str("\\r"),
+            '\t' => string.push_str("\\t"),
+            character if character < ' ' => write!(string, "\\u{:04x}", character as u32).unwrap(),
+            character => string.push(character),
+        }
+    }
+
+    string.push('"');
+    string
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+
+    use vst3::Steinberg::Vst::SDKVersionString;
+
+    use super::{class_id_string, format_class_id, json_string, ModuleInfo};
+
+    const CLASS_ID: u128 = 0x01234567_89ABCDEF_FEDCBA98_76543210;
+    const OLD_CLASS_ID: u128 = 0xFFEEDDCC_BBAA9988_77665544_33221100;
+
+    // Layout of the moduleinfo.json files the SDK's moduleinfotool writes
+    const GOLDEN: &str = r#"{
+  "Name": "Gain \"Pro\"",
+  "Version": "1.2.3",
+  "Factory Info": {
+    "Vendor": "Plinth",
+    "URL": "https://example.com",
+    "E-Mail": "",
+    "Flags": {
+      "Unicode": true,
+      "Classes Discardable": false,
+      "Component Non Discardable": false
+    }
+  },
+  "Compatibility": [
+    {
+      "New": "$NEW",
+      "Old": ["$OLD"]
+    }
+  ],
+  "Classes": [
+    {
+      "CID": "$NEW",
+      "Category": "Audio Module Class",
+      "Name": "Gain \"Pro\"",
+      "Vendor": "Plinth",
+      "Version": "1.2.3",
+      "SDKVersion": "$SDK",
+      "Sub Categories": ["Fx", "Dynamics"],
+      "Class Flags": 0,
+      "Cardinality": 2147483647,
+      "Snapshots": []
+    }
+  ]
+}
+"#;
+
+    #[test]
+    fn golden_json() {
+        let module_info = ModuleInfo {
+            name: "Gain \"Pro\"",
+            version: "1.2.3",
+            vendor: "Plinth",
+            url: "https://example.com",
+            email: "",
+            class_id: CLASS_ID,
+            subcategories: vec!["Fx", "Dynamics"],
+            compatibility_class_ids: Vec::new(),
+        }
+        .with_compatibility_class_id(OLD_CLASS_ID);
+
+        let sdk_version = unsafe { CStr::from_ptr(SDKVersionString) }.to_str().unwrap();
+        let expected = GOLDEN
+            .replace("$NEW", &class_id_string(CLASS_ID))
+            .replace("$OLD", &class_id_string(OLD_CLASS_ID))
+            .replace("$SDK", sdk_version);
+
+        assert_eq!(module_info.to_json(), expected);
+    }
+
+    #[test]
+    fn no_compatibility_entries() {
+        let module_info = ModuleInfo {
+            name: "Gain",
+            version: "1.0.0",
+            vendor: "Plinth",
+            url: "",
+            email: "",
+            class_id: CLASS_ID,
+            subcategories: vec!["Fx"],
+            compatibility_class_ids: Vec::new(),
+        };
+
+        assert!(module_info.to_json().contains(r#""Compatibility": [],"#));
+    }
+
+    #[test]
+    fn class_id_layouts() {
+        assert_eq!(format_class_id(CLASS_ID, false), "0123456789ABCDEFFEDCBA9876543210");
+        assert_eq!(format_class_id(CLASS_ID, true), "67452301AB89EFCDFEDCBA9876543210");
+    }
+
+    #[test]
+    fn json_escaping() {
+        assert_eq!(json_string("Plain"), r#""Plain""#);
+        assert_eq!(json_string("\"Quoted\" \\ path"), r#""\"Quoted\" \\ path""#);
+        assert_eq!(json_string("Tab\tNewline\n\u{1}"), r#""Tab\tNewline\n\u0001""#);
+    }
+}
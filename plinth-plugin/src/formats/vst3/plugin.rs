@@ -4,7 +4,11 @@ use super::subcategories::Subcategory;
 
 pub trait Vst3Plugin : Plugin {
     const CLASS_ID: u128;
-    const SUBCATEGORIES: &'static [Subcategory];
+    /// Overrides the subcategories mapped from `Plugin::CATEGORIES`
+    const SUBCATEGORIES: &'static [Subcategory] = &[];
 
     const EMAIL: Option<&'static str> = None;
+
+    /// Class ids of earlier versions that this plugin replaces, written to moduleinfo.json
+    const COMPATIBILITY_CLASS_IDS: &'static [u128] = &[];
 }
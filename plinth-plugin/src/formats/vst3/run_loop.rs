@@ -0,0 +1,108 @@
+use std::{cell::RefCell, io::{ErrorKind, Read, Write}, os::{fd::AsRawFd, unix::net::UnixStream}, sync::Arc};
+
+use vst3::{ComPtr, ComWrapper};
+use vst3::Steinberg::Linux::{FileDescriptor, IEventHandler, IEventHandlerTrait, IRunLoop, IRunLoopTrait, ITimerHandler, ITimerHandlerTrait};
+
+use crate::{main_thread::MAIN_THREAD_TIMER_MILLISECONDS, MainThreadWaker};
+
+/// Calls `callback` regularly from the host's run loop, and soon after its waker wakes
+pub(super) struct RunLoopTimer {
+    run_loop: ComPtr<IRunLoop>,
+    timer_handler: ComPtr<ITimerHandler>,
+    event_handler: ComPtr<IEventHandler>,
+    pipe: Arc<WakePipe>,
+}
+
+impl RunLoopTimer {
+    pub(super) fn new(run_loop: ComPtr<IRunLoop>, callback: impl FnMut() + 'static) -> Option<Self> {
+        let (reader, writer) = UnixStream::pair().ok()?;
+        reader.set_nonblocking(true).ok()?;
+        writer.set_nonblocking(true).ok()?;
+
+        // Wakers keep both ends open, so writes never go to a closed socket
+        let pipe = Arc::new(WakePipe { reader, writer });
+
+        let handler = ComWrapper::new(Handler {
+            callback: RefCell::new(Box::new(callback)),
+            pipe: pipe.clone(),
+        });
+
+        let timer_handler = handler.to_com_ptr::<ITimerHandler>()?;
+        let event_handler = handler.to_com_ptr::<IEventHandler>()?;
+
+        unsafe {
+            run_loop.registerTimer(timer_handler.as_ptr(), MAIN_THREAD_TIMER_MILLISECONDS);
+            run_loop.registerEventHandler(event_handler.as_ptr(), pipe.reader.as_raw_fd() as _);
+        }
+
+        Some(Self {
+            run_loop,
+            timer_handler,
+            event_handler,
+            pipe,
+        })
+    }
+
+    pub(super) fn waker(&self) -> MainThreadWaker {
+        let pipe = self.pipe.clone();
+
+        MainThreadWaker::new(move || {
+            // A full pipe already has a wake pending
+            let _ = (&pipe.writer).write(&[0]);
+        })
+    }
+}
+
+impl Drop for RunLoopTimer {
+    fn drop(&mut self) {
+        unsafe {
+            self.run_loop.unregisterEventHandler(self.event_handler.as_ptr());
+            self.run_loop.unregisterTimer(self.timer_handler.as_ptr());
+        }
+    }
+}
+
+struct WakePipe {
+    reader: UnixStream,
+    writer: UnixStream,
+}
+
+struct Handler {
+    callback: RefCell<Box<dyn FnMut()>>,
+    pipe: Arc<WakePipe>,
+}
+
+impl Handler {
+    fn call(&self) {
+        // Skip nested calls if the callback runs the run loop itself
+        if let Ok(mut callback) = self.callback.try_borrow_mut() {
+            callback();
+        }
+    }
+}
+
+impl vst3::Class for Handler {
+    type Interfaces = (ITimerHandler, IEventHandler);
+}
+
+impl ITimerHandlerTrait for Handler {
+    unsafe fn onTimer(&self) {
+        self.call();
+    }
+}
+
+impl IEventHandlerTrait for Handler {
+    unsafe fn onFDIsSet(&self, _fd: FileDescriptor) {
+        let mut buffer = [0; 64];
+        loop {
+            match (&self.pipe.reader).read(&mut buffer) {
+                Ok(0) => break,
+                Ok(_) => continue,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            }
+        }
+
+        self.call();
+    }
+}
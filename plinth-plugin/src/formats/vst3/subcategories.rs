@@ -1,3 +1,8 @@
+use crate::Category;
+
+use super::plugin::Vst3Plugin;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Subcategory {
     Fx,
     Instrument,
@@ -31,7 +36,12 @@ pub enum Subcategory {
 }
 
 impl Subcategory {
-    pub fn to_str(&self) -> &str {
+    /// Hosts expect either `Fx` or `Instrument`
+    pub const fn is_main(&self) -> bool {
+        matches!(self, Subcategory::Fx | Subcategory::Instrument)
+    }
+
+    pub const fn to_str(&self) -> &'static str {
         match self {
             Subcategory::Fx => "Fx",
             Subcategory::Instrument => "Instrument",
@@ -65,3 +75,20 @@ impl Subcategory {
         }
     }
 }
+
+/// `Vst3Plugin::SUBCATEGORIES` if set, otherwise the subcategories of `Plugin::CATEGORIES` without duplicates
+pub(crate) fn plugin_subcategories<P: Vst3Plugin>() -> Vec<Subcategory> {
+    if !P::SUBCATEGORIES.is_empty() {
+        return P::SUBCATEGORIES.to_vec();
+    }
+
+    let mut subcategories = Vec::new();
+
+    for subcategory in P::CATEGORIES.iter().flat_map(Category::subcategories) {
+        if !subcategories.contains(subcategory) {
+            subcategories.push(*subcategory);
+        }
+    }
+
+    subcategories
+}
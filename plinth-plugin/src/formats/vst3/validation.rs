@@ -0,0 +1,133 @@
+use crate::validation::{option_contains_nul, validate_plugin};
+
+use super::{plugin::Vst3Plugin, subcategories::Subcategory};
+
+// Field sizes from the SDK's PFactoryInfo and PClassInfo2, including the null terminator.
+// UTF-16 strings never have more code units than their UTF-8 source has bytes, so these also cover PClassInfoW.
+const NAME_SIZE: usize = 64;
+const VENDOR_SIZE: usize = 64;
+const VERSION_SIZE: usize = 64;
+const URL_SIZE: usize = 256;
+const EMAIL_SIZE: usize = 128;
+const SUBCATEGORIES_SIZE: usize = 128;
+
+/// Evaluated at compile time, so mistakes fail the build instead of crashing inside a host
+pub(crate) const fn validate<P: Vst3Plugin>() {
+    validate_plugin::<P>();
+
+    assert!(P::CLASS_ID != 0, "Vst3Plugin::CLASS_ID can't be zero");
+
+    let mut index = 0;
+    while index < P::COMPATIBILITY_CLASS_IDS.len() {
+        let class_id = P::COMPATIBILITY_CLASS_IDS[index];
+        assert!(class_id != 0, "Vst3Plugin::COMPATIBILITY_CLASS_IDS can't contain zero");
+        assert!(class_id != P::CLASS_ID, "Vst3Plugin::COMPATIBILITY_CLASS_IDS can't contain CLASS_ID");
+
+        index += 1;
+    }
+
+    assert!(P::NAME.len() < NAME_SIZE, "Plugin::NAME is too long for VST3, the limit is 63 bytes");
+    assert!(P::VENDOR.len() < VENDOR_SIZE, "Plugin::VENDOR is too long for VST3, the limit is 63 bytes");
+    assert!(P::VERSION.len() < VERSION_SIZE, "Plugin::VERSION is too long for VST3, the limit is 63 bytes");
+
+    if let Some(url) = P::URL {
+        assert!(url.len() < URL_SIZE, "Plugin::URL is too long for VST3, the limit is 255 bytes");
+    }
+
+    assert!(!option_contains_nul(P::EMAIL), "Vst3Plugin::EMAIL can't contain null characters");
+    if let Some(email) = P::EMAIL {
+        assert!(email.len() < EMAIL_SIZE, "Vst3Plugin::EMAIL is too long, the limit is 127 bytes");
+    }
+
+    assert!(has_main_subcategory::<P>(), "Plugin::CATEGORIES or Vst3Plugin::SUBCATEGORIES must include a main category");
+    assert!(subcategories_len::<P>() < SUBCATEGORIES_SIZE, "Too many VST3 subcategories, the limit is 127 bytes when joined with |");
+}
+
+const fn has_main_subcategory<P: Vst3Plugin>() -> bool {
+    if !P::SUBCATEGORIES.is_empty() {
+        return contains_main(P::SUBCATEGORIES);
+    }
+
+    let mut index = 0;
+    while index < P::CATEGORIES.len() {
+        if contains_main(P::CATEGORIES[index].subcategories()) {
+            return true;
+        }
+
+        index += 1;
+    }
+
+    false
+}
+
+const fn contains_main(subcategories: &[Subcategory]) -> bool {
+    let mut index = 0;
+    while index < subcategories.len() {
+        if subcategories[index].is_main() {
+            return true;
+        }
+
+        index += 1;
+    }
+
+    false
+}
+
+/// Length of the `|` separated subcategory string, matching `plugin_subcategories()`
+const fn subcategories_len<P: Vst3Plugin>() -> usize {
+    let mut len = 0;
+
+    if !P::SUBCATEGORIES.is_empty() {
+        let mut index = 0;
+        while index < P::SUBCATEGORIES.len() {
+            len += P::SUBCATEGORIES[index].to_str().len() + 1;
+            index += 1;
+        }
+    } else {
+        let mut category_index = 0;
+        while category_index < P::CATEGORIES.len() {
+            let subcategories = P::CATEGORIES[category_index].subcategories();
+
+            let mut index = 0;
+            while index < subcategories.len() {
+                if !is_mapped_before::<P>(subcategories[index], category_index, index) {
+                    len += subcategories[index].to_str().len() + 1;
+                }
+
+                index += 1;
+            }
+
+            category_index += 1;
+        }
+    }
+
+    // No separator after the last subcategory
+    len.saturating_sub(1)
+}
+
+/// Whether a category listed before the given position already maps to the subcategory
+const fn is_mapped_before<P: Vst3Plugin>(subcategory: Subcategory, category_index: usize, index: usize) -> bool {
+    let mut current_category_index = 0;
+    while current_category_index <= category_index {
+        let subcategories = P::CATEGORIES[current_category_index].subcategories();
+
+        let end = if current_category_index == category_index {
+            index
+        } else {
+            subcategories.len()
+        };
+
+        let mut current_index = 0;
+        while current_index < end {
+            if subcategories[current_index] as u32 == subcategory as u32 {
+                return true;
+            }
+
+            current_index += 1;
+        }
+
+        current_category_index += 1;
+    }
+
+    false
+}
@@ -16,6 +16,9 @@ pub struct ViewContext {
 
 pub struct View<P: Vst3Plugin> {
     editor: Rc<RefCell<Option<P::Editor>>>,
+    // Only set when the component has no run loop timer of its own
+    #[cfg(target_os="linux")]
+    plugin: Option<Rc<RefCell<Option<P>>>>,
     context: Rc<RefCell<ViewContext>>,
 }
 
@@ -23,6 +26,8 @@ impl<P: Vst3Plugin + 'static> View<P> {
     pub fn new(
         plugin: Rc<RefCell<Option<P>>>,
         component_handler: Rc<RefCell<Option<ComPtr<IComponentHandler>>>>,
+        #[cfg_attr(not(target_os="linux"), allow(unused_variables))]
+        call_on_main_thread: bool,
     ) -> ComWrapper<Self> {
         let context = ViewContext {
             frame: None,
@@ -36,6 +41,8 @@ impl<P: Vst3Plugin + 'static> View<P> {
         // We have a circular dependency here so need to create editor after creating host
         let view = ComWrapper::new(Self {
             editor: Default::default(),
+            #[cfg(target_os="linux")]
+            plugin: call_on_main_thread.then(|| plugin.clone()),
             context: context.clone(),
         });
 
@@ -183,6 +190,7 @@ impl<P: Vst3Plugin + 'static> IPlugViewTrait for View<P> {
             if let Some(run_loop) = frame.cast::<vst3::Steinberg::Linux::IRunLoop>() {
                 let timer_handler = vst3::ComWrapper::new(TimerHandler::<P> {
                     editor: self.editor.clone(),
+                    plugin: self.plugin.clone(),
                 });
 
                 context.timer_handler = timer_handler.to_com_ptr();
@@ -240,6 +248,7 @@ impl<P: Vst3Plugin + 'static> IPlugViewContentScaleSupportTrait for View<P> {
 #[cfg(target_os="linux")]
 struct TimerHandler<P: Vst3Plugin> {
     editor: Rc<RefCell<Option<P::Editor>>>,
+    plugin: Option<Rc<RefCell<Option<P>>>>,
 }
 
 #[cfg(target_os="linux")]
@@ -250,6 +259,11 @@ impl<P: Vst3Plugin> vst3::Class for TimerHandler<P> {
 #[cfg(target_os="linux")]
 impl<P: Vst3Plugin> vst3::Steinberg::Linux::ITimerHandlerTrait for TimerHandler<P> {
     unsafe fn onTimer(&self) {
+        // The plugin can be borrowed already if the host runs the loop from inside a plugin call
+        if let Some(plugin) = self.plugin.as_ref() && let Ok(mut plugin) = plugin.try_borrow_mut() && let Some(plugin) = plugin.as_mut() {
+            plugin.on_main_thread();
+        }
+
         if let Some(editor) = self.editor.borrow_mut().as_mut() {
             editor.on_frame();
         }
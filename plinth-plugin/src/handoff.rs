@@ -0,0 +1,242 @@
+use std::{sync::{atomic::{AtomicBool, Ordering}, Arc, Mutex}, thread::JoinHandle, time::Duration};
+
+/// Creates a lock-free channel for handing owned objects from the plugin to its processor
+///
+/// The processor returns the objects it's done with, so they can be dropped outside the audio thread,
+/// either in `Plugin::on_main_thread()` or on a `GarbageThread`.
+pub fn handoff<T: Send + 'static>(capacity: usize) -> (HandoffSender<T>, HandoffReceiver<T>) {
+    let (to_processor, from_plugin) = rtrb::RingBuffer::new(capacity);
+    // Room for everything in flight plus the object the processor currently holds
+    let (to_plugin, returned) = rtrb::RingBuffer::new(capacity + 1);
+
+    let sender = HandoffSender {
+        to_processor,
+        returned: Some(returned),
+    };
+
+    let receiver = HandoffReceiver {
+        from_plugin,
+        to_plugin,
+    };
+
+    (sender, receiver)
+}
+
+/// Main thread end of a handoff
+pub struct HandoffSender<T: Send + 'static> {
+    to_processor: rtrb::Producer<T>,
+    // Moved to a garbage thread by `collect_on()`
+    returned: Option<rtrb::Consumer<T>>,
+}
+
+impl<T: Send + 'static> HandoffSender<T> {
+    /// Gives the value back if the queue is full
+    pub fn send(&mut self, value: T) -> Result<(), T> {
+        self.to_processor.push(value)
+            .map_err(|rtrb::PushError::Full(value)| value)
+    }
+
+    /// Drops the objects the processor has returned, call this from `Plugin::on_main_thread()`
+    ///
+    /// Returns how many objects were dropped.
+    pub fn collect_garbage(&mut self) -> usize {
+        match self.returned.as_mut() {
+            Some(returned) => drain(returned),
+            None => 0,
+        }
+    }
+
+    /// Drops returned objects on a background thread from now on, instead of in `collect_garbage()`
+    pub fn collect_on(&mut self, thread: &GarbageThread) {
+        if let Some(mut returned) = self.returned.take() {
+            thread.add_collector(Box::new(move || {
+                drain(&mut returned);
+                !returned.is_abandoned()
+            }));
+        }
+    }
+}
+
+/// Audio thread end of a handoff
+pub struct HandoffReceiver<T: Send + 'static> {
+    from_plugin: rtrb::Consumer<T>,
+    to_plugin: rtrb::Producer<T>,
+}
+
+impl<T: Send + 'static> HandoffReceiver<T> {
+    pub fn receive(&mut self) -> Option<T> {
+        self.from_plugin.pop().ok()
+    }
+
+    /// Sends an object back to be dropped outside the audio thread, gives it back if the queue is full
+    pub fn retire(&mut self, value: T) -> Result<(), T> {
+        self.to_plugin.push(value)
+            .map_err(|rtrb::PushError::Full(value)| value)
+    }
+
+    /// Replaces `current` with the newest object sent by the plugin and retires the replaced ones
+    ///
+    /// Objects are only taken when they can be retired, so nothing is ever dropped here.
+    /// Returns true if `current` changed.
+    pub fn swap_in(&mut self, current: &mut T) -> bool {
+        let mut changed = false;
+
+        while self.to_plugin.slots() > 0 {
+            let Ok(mut value) = self.from_plugin.pop() else {
+                break;
+            };
+
+            std::mem::swap(current, &mut value);
+            // Can't fail since there was a free slot
+            let _ = self.to_plugin.push(value);
+            changed = true;
+        }
+
+        changed
+    }
+}
+
+/// Background thread that drops objects returned through handoffs
+///
+/// Stops and drops everything left when it goes out of scope, so keep it in the `Plugin`.
+pub struct GarbageThread {
+    shared: Arc<GarbageShared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+struct GarbageShared {
+    // Each returns false once its handoff receiver is gone
+    collectors: Mutex<Vec<Box<dyn FnMut() -> bool + Send>>>,
+    running: AtomicBool,
+}
+
+impl GarbageThread {
+    pub fn new(interval: Duration) -> Self {
+        let shared = Arc::new(GarbageShared {
+            collectors: Default::default(),
+            running: AtomicBool::new(true),
+        });
+
+        let thread = std::thread::Builder::new()
+            .name("plinth-garbage".into())
+            .spawn({
+                let shared = shared.clone();
+
+                move || {
+                    while shared.running.load(Ordering::Acquire) {
+                        shared.collect();
+                        std::thread::park_timeout(interval);
+                    }
+
+                    shared.collect();
+                }
+            })
+            .expect("Failed to spawn garbage thread");
+
+        Self {
+            shared,
+            thread: Some(thread),
+        }
+    }
+
+    fn add_collector(&self, collector: Box<dyn FnMut() -> bool + Send>) {
+        self.shared.collectors.lock().unwrap().push(collector);
+    }
+}
+
+impl Default for GarbageThread {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(100))
+    }
+}
+
+impl Drop for GarbageThread {
+    fn drop(&mut self) {
+        self.shared.running.store(false, Ordering::Release);
+
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
+
+impl GarbageShared {
+    fn collect(&self) {
+        self.collectors.lock().unwrap().retain_mut(|collector| collector());
+    }
+}
+
+fn drain<T>(consumer: &mut rtrb::Consumer<T>) -> usize {
+    let mut count = 0;
+    while consumer.pop().is_ok() {
+        count += 1;
+    }
+
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::{atomic::{AtomicUsize, Ordering}, Arc}, time::Duration};
+
+    use super::{handoff, GarbageThread};
+
+    struct Tracked(Arc<AtomicUsize>);
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn swap_and_collect() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let (mut sender, mut receiver) = handoff(2);
+
+        let mut current = Tracked(drops.clone());
+        assert!(!receiver.swap_in(&mut current));
+
+        assert!(sender.send(Tracked(drops.clone())).is_ok());
+        assert!(sender.send(Tracked(drops.clone())).is_ok());
+        assert!(sender.send(Tracked(drops.clone())).is_err());
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+
+        assert!(receiver.swap_in(&mut current));
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+
+        assert_eq!(sender.collect_garbage(), 2);
+        assert_eq!(drops.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn garbage_thread() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let (mut sender, mut receiver) = handoff(1);
+
+        {
+            let thread = GarbageThread::new(Duration::from_millis(1));
+            sender.collect_on(&thread);
+
+            assert!(receiver.retire(Tracked(drops.clone())).is_ok());
+            assert_eq!(sender.collect_garbage(), 0);
+        }
+
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn abandoned_collectors_are_removed() {
+        let thread = GarbageThread::new(Duration::from_secs(60));
+        let (mut sender, receiver) = handoff::<u32>(1);
+        sender.collect_on(&thread);
+
+        thread.shared.collect();
+        assert_eq!(thread.shared.collectors.lock().unwrap().len(), 1);
+
+        drop(receiver);
+        thread.shared.collect();
+        assert!(thread.shared.collectors.lock().unwrap().is_empty());
+    }
+}
@@ -1,11 +1,57 @@
+use std::sync::{atomic::{AtomicU32, Ordering}, Arc};
+
 use crate::ParameterId;
 use crate::formats::PluginFormat;
 use crate::parameters::ParameterValue;
+use crate::tasks::MainThreadWaker;
 
 #[derive(Clone)]
 pub struct HostInfo {
     pub name: Option<String>,
+    pub vendor: Option<String>,
+    pub version: Option<String>,
+    pub url: Option<String>,
     pub format: PluginFormat,
+    pub main_thread_waker: MainThreadWaker,
+    pub capabilities: HostCapabilities,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HostCapability {
+    /// The editor can ask the host to resize its window
+    Resize,
+    NoteExpressions,
+    /// CLAP hosts can't be asked about this, so it's never reported for CLAP
+    PolyphonicModulation,
+    RemoteControls,
+    /// The host tells the plugin about the track it's on
+    TrackInfo,
+    /// `ProcessorConfig::parallel` runs tasks on the host's threads
+    ThreadPool,
+    /// The `logging` module writes to the host's log
+    Log,
+}
+
+/// What the host supports, shared by every copy of a `HostInfo`
+///
+/// CLAP hosts can only be queried after `Plugin::new()`, so these are filled in right after it returns.
+#[derive(Clone, Debug, Default)]
+pub struct HostCapabilities {
+    flags: Arc<AtomicU32>,
+}
+
+impl HostCapabilities {
+    pub fn supports(&self, capability: HostCapability) -> bool {
+        self.flags.load(Ordering::Acquire) & (1 << capability as u32) != 0
+    }
+
+    pub(crate) fn set(&self, capability: HostCapability, supported: bool) {
+        if supported {
+            self.flags.fetch_or(1 << capability as u32, Ordering::Release);
+        } else {
+            self.flags.fetch_and(!(1 << capability as u32), Ordering::Release);
+        }
+    }
 }
 
 pub trait Host {
@@ -23,3 +69,24 @@ pub trait Host {
 
     fn mark_state_dirty(&self);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{HostCapabilities, HostCapability};
+
+    #[test]
+    fn set_and_supports() {
+        let capabilities = HostCapabilities::default();
+        let shared = capabilities.clone();
+
+        capabilities.set(HostCapability::Resize, true);
+        capabilities.set(HostCapability::Log, true);
+        assert!(shared.supports(HostCapability::Resize));
+        assert!(shared.supports(HostCapability::Log));
+        assert!(!shared.supports(HostCapability::ThreadPool));
+
+        capabilities.set(HostCapability::Resize, false);
+        assert!(!shared.supports(HostCapability::Resize));
+        assert!(shared.supports(HostCapability::Log));
+    }
+}
@@ -1,8 +1,12 @@
+pub use bypass::Bypass;
+pub use category::Category;
 pub use editor::{Editor, NoEditor};
 pub use error::Error;
 pub use event::Event;
-pub use host::{Host, HostInfo};
-pub use formats::{clap, vst3};
+pub use handoff::{handoff, GarbageThread, HandoffReceiver, HandoffSender};
+pub use host::{Host, HostCapabilities, HostCapability, HostInfo};
+pub use formats::{clap, vst3, PluginFormat};
+pub use parallel::Parallel;
 pub use parameters::{Parameters, ParameterId, ParameterValue};
 pub use parameters::bool::{BoolParameter, BoolFormatter};
 pub use parameters::enums::{Enum, EnumParameter};
@@ -12,8 +16,9 @@ pub use parameters::int::{IntParameter, IntRange, IntFormatter};
 pub use parameters::map::ParameterMap;
 pub use parameters::parameter::Parameter;
 pub use parameters::range::ParameterRange;
-pub use plugin::Plugin;
-pub use processor::{Processor, ProcessorConfig, ProcessState, ProcessMode};
+pub use plugin::{reported_latency, Plugin};
+pub use tasks::{MainThreadWaker, Tasks};
+pub use processor::{FixedBlockProcessor, Processor, ProcessorConfig, ProcessState, ProcessMode, SleepDetector};
 pub use transport::Transport;
 
 #[cfg(target_os="macos")]
@@ -25,14 +30,23 @@ pub use plinth_core;
 pub use raw_window_handle;
 pub use xxhash_rust;
 
+mod bypass;
+mod category;
 mod editor;
 pub mod error;
 mod event;
 mod host;
+pub mod logging;
 mod formats;
+mod handoff;
+mod main_thread;
+mod parallel;
 pub mod parameters;
 mod plugin;
 mod processor;
+pub mod realtime;
 pub mod string;
+mod tasks;
 mod transport;
+mod validation;
 mod window_handle;
@@ -0,0 +1,401 @@
+use std::{cell::Cell, ffi::{CStr, CString}, fmt::Write as _, fs::File, io::Write as _, path::{Path, PathBuf}, sync::{atomic::{AtomicUsize, Ordering}, Mutex, OnceLock}, time::{SystemTime, UNIX_EPOCH}};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::{error::Error, realtime::in_realtime_region};
+
+const REALTIME_QUEUE_LEN: usize = 256;
+const REALTIME_MESSAGE_LEN: usize = 256;
+
+type HostSink = Box<dyn Fn(Level, &CStr) + Send + Sync>;
+
+/// Settings for the logger installed by `init()`
+#[derive(Clone, Debug)]
+pub struct LoggerConfig {
+    name: String,
+    level: LevelFilter,
+    directory: Option<PathBuf>,
+    max_file_size: u64,
+    max_files: usize,
+}
+
+impl LoggerConfig {
+    /// `name` is used for the log file, usually the plugin name
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            level: LevelFilter::Info,
+            directory: Some(std::env::temp_dir()),
+            max_file_size: 1024 * 1024,
+            max_files: 5,
+        }
+    }
+
+    pub fn with_level(mut self, level: LevelFilter) -> Self {
+        self.level = level;
+        self
+    }
+
+    pub fn with_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    /// Only log to the host
+    pub fn without_file(mut self) -> Self {
+        self.directory = None;
+        self
+    }
+
+    /// The file is rotated when it grows past `size` bytes, and when a new logger starts
+    pub fn with_max_file_size(mut self, size: u64) -> Self {
+        self.max_file_size = size;
+        self
+    }
+
+    /// Rotated files to keep, including the current one
+    pub fn with_max_files(mut self, count: usize) -> Self {
+        self.max_files = count.max(1);
+        self
+    }
+}
+
+/// Installs a logger that writes to the host's log when it has one, and to a rotating log file otherwise
+///
+/// Messages logged from inside a plugin call go to that instance's host, others go to every host.
+/// Messages logged on the audio thread are queued without allocating or blocking and written out later,
+/// on the next log call from another thread or on `flush()`.
+///
+/// The log file is shared by every instance in the process. Only the first call installs the logger,
+/// later calls keep its settings and return `Ok`.
+pub fn init(config: LoggerConfig) -> Result<(), Error> {
+    if LOGGER.get().is_some() {
+        return Ok(());
+    }
+
+    let mut installing = false;
+    let logger = LOGGER.get_or_init(|| {
+        installing = true;
+
+        let (producer, consumer) = rtrb::RingBuffer::new(REALTIME_QUEUE_LEN);
+
+        Logger {
+            file: Mutex::new(None),
+            realtime_producer: Mutex::new(producer),
+            realtime_consumer: Mutex::new(consumer),
+            dropped: AtomicUsize::new(0),
+        }
+    });
+
+    // Another thread got there first
+    if !installing {
+        return Ok(());
+    }
+
+    log::set_logger(logger)?;
+    log::set_max_level(config.level);
+
+    // Only opened once the logger is in place, since opening rotates the existing files
+    if let Some(directory) = config.directory.as_ref() {
+        *logger.file.lock().unwrap() = Some(LogFile::open(directory, &config)?);
+    }
+
+    Ok(())
+}
+
+/// Writes out messages queued from the audio thread, call this from the main thread
+pub fn flush() {
+    if let Some(logger) = LOGGER.get() {
+        logger.flush();
+    }
+}
+
+/// Sends log messages from the instance `id` to its host
+pub(crate) fn add_host_sink(id: usize, sink: impl Fn(Level, &CStr) + Send + Sync + 'static) {
+    HOST_SINKS.lock().unwrap().push((id, Box::new(sink)));
+}
+
+pub(crate) fn remove_host_sink(id: usize) {
+    HOST_SINKS.lock().unwrap().retain(|(sink_id, _)| *sink_id != id);
+}
+
+/// Marks the instance `id` as the source of messages logged on this thread until dropped
+pub(crate) struct InstanceScope {
+    previous: Option<usize>,
+}
+
+impl InstanceScope {
+    pub(crate) fn enter(id: usize) -> Self {
+        Self {
+            previous: CURRENT_INSTANCE.replace(Some(id)),
+        }
+    }
+}
+
+impl Drop for InstanceScope {
+    fn drop(&mut self) {
+        CURRENT_INSTANCE.set(self.previous);
+    }
+}
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+static HOST_SINKS: Mutex<Vec<(usize, HostSink)>> = Mutex::new(Vec::new());
+
+thread_local! {
+    static CURRENT_INSTANCE: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// The instance's own sink, or every sink for messages from outside a plugin call
+fn sinks_for(sinks: &[(usize, HostSink)], instance: Option<usize>) -> impl Iterator<Item = &HostSink> {
+    sinks.iter()
+        .filter(move |(id, _)| instance.is_none_or(|instance| *id == instance))
+        .map(|(_, sink)| sink)
+}
+
+struct Logger {
+    file: Mutex<Option<LogFile>>,
+    // Audio threads only try to lock, so a contended message is dropped instead of blocking
+    realtime_producer: Mutex<rtrb::Producer<RealtimeMessage>>,
+    realtime_consumer: Mutex<rtrb::Consumer<RealtimeMessage>>,
+    dropped: AtomicUsize,
+}
+
+impl Logger {
+    fn queue(&self, record: &Record) {
+        let mut message = RealtimeMessage {
+            level: record.level(),
+            instance: CURRENT_INSTANCE.get(),
+            length: 0,
+            text: [0; REALTIME_MESSAGE_LEN],
+        };
+
+        // Truncation is fine
+        let _ = write!(message, "{}: {}", record.target(), record.args());
+
+        let pushed = match self.realtime_producer.try_lock() {
+            Ok(mut producer) => producer.push(message).is_ok(),
+            Err(_) => false,
+        };
+
+        if !pushed {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn write(&self, level: Level, instance: Option<usize>, text: &str) {
+        let host_sinks = HOST_SINKS.lock().unwrap();
+        let mut sinks = sinks_for(&host_sinks, instance).peekable();
+
+        if sinks.peek().is_some() {
+            if let Ok(text) = CString::new(text) {
+                sinks.for_each(|sink| sink(level, &text));
+            }
+        } else if let Some(file) = self.file.lock().unwrap().as_mut() {
+            file.write(level, text);
+        }
+    }
+
+    fn write_queued(&self) {
+        let Ok(mut consumer) = self.realtime_consumer.try_lock() else {
+            return;
+        };
+
+        while let Ok(message) = consumer.pop() {
+            self.write(message.level, message.instance, message.as_str());
+        }
+
+        let dropped = self.dropped.swap(0, Ordering::Relaxed);
+        if dropped > 0 {
+            self.write(Level::Warn, None, &format!("{dropped} log messages from the audio thread were dropped"));
+        }
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        if in_realtime_region() {
+            self.queue(record);
+            return;
+        }
+
+        self.write_queued();
+        self.write(record.level(), CURRENT_INSTANCE.get(), &format!("{}: {}", record.target(), record.args()));
+    }
+
+    fn flush(&self) {
+        if in_realtime_region() {
+            return;
+        }
+
+        self.write_queued();
+
+        if let Some(file) = self.file.lock().unwrap().as_mut() {
+            let _ = file.file.flush();
+        }
+    }
+}
+
+struct RealtimeMessage {
+    level: Level,
+    instance: Option<usize>,
+    length: usize,
+    text: [u8; REALTIME_MESSAGE_LEN],
+}
+
+impl RealtimeMessage {
+    fn as_str(&self) -> &str {
+        // Only whole characters are written
+        std::str::from_utf8(&self.text[..self.length]).unwrap_or_default()
+    }
+}
+
+impl std::fmt::Write for RealtimeMessage {
+    fn write_str(&mut self, string: &str) -> std::fmt::Result {
+        let mut length = usize::min(string.len(), REALTIME_MESSAGE_LEN - self.length);
+        while !string.is_char_boundary(length) {
+            length -= 1;
+        }
+
+        self.text[self.length..self.length + length].copy_from_slice(&string.as_bytes()[..length]);
+        self.length += length;
+
+        if length < string.len() {
+            Err(std::fmt::Error)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+struct LogFile {
+    directory: PathBuf,
+    name: String,
+    file: File,
+    size: u64,
+    max_size: u64,
+    max_files: usize,
+}
+
+impl LogFile {
+    fn open(directory: &Path, config: &LoggerConfig) -> Result<Self, Error> {
+        std::fs::create_dir_all(directory)?;
+
+        // Each logger starts with a new file
+        let file = create_rotated(directory, &config.name, config.max_files)?;
+
+        Ok(Self {
+            directory: directory.to_path_buf(),
+            name: config.name.clone(),
+            file,
+            size: 0,
+            max_size: config.max_file_size,
+            max_files: config.max_files,
+        })
+    }
+
+    fn write(&mut self, level: Level, text: &str) {
+        if self.size >= self.max_size {
+            match create_rotated(&self.directory, &self.name, self.max_files) {
+                Ok(file) => self.file = file,
+                Err(_) => return,
+            }
+
+            self.size = 0;
+        }
+
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let line = format!("{}.{:03} {level:<5} {text}\n", time.as_secs(), time.subsec_millis());
+
+        if self.file.write_all(line.as_bytes()).is_ok() {
+            self.size += line.len() as u64;
+        }
+    }
+}
+
+/// Shifts the existing files up by one index, dropping the oldest, and creates a new one
+fn create_rotated(directory: &Path, name: &str, max_files: usize) -> std::io::Result<File> {
+    let path = |index| match index {
+        0 => directory.join(format!("{name}.log")),
+        _ => directory.join(format!("{name}.{index}.log")),
+    };
+
+    let _ = std::fs::remove_file(path(max_files - 1));
+
+    for index in (0..max_files - 1).rev() {
+        let _ = std::fs::rename(path(index), path(index + 1));
+    }
+
+    File::create(path(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ffi::CStr, fmt::Write, io::Write as _, sync::{Arc, Mutex}};
+
+    use log::Level;
+
+    use super::{create_rotated, sinks_for, HostSink, RealtimeMessage, REALTIME_MESSAGE_LEN};
+
+    #[test]
+    fn realtime_message_truncates() {
+        let mut message = RealtimeMessage {
+            level: Level::Info,
+            instance: None,
+            length: 0,
+            text: [0; REALTIME_MESSAGE_LEN],
+        };
+
+        assert!(write!(message, "{}", "ä".repeat(REALTIME_MESSAGE_LEN)).is_err());
+        assert_eq!(message.as_str(), "ä".repeat(REALTIME_MESSAGE_LEN / 2));
+    }
+
+    #[test]
+    fn instance_sink_selection() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        let sinks: Vec<(usize, HostSink)> = (1..=2)
+            .map(|id| {
+                let received = received.clone();
+                let sink: HostSink = Box::new(move |level, _: &CStr| received.lock().unwrap().push((id, level)));
+                (id, sink)
+            })
+            .collect();
+
+        let send = |instance| {
+            received.lock().unwrap().clear();
+            sinks_for(&sinks, instance).for_each(|sink| sink(Level::Warn, c"message"));
+            received.lock().unwrap().clone()
+        };
+
+        assert_eq!(send(Some(2)), [(2, Level::Warn)]);
+        assert_eq!(send(Some(3)), []);
+        assert_eq!(send(None), [(1, Level::Warn), (2, Level::Warn)]);
+    }
+
+    #[test]
+    fn rotation_keeps_max_files() {
+        let directory = std::env::temp_dir().join(format!("plinth-logging-test-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        for index in 0..4 {
+            let mut file = create_rotated(&directory, "test", 3).unwrap();
+            write!(file, "{index}").unwrap();
+        }
+
+        let read = |name: &str| std::fs::read_to_string(directory.join(name)).unwrap();
+        assert_eq!(read("test.log"), "3");
+        assert_eq!(read("test.1.log"), "2");
+        assert_eq!(read("test.2.log"), "1");
+        assert!(!directory.join("test.3.log").exists());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,12 @@
+#[cfg(target_os="macos")]
+mod macos;
+#[cfg(target_os="windows")]
+mod windows;
+
+#[cfg(target_os="macos")]
+pub(crate) use macos::MainThreadTimer;
+#[cfg(target_os="windows")]
+pub(crate) use windows::MainThreadTimer;
+
+/// How often wrappers call `Plugin::on_main_thread()` when nothing wakes them sooner
+pub(crate) const MAIN_THREAD_TIMER_MILLISECONDS: u64 = 50;
@@ -0,0 +1,121 @@
+use std::{cell::RefCell, ffi::c_void, ptr::null, sync::Arc};
+
+use crate::MainThreadWaker;
+
+use super::MAIN_THREAD_TIMER_MILLISECONDS;
+
+type CFRunLoopRef = *mut c_void;
+type CFRunLoopTimerRef = *mut c_void;
+type CFStringRef = *const c_void;
+
+#[repr(C)]
+struct CFRunLoopTimerContext {
+    version: isize,
+    info: *mut c_void,
+    retain: *const c_void,
+    release: *const c_void,
+    copy_description: *const c_void,
+}
+
+#[link(name = "CoreFoundation", kind = "framework")]
+unsafe extern "C" {
+    static kCFRunLoopCommonModes: CFStringRef;
+
+    fn CFAbsoluteTimeGetCurrent() -> f64;
+    fn CFRelease(cf: *const c_void);
+    fn CFRunLoopAddTimer(run_loop: CFRunLoopRef, timer: CFRunLoopTimerRef, mode: CFStringRef);
+    fn CFRunLoopGetMain() -> CFRunLoopRef;
+    fn CFRunLoopTimerCreate(
+        allocator: *const c_void,
+        fire_date: f64,
+        interval: f64,
+        flags: usize,
+        order: isize,
+        callout: unsafe extern "C" fn(CFRunLoopTimerRef, *mut c_void),
+        context: *mut CFRunLoopTimerContext,
+    ) -> CFRunLoopTimerRef;
+    fn CFRunLoopTimerInvalidate(timer: CFRunLoopTimerRef);
+    fn CFRunLoopTimerSetNextFireDate(timer: CFRunLoopTimerRef, fire_date: f64);
+    fn CFRunLoopWakeUp(run_loop: CFRunLoopRef);
+}
+
+type Callback = RefCell<Box<dyn FnMut()>>;
+
+/// Calls `callback` regularly on the main run loop, and soon after its waker wakes
+///
+/// Has to be dropped on the main thread so the callback can't be running at the same time.
+pub(crate) struct MainThreadTimer {
+    timer: Arc<RetainedTimer>,
+    callback: *mut Callback,
+}
+
+impl MainThreadTimer {
+    pub(crate) fn new(callback: impl FnMut() + 'static) -> Option<Self> {
+        let callback: *mut Callback = Box::into_raw(Box::new(RefCell::new(Box::new(callback))));
+
+        let mut context = CFRunLoopTimerContext {
+            version: 0,
+            info: callback as _,
+            retain: null(),
+            release: null(),
+            copy_description: null(),
+        };
+
+        let interval = MAIN_THREAD_TIMER_MILLISECONDS as f64 / 1000.0;
+
+        unsafe {
+            let timer = CFRunLoopTimerCreate(null(), CFAbsoluteTimeGetCurrent() + interval, interval, 0, 0, Self::on_timer, &mut context);
+            if timer.is_null() {
+                drop(Box::from_raw(callback));
+                return None;
+            }
+
+            CFRunLoopAddTimer(CFRunLoopGetMain(), timer, kCFRunLoopCommonModes);
+
+            Some(Self {
+                timer: Arc::new(RetainedTimer(timer)),
+                callback,
+            })
+        }
+    }
+
+    pub(crate) fn waker(&self) -> MainThreadWaker {
+        let timer = self.timer.clone();
+
+        MainThreadWaker::new(move || unsafe {
+            // Invalidated timers ignore this
+            CFRunLoopTimerSetNextFireDate(timer.0, CFAbsoluteTimeGetCurrent());
+            CFRunLoopWakeUp(CFRunLoopGetMain());
+        })
+    }
+
+    unsafe extern "C" fn on_timer(_timer: CFRunLoopTimerRef, info: *mut c_void) {
+        let callback = unsafe { &*(info as *const Callback) };
+
+        // Skip nested calls if the callback runs the run loop itself
+        if let Ok(mut callback) = callback.try_borrow_mut() {
+            callback();
+        }
+    }
+}
+
+impl Drop for MainThreadTimer {
+    fn drop(&mut self) {
+        unsafe {
+            CFRunLoopTimerInvalidate(self.timer.0);
+            drop(Box::from_raw(self.callback));
+        }
+    }
+}
+
+struct RetainedTimer(CFRunLoopTimerRef);
+
+impl Drop for RetainedTimer {
+    fn drop(&mut self) {
+        unsafe { CFRelease(self.0) };
+    }
+}
+
+// SAFETY: CFRunLoopTimer functions are thread safe, the callback isn't reachable through this
+unsafe impl Send for RetainedTimer {}
+unsafe impl Sync for RetainedTimer {}
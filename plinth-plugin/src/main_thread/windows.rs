@@ -0,0 +1,180 @@
+use std::{cell::RefCell, ffi::c_void, ptr::{null, null_mut}, sync::{atomic::{AtomicBool, Ordering}, Arc, Mutex}};
+
+use crate::MainThreadWaker;
+
+use super::MAIN_THREAD_TIMER_MILLISECONDS;
+
+type Hwnd = *mut c_void;
+type WndProc = unsafe extern "system" fn(Hwnd, u32, usize, isize) -> isize;
+
+#[repr(C)]
+struct WndClassW {
+    style: u32,
+    wnd_proc: Option<WndProc>,
+    cls_extra: i32,
+    wnd_extra: i32,
+    instance: *mut c_void,
+    icon: *mut c_void,
+    cursor: *mut c_void,
+    background: *mut c_void,
+    menu_name: *const u16,
+    class_name: *const u16,
+}
+
+const GWLP_USERDATA: i32 = -21;
+const HWND_MESSAGE: Hwnd = -3isize as Hwnd;
+const TIMER_ID: usize = 1;
+const WM_TIMER: u32 = 0x0113;
+const WM_WAKE: u32 = 0x0400; // WM_USER
+
+#[link(name = "kernel32")]
+unsafe extern "system" {
+    fn GetModuleHandleW(module_name: *const u16) -> *mut c_void;
+}
+
+#[link(name = "user32")]
+unsafe extern "system" {
+    fn CreateWindowExW(
+        ex_style: u32,
+        class_name: *const u16,
+        window_name: *const u16,
+        style: u32,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        parent: Hwnd,
+        menu: *mut c_void,
+        instance: *mut c_void,
+        param: *mut c_void,
+    ) -> Hwnd;
+    fn DefWindowProcW(hwnd: Hwnd, message: u32, wparam: usize, lparam: isize) -> isize;
+    fn DestroyWindow(hwnd: Hwnd) -> i32;
+    fn GetWindowLongPtrW(hwnd: Hwnd, index: i32) -> isize;
+    fn KillTimer(hwnd: Hwnd, id: usize) -> i32;
+    fn PostMessageW(hwnd: Hwnd, message: u32, wparam: usize, lparam: isize) -> i32;
+    fn RegisterClassW(class: *const WndClassW) -> u16;
+    fn SetTimer(hwnd: Hwnd, id: usize, elapse: u32, timer_func: *const c_void) -> usize;
+    fn SetWindowLongPtrW(hwnd: Hwnd, index: i32, value: isize) -> isize;
+}
+
+struct Handler {
+    callback: RefCell<Box<dyn FnMut()>>,
+    wake: Arc<Wake>,
+}
+
+struct Wake {
+    // None once the window is destroyed, locked while posting so the window can't go away in between
+    window: Mutex<Option<usize>>,
+    pending: AtomicBool,
+}
+
+/// Calls `callback` regularly from a message-only window's timer, and soon after its waker wakes
+///
+/// Has to be created and dropped on the main thread, since the window belongs to the thread that created it.
+pub(crate) struct MainThreadTimer {
+    window: Hwnd,
+    handler: *mut Handler,
+}
+
+impl MainThreadTimer {
+    pub(crate) fn new(callback: impl FnMut() + 'static) -> Option<Self> {
+        // Unique to this module, so several plugin binaries in one process don't share a window procedure
+        let class_name: Vec<u16> = format!("plinth-main-thread-{:p}", Self::window_proc as *const c_void)
+            .encode_utf16()
+            .chain(Some(0))
+            .collect();
+
+        unsafe {
+            let instance = GetModuleHandleW(null());
+
+            let class = WndClassW {
+                style: 0,
+                wnd_proc: Some(Self::window_proc),
+                cls_extra: 0,
+                wnd_extra: 0,
+                instance,
+                icon: null_mut(),
+                cursor: null_mut(),
+                background: null_mut(),
+                menu_name: null(),
+                class_name: class_name.as_ptr(),
+            };
+
+            // Fails harmlessly if another instance registered the class already
+            RegisterClassW(&class);
+
+            let window = CreateWindowExW(0, class_name.as_ptr(), null(), 0, 0, 0, 0, 0, HWND_MESSAGE, null_mut(), instance, null_mut());
+            if window.is_null() {
+                return None;
+            }
+
+            let handler = Box::into_raw(Box::new(Handler {
+                callback: RefCell::new(Box::new(callback)),
+                wake: Arc::new(Wake {
+                    window: Mutex::new(Some(window as usize)),
+                    pending: AtomicBool::new(false),
+                }),
+            }));
+
+            SetWindowLongPtrW(window, GWLP_USERDATA, handler as isize);
+            SetTimer(window, TIMER_ID, MAIN_THREAD_TIMER_MILLISECONDS as u32, null());
+
+            Some(Self {
+                window,
+                handler,
+            })
+        }
+    }
+
+    pub(crate) fn waker(&self) -> MainThreadWaker {
+        let wake = unsafe { (*self.handler).wake.clone() };
+
+        MainThreadWaker::new(move || {
+            let window = wake.window.lock().unwrap();
+
+            // Only one wake message is queued at a time
+            if let Some(window) = *window && !wake.pending.swap(true, Ordering::AcqRel) {
+                unsafe { PostMessageW(window as Hwnd, WM_WAKE, 0, 0) };
+            }
+        })
+    }
+
+    unsafe extern "system" fn window_proc(hwnd: Hwnd, message: u32, wparam: usize, lparam: isize) -> isize {
+        if message == WM_TIMER || message == WM_WAKE {
+            let handler = unsafe { GetWindowLongPtrW(hwnd, GWLP_USERDATA) } as *const Handler;
+            if handler.is_null() {
+                return 0;
+            }
+
+            let handler = unsafe { &*handler };
+            if message == WM_WAKE {
+                handler.wake.pending.store(false, Ordering::Release);
+            }
+
+            // Skip nested calls if the callback pumps messages itself
+            if let Ok(mut callback) = handler.callback.try_borrow_mut() {
+                callback();
+            }
+
+            return 0;
+        }
+
+        unsafe { DefWindowProcW(hwnd, message, wparam, lparam) }
+    }
+}
+
+impl Drop for MainThreadTimer {
+    fn drop(&mut self) {
+        unsafe {
+            let handler = &*self.handler;
+            *handler.wake.window.lock().unwrap() = None;
+
+            KillTimer(self.window, TIMER_ID);
+            SetWindowLongPtrW(self.window, GWLP_USERDATA, 0);
+            DestroyWindow(self.window);
+
+            drop(Box::from_raw(self.handler));
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+/// Runs independent tasks from inside `Processor::process()`, on the host's thread pool when it has one
+///
+/// Only CLAP hosts can provide a thread pool, otherwise tasks run one after another on the audio thread.
+#[derive(Clone, Default)]
+pub struct Parallel {
+    pool: Option<Arc<dyn ThreadPool>>,
+}
+
+/// A host thread pool
+pub(crate) trait ThreadPool: Send + Sync {
+    /// Returns false if the host couldn't run the tasks, in which case none of them ran
+    fn execute(&self, task_count: usize, task: &(dyn Fn(usize) + Sync)) -> bool;
+}
+
+impl Parallel {
+    pub(crate) fn new(pool: Arc<dyn ThreadPool>) -> Self {
+        Self {
+            pool: Some(pool),
+        }
+    }
+
+    /// True if tasks can run in parallel
+    pub fn is_parallel(&self) -> bool {
+        self.pool.is_some()
+    }
+
+    /// Calls `task` with every index from 0 to `task_count` and returns once all of them have finished
+    ///
+    /// Don't call this recursively from inside a task.
+    pub fn execute(&self, task_count: usize, task: impl Fn(usize) + Sync) {
+        if let Some(pool) = self.pool.as_ref() && task_count > 1 && pool.execute(task_count, &task) {
+            return;
+        }
+
+        for index in 0..task_count {
+            task(index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+
+    use super::{Parallel, ThreadPool};
+
+    struct ScopedPool {
+        accept: bool,
+    }
+
+    impl ThreadPool for ScopedPool {
+        fn execute(&self, task_count: usize, task: &(dyn Fn(usize) + Sync)) -> bool {
+            if !self.accept {
+                return false;
+            }
+
+            std::thread::scope(|scope| {
+                for index in 0..task_count {
+                    scope.spawn(move || task(index));
+                }
+            });
+
+            true
+        }
+    }
+
+    fn run(parallel: &Parallel) -> Vec<usize> {
+        let counts: Vec<_> = (0..8).map(|_| AtomicUsize::new(0)).collect();
+        parallel.execute(counts.len(), |index| { counts[index].fetch_add(1, Ordering::Relaxed); });
+
+        counts.iter().map(|count| count.load(Ordering::Relaxed)).collect()
+    }
+
+    #[test]
+    fn every_task_runs_once() {
+        assert_eq!(run(&Parallel::default()), vec![1; 8]);
+        assert_eq!(run(&Parallel::new(Arc::new(ScopedPool { accept: true }))), vec![1; 8]);
+        assert_eq!(run(&Parallel::new(Arc::new(ScopedPool { accept: false }))), vec![1; 8]);
+    }
+}
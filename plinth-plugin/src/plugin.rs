@@ -1,6 +1,6 @@
 use std::{io::{Read, Write}, rc::Rc};
 
-use crate::{error::Error, host::HostInfo, processor::ProcessorConfig, Editor, Event, Host, Parameters, Processor};
+use crate::{category::Category, error::Error, host::HostInfo, processor::ProcessorConfig, Editor, Event, Host, Parameters, Processor};
 
 pub trait Plugin {
     const NAME: &'static str;
@@ -9,6 +9,19 @@ pub trait Plugin {
     
     const URL: Option<&'static str> = None;
 
+    /// Mapped to CLAP features and VST3 subcategories, unless the format traits override them
+    const CATEGORIES: &'static [Category] = &[];
+
+    /// Let the wrapper handle the bypass parameter by crossfading to the dry signal delayed by `latency()`
+    const AUTOMATIC_BYPASS: bool = false;
+    /// With `AUTOMATIC_BYPASS`, keep calling the processor while bypassed so tails and internal state carry on
+    const PROCESS_WHILE_BYPASSED: bool = true;
+
+    /// Block size of the `FixedBlockProcessor` built with `FixedBlockProcessor::for_plugin()`, if the plugin uses one
+    ///
+    /// The wrappers add it to `latency()` when reporting latency to the host.
+    const FIXED_BLOCK_SIZE: Option<usize> = None;
+
     const HAS_AUX_INPUT: bool = false;
     const HAS_NOTE_INPUT: bool = false;
     const HAS_NOTE_OUTPUT: bool = false;
@@ -28,7 +41,19 @@ pub trait Plugin {
     fn save_state(&self, writer: &mut impl Write) -> Result<(), Error>;
     fn load_state(&mut self, reader: &mut impl Read) -> Result<(), Error>;
 
+    /// Latency of the processor itself, not counting `FIXED_BLOCK_SIZE`
     fn latency(&self) -> u32 {
         0
     }
+
+    /// Called on the main thread, for example to collect handoff garbage or `Tasks` results
+    ///
+    /// Called regularly and soon after a `MainThreadWaker` wakes, whether or not the editor is open. The exception is a
+    /// Linux VST3 host without a run loop in its host context, where it's only called while the editor is open.
+    fn on_main_thread(&mut self) {}
+}
+
+/// The latency the wrappers report to the host, including the delay added by `FIXED_BLOCK_SIZE`
+pub fn reported_latency<P: Plugin>(plugin: &P) -> u32 {
+    plugin.latency() + P::FIXED_BLOCK_SIZE.unwrap_or(0) as u32
 }
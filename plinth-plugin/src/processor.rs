@@ -1,6 +1,10 @@
 use plinth_core::signals::signal::{Signal, SignalMut};
 
-use crate::{event::Event, transport::Transport};
+use crate::{event::Event, parallel::Parallel, transport::Transport};
+
+mod fixed_block;
+
+pub use fixed_block::FixedBlockProcessor;
 
 #[derive(Clone, Default)]
 pub struct ProcessorConfig {
@@ -8,6 +12,8 @@ pub struct ProcessorConfig {
     pub min_block_size: usize,
     pub max_block_size: usize,
     pub process_mode: ProcessMode,
+    /// Keep this in the processor to spread work like voices over the host's threads
+    pub parallel: Parallel,
 }
 
 #[derive(Clone, Copy, Default, PartialEq)]
@@ -17,11 +23,14 @@ pub enum ProcessMode {
     Offline,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ProcessState {
     Error,
     Normal,
     Tail(usize),
     KeepAlive,
+    /// Output is silent and stays that way until new events or input arrive, see `SleepDetector`
+    Sleep,
 }
 
 pub trait Processor: Send {
@@ -29,4 +38,100 @@ pub trait Processor: Send {
     fn process(&mut self, buffer: &mut impl SignalMut, aux: Option<&impl Signal>, transport: Option<Transport>, events: impl Iterator<Item = Event>) -> ProcessState;
     // Called when there's no audio to process
     fn process_events(&mut self, events: impl Iterator<Item = Event>);
+
+    /// Called before `process()` when the host reports which input channels are constant for the whole block,
+    /// one bit per channel. Constant channels hold their first sample everywhere, which is usually silence.
+    fn set_input_constant_mask(&mut self, _main: u64, _aux: u64) {}
+
+    /// Output channels left constant by the last `process()` call, one bit per channel, so the host can skip reading them
+    fn output_constant_mask(&self) -> u64 {
+        0
+    }
+}
+
+/// Returns `ProcessState::Sleep` once the input has been silent for longer than the tail
+pub struct SleepDetector {
+    tail_length: usize,
+    silent_samples: usize,
+}
+
+impl SleepDetector {
+    pub fn new(tail_length: usize) -> Self {
+        Self {
+            tail_length,
+            silent_samples: 0,
+        }
+    }
+
+    pub fn set_tail_length(&mut self, tail_length: usize) {
+        self.tail_length = tail_length;
+    }
+
+    pub fn reset(&mut self) {
+        self.silent_samples = 0;
+    }
+
+    /// Call once per block, `silent` tells if the whole block of input was silent
+    pub fn update(&mut self, silent: bool, sample_count: usize) -> ProcessState {
+        if silent {
+            self.silent_samples = self.silent_samples.saturating_add(sample_count);
+        } else {
+            self.silent_samples = 0;
+        }
+
+        if silent && self.silent_samples > self.tail_length {
+            ProcessState::Sleep
+        } else {
+            ProcessState::Normal
+        }
+    }
+
+    /// Like `update()` but checks the input for silence, skipping channels in `constant_mask` that start with zero
+    pub fn update_from_signal(&mut self, input: &impl Signal, constant_mask: u64) -> ProcessState {
+        let silent = input.iter_channels()
+            .enumerate()
+            .all(|(index, channel)| {
+                if index < 64 && constant_mask & (1 << index) != 0 {
+                    channel.first().is_none_or(|&sample| sample == 0.0)
+                } else {
+                    channel.iter().all(|&sample| sample == 0.0)
+                }
+            });
+
+        self.update(silent, input.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plinth_core::{buffers::buffer::Buffer, signals::signal::SignalMut};
+
+    use super::{ProcessState, SleepDetector};
+
+    #[test]
+    fn sleep_after_tail() {
+        let mut detector = SleepDetector::new(100);
+
+        assert!(matches!(detector.update(false, 64), ProcessState::Normal));
+        assert!(matches!(detector.update(true, 64), ProcessState::Normal));
+        assert!(matches!(detector.update(true, 64), ProcessState::Sleep));
+
+        // New input wakes it up again
+        assert!(matches!(detector.update(false, 64), ProcessState::Normal));
+        assert!(matches!(detector.update(true, 64), ProcessState::Normal));
+    }
+
+    #[test]
+    fn silence_from_signal() {
+        let mut detector = SleepDetector::new(0);
+        let mut buffer = Buffer::new(2, 16);
+
+        assert!(matches!(detector.update_from_signal(&buffer, 0), ProcessState::Sleep));
+
+        buffer.channel_mut(1)[8] = 0.5;
+        assert!(matches!(detector.update_from_signal(&buffer, 0), ProcessState::Normal));
+
+        // A constant channel is only checked at its first sample
+        assert!(matches!(detector.update_from_signal(&buffer, 0b10), ProcessState::Sleep));
+    }
 }
@@ -0,0 +1,287 @@
+use std::mem;
+
+use plinth_core::{buffers::buffer::Buffer, signals::{signal::{Signal, SignalMut}, signal_base::SignalBase}};
+
+use crate::{Event, Plugin, ProcessState, Processor, Transport};
+
+const EVENT_QUEUE_LEN: usize = 1024;
+
+/// Runs an inner processor on blocks of exactly `block_size` samples, whatever the host block size is
+///
+/// This adds `block_size` samples of latency. Build it with `for_plugin()` so the wrappers report it to the host.
+/// Events are moved to the matching position in the fixed block that contains them.
+pub struct FixedBlockProcessor<P: Processor> {
+    processor: P,
+    block_size: usize,
+
+    input: Buffer,
+    aux: Buffer,
+    output: Buffer,
+    position: usize,
+    has_aux: bool,
+
+    events: Vec<Event>,
+    state: ProcessState,
+}
+
+impl<P: Processor> FixedBlockProcessor<P> {
+    /// `channels` is used for both the main and aux signals
+    pub fn new(processor: P, block_size: usize, channels: usize) -> Self {
+        assert!(block_size > 0);
+
+        Self {
+            processor,
+            block_size,
+
+            input: Buffer::new(channels, block_size),
+            aux: Buffer::new(channels, block_size),
+            output: Buffer::new(channels, block_size),
+            position: 0,
+            has_aux: false,
+
+            events: Vec::with_capacity(EVENT_QUEUE_LEN),
+            state: ProcessState::Normal,
+        }
+    }
+
+    /// Uses `Plugin::FIXED_BLOCK_SIZE`, which has to be set
+    pub fn for_plugin<T: Plugin>(processor: P, channels: usize) -> Self {
+        let block_size = T::FIXED_BLOCK_SIZE.expect("Plugin::FIXED_BLOCK_SIZE isn't set");
+        Self::new(processor, block_size, channels)
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Latency added on top of the inner processor's own latency
+    pub fn latency(&self) -> usize {
+        self.block_size
+    }
+
+    pub fn inner(&self) -> &P {
+        &self.processor
+    }
+
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.processor
+    }
+
+    fn queue_event(&mut self, event: Event) {
+        if self.events.len() == self.events.capacity() {
+            log::error!("Fixed block event queue is full, dropping event");
+            return;
+        }
+
+        self.events.push(event);
+    }
+
+    fn process_block(&mut self, transport: Option<Transport>) {
+        let events = self.events.drain(..);
+
+        let state = if self.has_aux {
+            self.processor.process(&mut self.input, Some(&self.aux), transport, events)
+        } else {
+            self.processor.process(&mut self.input, None::<&Buffer>, transport, events)
+        };
+
+        // The processed block is played back while the next one is collected
+        mem::swap(&mut self.input, &mut self.output);
+        self.position = 0;
+
+        self.state = match state {
+            ProcessState::Tail(tail) => ProcessState::Tail(tail + self.block_size),
+            state => state,
+        };
+    }
+
+    /// The inner state, except that a sleeping processor is kept awake until the input it hasn't seen yet is processed
+    fn reported_state(&self) -> ProcessState {
+        if self.state != ProcessState::Sleep || (self.events.is_empty() && !self.has_pending_input()) {
+            return self.state;
+        }
+
+        // The pending input is processed once the block is full, and played back over the block after that
+        ProcessState::Tail(2 * self.block_size - self.position)
+    }
+
+    fn has_pending_input(&self) -> bool {
+        let pending = |buffer: &Buffer| (0..buffer.channels())
+            .any(|channel| buffer.channel(channel)[..self.position].iter().any(|&sample| sample != 0.0));
+
+        pending(&self.input) || (self.has_aux && pending(&self.aux))
+    }
+}
+
+impl<P: Processor> Processor for FixedBlockProcessor<P> {
+    fn reset(&mut self) {
+        self.input.fill(0.0);
+        self.aux.fill(0.0);
+        self.output.fill(0.0);
+        self.position = 0;
+        self.events.clear();
+        self.state = ProcessState::Normal;
+
+        self.processor.reset();
+    }
+
+    fn process(&mut self, buffer: &mut impl SignalMut, aux: Option<&impl Signal>, transport: Option<Transport>, events: impl Iterator<Item = Event>) -> ProcessState {
+        let mut events = events.peekable();
+        let channels = usize::min(buffer.channels(), self.input.channels());
+        let mut error = false;
+
+        self.has_aux = aux.is_some();
+
+        let mut offset = 0;
+        while offset < buffer.len() {
+            let chunk_len = usize::min(self.block_size - self.position, buffer.len() - offset);
+            let chunk_end = offset + chunk_len;
+            let last_chunk = chunk_end == buffer.len();
+
+            // Events past the end of the buffer are applied at its last sample
+            while let Some(mut event) = events.next_if(|event| last_chunk || event.sample_offset() < chunk_end) {
+                let chunk_offset = usize::min(event.sample_offset().saturating_sub(offset), chunk_len - 1);
+                event.set_sample_offset(self.position + chunk_offset);
+                self.queue_event(event);
+            }
+
+            let block_range = self.position..self.position + chunk_len;
+
+            for channel in 0..channels {
+                let buffer_channel = &mut buffer.channel_mut(channel)[offset..chunk_end];
+                self.input.channel_mut(channel)[block_range.clone()].copy_from_slice(buffer_channel);
+                buffer_channel.copy_from_slice(&self.output.channel(channel)[block_range.clone()]);
+            }
+
+            if let Some(aux) = aux {
+                for channel in 0..usize::min(aux.channels(), self.aux.channels()) {
+                    self.aux.channel_mut(channel)[block_range.clone()].copy_from_slice(&aux.channel(channel)[offset..chunk_end]);
+                }
+            }
+
+            self.position += chunk_len;
+            offset = chunk_end;
+
+            if self.position == self.block_size {
+                // Transport at the first sample of the fixed block
+                let block_transport = transport.map(|transport| Transport::new(
+                    transport.playing(),
+                    transport.tempo(),
+                    transport.position_samples() + offset as i64 - self.block_size as i64,
+                ));
+
+                self.process_block(block_transport);
+                error |= self.state == ProcessState::Error;
+            }
+        }
+
+        // Only happens with an empty buffer
+        for mut event in events {
+            event.set_sample_offset(self.position);
+            self.queue_event(event);
+        }
+
+        if error {
+            ProcessState::Error
+        } else {
+            self.reported_state()
+        }
+    }
+
+    fn process_events(&mut self, events: impl Iterator<Item = Event>) {
+        self.processor.process_events(events);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plinth_core::{buffers::buffer::Buffer, signals::signal::{Signal, SignalMut}};
+
+    use crate::{Event, ProcessState, Processor, Transport};
+
+    use super::FixedBlockProcessor;
+
+    #[derive(Default)]
+    struct RecordingProcessor {
+        block_sizes: Vec<usize>,
+        event_offsets: Vec<usize>,
+        positions: Vec<i64>,
+    }
+
+    impl Processor for RecordingProcessor {
+        fn reset(&mut self) {}
+
+        fn process(&mut self, buffer: &mut impl SignalMut, _aux: Option<&impl Signal>, transport: Option<Transport>, events: impl Iterator<Item = Event>) -> ProcessState {
+            self.block_sizes.push(buffer.len());
+            self.event_offsets.extend(events.map(|event| event.sample_offset()));
+            self.positions.extend(transport.map(|transport| transport.position_samples()));
+
+            ProcessState::Normal
+        }
+
+        fn process_events(&mut self, _events: impl Iterator<Item = Event>) {}
+    }
+
+    struct SleepingProcessor;
+
+    impl Processor for SleepingProcessor {
+        fn reset(&mut self) {}
+
+        fn process(&mut self, _buffer: &mut impl SignalMut, _aux: Option<&impl Signal>, _transport: Option<Transport>, _events: impl Iterator<Item = Event>) -> ProcessState {
+            ProcessState::Sleep
+        }
+
+        fn process_events(&mut self, _events: impl Iterator<Item = Event>) {}
+    }
+
+    fn note_on(sample_offset: usize) -> Event {
+        Event::NoteOn { sample_offset, channel: 0, key: 60, note: -1, velocity: 1.0 }
+    }
+
+    #[test]
+    fn output_is_delayed_by_block_size() {
+        let mut processor = FixedBlockProcessor::new(RecordingProcessor::default(), 4, 1);
+        let input: Vec<f32> = (1..=12).map(|sample| sample as f32).collect();
+        let mut output = Vec::new();
+
+        for host_block in [3, 5, 4] {
+            let start = output.len();
+            let mut buffer = Buffer::from(vec![input[start..start + host_block].to_vec()]);
+            processor.process(&mut buffer, None::<&Buffer>, None, std::iter::empty());
+            output.extend_from_slice(buffer.channel(0));
+        }
+
+        assert_eq!(output, [0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+        assert_eq!(processor.inner().block_sizes, [4, 4, 4]);
+    }
+
+    #[test]
+    fn events_follow_the_fixed_blocks() {
+        let mut processor = FixedBlockProcessor::new(RecordingProcessor::default(), 4, 1);
+
+        let mut buffer = Buffer::new(1, 3);
+        processor.process(&mut buffer, None::<&Buffer>, Some(Transport::new(true, 120.0, 0)), [note_on(1)].into_iter());
+
+        let mut buffer = Buffer::new(1, 6);
+        processor.process(&mut buffer, None::<&Buffer>, Some(Transport::new(true, 120.0, 3)), [note_on(0), note_on(2), note_on(5)].into_iter());
+
+        // Host offsets 1, 3, 5 and 8 in absolute time
+        assert_eq!(processor.inner().event_offsets, [1, 3, 1]);
+        assert_eq!(processor.inner().positions, [0, 4]);
+
+        let mut buffer = Buffer::new(1, 4);
+        processor.process(&mut buffer, None::<&Buffer>, None, std::iter::empty());
+        assert_eq!(processor.inner().event_offsets, [1, 3, 1, 0]);
+    }
+
+    #[test]
+    fn sleep_waits_for_pending_input() {
+        let mut processor = FixedBlockProcessor::new(SleepingProcessor, 4, 1);
+
+        let mut buffer = Buffer::from(vec![vec![1.0; 6]]);
+        assert_eq!(processor.process(&mut buffer, None::<&Buffer>, None, std::iter::empty()), ProcessState::Tail(6));
+
+        let mut buffer = Buffer::new(1, 2);
+        assert_eq!(processor.process(&mut buffer, None::<&Buffer>, None, std::iter::empty()), ProcessState::Sleep);
+    }
+}
@@ -0,0 +1,230 @@
+use std::cell::Cell;
+#[cfg(feature = "rt-check")]
+use std::{alloc::{GlobalAlloc, Layout, System}, backtrace::Backtrace, cell::RefCell, sync::atomic::{AtomicBool, Ordering}};
+
+/// What happens when realtime code allocates, deallocates or blocks
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ViolationAction {
+    /// Logs an error with a backtrace
+    #[default]
+    Log,
+    /// Logs an error with a backtrace and aborts the host process
+    ///
+    /// Panics can't unwind through the plugin format's C entry points, so aborting is the only way to stop.
+    /// Violations inside the allocator abort once the realtime region ends, since the allocator itself can't log.
+    Abort,
+}
+
+#[cfg(feature = "rt-check")]
+static ABORT_ON_VIOLATION: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static REGION_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+#[cfg(feature = "rt-check")]
+thread_local! {
+    static PERMIT_DEPTH: Cell<usize> = const { Cell::new(0) };
+    static VIOLATION_COUNT: Cell<usize> = const { Cell::new(0) };
+    static PENDING_ABORT: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+#[cfg(feature = "rt-check")]
+#[global_allocator]
+static ALLOCATOR: CheckingAllocator = CheckingAllocator;
+
+pub fn set_violation_action(action: ViolationAction) {
+    #[cfg(feature = "rt-check")]
+    ABORT_ON_VIOLATION.store(action == ViolationAction::Abort, Ordering::Relaxed);
+    #[cfg(not(feature = "rt-check"))]
+    let _ = action;
+}
+
+/// True when called from inside `Processor::process()`, a parameter flush or a host thread pool task
+pub fn in_realtime_region() -> bool {
+    REGION_DEPTH.try_with(Cell::get).unwrap_or(0) > 0
+}
+
+/// Violations reported on the current thread so far, always 0 without the `rt-check` feature
+pub fn violation_count() -> usize {
+    #[cfg(feature = "rt-check")]
+    return VIOLATION_COUNT.try_with(Cell::get).unwrap_or(0);
+    #[cfg(not(feature = "rt-check"))]
+    0
+}
+
+/// Runs `function` without checking, for code that is known to be fine, like a one-off allocation on the first block
+pub fn permit_violations<R>(function: impl FnOnce() -> R) -> R {
+    #[cfg(feature = "rt-check")]
+    let _permit = Permit::new();
+
+    function()
+}
+
+/// Reports a violation if called from a realtime region
+///
+/// Locks and other blocking calls can't be detected automatically, so call this before them.
+pub fn check_blocking(operation: &str) {
+    #[cfg(feature = "rt-check")]
+    if is_checking() {
+        report(&format_args!("blocking call ({operation})"), true);
+    }
+    #[cfg(not(feature = "rt-check"))]
+    let _ = operation;
+}
+
+/// Marks the current thread as realtime until dropped
+pub(crate) struct RealtimeRegion {
+    // Regions are tied to the thread they were entered on
+    _not_send: std::marker::PhantomData<*const ()>,
+}
+
+impl RealtimeRegion {
+    pub(crate) fn enter() -> Self {
+        REGION_DEPTH.with(|depth| depth.set(depth.get() + 1));
+
+        Self {
+            _not_send: std::marker::PhantomData,
+        }
+    }
+}
+
+impl Drop for RealtimeRegion {
+    fn drop(&mut self) {
+        REGION_DEPTH.with(|depth| depth.set(depth.get() - 1));
+
+        #[cfg(feature = "rt-check")]
+        if let Some(message) = PENDING_ABORT.with(|pending| pending.borrow_mut().take()) {
+            abort(&message);
+        }
+    }
+}
+
+#[cfg(feature = "rt-check")]
+struct Permit;
+
+#[cfg(feature = "rt-check")]
+impl Permit {
+    fn new() -> Self {
+        PERMIT_DEPTH.with(|depth| depth.set(depth.get() + 1));
+        Self
+    }
+}
+
+#[cfg(feature = "rt-check")]
+impl Drop for Permit {
+    fn drop(&mut self) {
+        PERMIT_DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+#[cfg(feature = "rt-check")]
+fn is_checking() -> bool {
+    // Thread locals can be gone while a thread shuts down
+    let in_region = REGION_DEPTH.try_with(Cell::get).unwrap_or(0) > 0;
+    let permitted = PERMIT_DEPTH.try_with(Cell::get).unwrap_or(1) > 0;
+
+    in_region && !permitted
+}
+
+#[cfg(feature = "rt-check")]
+fn report(violation: &std::fmt::Arguments, can_abort: bool) {
+    // Reporting allocates, so don't check it
+    let _permit = Permit::new();
+
+    VIOLATION_COUNT.with(|count| count.set(count.get() + 1));
+    let message = format!("Realtime violation: {violation} on the audio thread\n{}", Backtrace::force_capture());
+
+    if !ABORT_ON_VIOLATION.load(Ordering::Relaxed) {
+        log::error!("{message}");
+    } else if can_abort {
+        abort(&message);
+    } else {
+        PENDING_ABORT.with(|pending| {
+            pending.borrow_mut().get_or_insert(message);
+        });
+    }
+}
+
+#[cfg(feature = "rt-check")]
+fn abort(message: &str) -> ! {
+    let _permit = Permit::new();
+
+    // The logger may only queue messages from the audio thread, so make sure this is seen
+    eprintln!("{message}");
+    log::error!("{message}");
+    log::logger().flush();
+
+    std::process::abort();
+}
+
+#[cfg(feature = "rt-check")]
+struct CheckingAllocator;
+
+#[cfg(feature = "rt-check")]
+unsafe impl GlobalAlloc for CheckingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if is_checking() {
+            report(&format_args!("allocation of {} bytes", layout.size()), false);
+        }
+
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        if is_checking() {
+            report(&format_args!("allocation of {} bytes", layout.size()), false);
+        }
+
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if is_checking() {
+            report(&format_args!("deallocation of {} bytes", layout.size()), false);
+        }
+
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if is_checking() {
+            report(&format_args!("reallocation from {} to {new_size} bytes", layout.size()), false);
+        }
+
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[cfg(all(test, feature = "rt-check"))]
+mod tests {
+    use super::{check_blocking, permit_violations, violation_count, RealtimeRegion};
+
+    #[test]
+    fn detects_violations() {
+        let count = violation_count();
+        {
+            let _region = RealtimeRegion::enter();
+            std::hint::black_box(vec![0u8; 16]);
+        }
+        assert_eq!(violation_count(), count + 2);
+
+        {
+            let _region = RealtimeRegion::enter();
+            permit_violations(|| {
+                std::hint::black_box(vec![0u8; 16]);
+                check_blocking("permitted");
+            });
+        }
+        assert_eq!(violation_count(), count + 2);
+
+        {
+            let _region = RealtimeRegion::enter();
+            check_blocking("lock");
+        }
+        assert_eq!(violation_count(), count + 3);
+
+        std::hint::black_box(vec![0u8; 16]);
+        assert_eq!(violation_count(), count + 3);
+    }
+}
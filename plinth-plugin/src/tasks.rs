@@ -0,0 +1,174 @@
+use std::{sync::{atomic::{AtomicBool, AtomicUsize, Ordering}, mpsc, Arc, Mutex}, thread::JoinHandle};
+
+use crate::host::HostInfo;
+
+type Job<T> = Box<dyn FnOnce() -> T + Send>;
+
+/// Asks the host to call `Plugin::on_main_thread()` soon, can be used from any thread
+#[derive(Clone, Default)]
+pub struct MainThreadWaker {
+    wake: Option<Arc<dyn Fn() + Send + Sync>>,
+}
+
+impl MainThreadWaker {
+    pub(crate) fn new(wake: impl Fn() + Send + Sync + 'static) -> Self {
+        Self {
+            wake: Some(Arc::new(wake)),
+        }
+    }
+
+    pub fn wake(&self) {
+        if let Some(wake) = self.wake.as_ref() {
+            wake();
+        }
+    }
+}
+
+/// Runs jobs on background threads and hands their results back on the main thread
+///
+/// Results are collected with `poll()` from `Plugin::on_main_thread()`, which the host is asked to call whenever a job
+/// finishes.
+/// Jobs that haven't started yet are skipped when `Tasks` is dropped, running ones are waited for.
+pub struct Tasks<T: Send + 'static> {
+    jobs: Option<mpsc::Sender<Job<T>>>,
+    results: mpsc::Receiver<T>,
+    shared: Arc<TasksShared>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+struct TasksShared {
+    pending: AtomicUsize,
+    cancelled: AtomicBool,
+}
+
+impl<T: Send + 'static> Tasks<T> {
+    pub fn new(host_info: &HostInfo, threads: usize) -> Self {
+        assert!(threads > 0);
+
+        if host_info.main_thread_waker.wake.is_none() {
+            log::warn!("The {} host can't wake the main thread, task results wait for the next on_main_thread() call", host_info.format);
+        }
+
+        let (jobs, job_receiver) = mpsc::channel::<Job<T>>();
+        let (result_sender, results) = mpsc::channel();
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+
+        let shared = Arc::new(TasksShared {
+            pending: AtomicUsize::new(0),
+            cancelled: AtomicBool::new(false),
+        });
+
+        let workers = (0..threads)
+            .map(|index| {
+                let job_receiver = job_receiver.clone();
+                let result_sender = result_sender.clone();
+                let shared = shared.clone();
+                let waker = host_info.main_thread_waker.clone();
+
+                std::thread::Builder::new()
+                    .name(format!("plinth-task-{index}"))
+                    .spawn(move || {
+                        loop {
+                            // The lock is released before running the job, so other workers can pick up jobs
+                            let Ok(job) = job_receiver.lock().unwrap().recv() else {
+                                break;
+                            };
+
+                            if shared.cancelled.load(Ordering::Acquire) {
+                                break;
+                            }
+
+                            let result = job();
+                            if result_sender.send(result).is_err() {
+                                break;
+                            }
+
+                            waker.wake();
+                        }
+                    })
+                    .expect("Failed to spawn task thread")
+            })
+            .collect();
+
+        Self {
+            jobs: Some(jobs),
+            results,
+            shared,
+            workers,
+        }
+    }
+
+    pub fn spawn(&self, job: impl FnOnce() -> T + Send + 'static) {
+        self.shared.pending.fetch_add(1, Ordering::Relaxed);
+        self.jobs.as_ref().unwrap().send(Box::new(job)).expect("Task threads have stopped");
+    }
+
+    /// Jobs that have been spawned but whose results haven't been polled yet
+    pub fn pending(&self) -> usize {
+        self.shared.pending.load(Ordering::Relaxed)
+    }
+
+    /// Returns the results of finished jobs in the order they finished, call this on the main thread
+    pub fn poll(&self) -> impl Iterator<Item = T> + '_ {
+        self.results.try_iter()
+            .inspect(|_| { self.shared.pending.fetch_sub(1, Ordering::Relaxed); })
+    }
+}
+
+impl<T: Send + 'static> Drop for Tasks<T> {
+    fn drop(&mut self) {
+        self.shared.cancelled.store(true, Ordering::Release);
+        self.jobs = None;
+
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::{atomic::{AtomicUsize, Ordering}, Arc}, time::{Duration, Instant}};
+
+    use crate::{host::HostInfo, PluginFormat};
+
+    use super::{MainThreadWaker, Tasks};
+
+    #[test]
+    fn results_and_wakes() {
+        let wakes = Arc::new(AtomicUsize::new(0));
+
+        let host_info = HostInfo {
+            name: None,
+            vendor: None,
+            version: None,
+            url: None,
+            format: PluginFormat::Clap,
+            main_thread_waker: MainThreadWaker::new({
+                let wakes = wakes.clone();
+                move || { wakes.fetch_add(1, Ordering::Relaxed); }
+            }),
+            capabilities: Default::default(),
+        };
+
+        let tasks = Tasks::new(&host_info, 2);
+        for value in 0..8 {
+            tasks.spawn(move || value * 2);
+        }
+
+        let mut results = Vec::new();
+        let start = Instant::now();
+        while results.len() < 8 && start.elapsed() < Duration::from_secs(10) {
+            results.extend(tasks.poll());
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        results.sort();
+        assert_eq!(results, (0..8).map(|value| value * 2).collect::<Vec<_>>());
+        assert_eq!(tasks.pending(), 0);
+
+        // Workers wake after sending, so wait for them to finish
+        drop(tasks);
+        assert_eq!(wakes.load(Ordering::Relaxed), 8);
+    }
+}
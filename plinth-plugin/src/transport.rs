@@ -1,3 +1,4 @@
+#[derive(Clone, Copy, Debug)]
 pub struct Transport {
     pub(crate) playing: bool,
     pub(crate) tempo: f64,
@@ -0,0 +1,35 @@
+use crate::Plugin;
+
+/// Checks shared by all formats, evaluated at compile time when a plugin is exported
+pub(crate) const fn validate_plugin<P: Plugin>() {
+    assert!(!P::NAME.is_empty(), "Plugin::NAME can't be empty");
+    assert!(!P::VENDOR.is_empty(), "Plugin::VENDOR can't be empty");
+    assert!(!P::VERSION.is_empty(), "Plugin::VERSION can't be empty");
+
+    assert!(!contains_nul(P::NAME), "Plugin::NAME can't contain null characters");
+    assert!(!contains_nul(P::VENDOR), "Plugin::VENDOR can't contain null characters");
+    assert!(!contains_nul(P::VERSION), "Plugin::VERSION can't contain null characters");
+    assert!(!option_contains_nul(P::URL), "Plugin::URL can't contain null characters");
+}
+
+pub(crate) const fn contains_nul(string: &str) -> bool {
+    let bytes = string.as_bytes();
+
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == 0 {
+            return true;
+        }
+
+        index += 1;
+    }
+
+    false
+}
+
+pub(crate) const fn option_contains_nul(string: Option<&str>) -> bool {
+    match string {
+        Some(string) => contains_nul(string),
+        None => false,
+    }
+}
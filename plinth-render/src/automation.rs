@@ -0,0 +1,145 @@
+use std::path::Path;
+
+use plinth_plugin::{Event, ParameterId, ParameterValue};
+
+use crate::error::Error;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AutomationPoint {
+    /// Time in seconds from the start of the render
+    pub time: f64,
+    pub id: ParameterId,
+    /// Normalized value
+    pub value: ParameterValue,
+}
+
+impl AutomationPoint {
+    fn sample_position(&self, sample_rate: f64) -> usize {
+        f64::round(self.time * sample_rate).max(0.0) as _
+    }
+}
+
+/// Parameter changes applied during a render
+///
+/// The script format has one point per line: `<time in seconds> <parameter id> <normalized value>`.
+/// Empty lines and lines starting with `#` are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct Automation {
+    points: Vec<AutomationPoint>,
+}
+
+impl Automation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_point(mut self, time: f64, id: ParameterId, value: ParameterValue) -> Self {
+        self.points.push(AutomationPoint { time, id, value });
+        self.points.sort_by(|a, b| a.time.total_cmp(&b.time));
+        self
+    }
+
+    pub fn parse(script: &str) -> Result<Self, Error> {
+        let mut points = Vec::new();
+
+        for (index, line) in script.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |message: &str| Error::AutomationError {
+                line: index + 1,
+                message: message.to_string(),
+            };
+
+            let fields: Vec<_> = line.split_whitespace().collect();
+            let [time, id, value] = fields[..] else {
+                return Err(error("expected <time> <parameter id> <value>"));
+            };
+
+            let time: f64 = time.parse().map_err(|_| error("invalid time"))?;
+            let id: ParameterId = id.parse().map_err(|_| error("invalid parameter id"))?;
+            let value: ParameterValue = value.parse().map_err(|_| error("invalid value"))?;
+
+            if !time.is_finite() || time < 0.0 {
+                return Err(error("time must be a non-negative number of seconds"));
+            }
+            if !(0.0..=1.0).contains(&value) {
+                return Err(error("value must be normalized to 0..1"));
+            }
+
+            points.push(AutomationPoint { time, id, value });
+        }
+
+        points.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        Ok(Self {
+            points,
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let script = std::fs::read_to_string(path)?;
+        Self::parse(&script)
+    }
+
+    pub fn points(&self) -> &[AutomationPoint] {
+        &self.points
+    }
+
+    /// Parameter value events for the block starting at `start`, with offsets relative to the block
+    pub(crate) fn events(&self, sample_rate: f64, start: usize, length: usize) -> impl Iterator<Item = Event> + '_ {
+        let first_index = self.points.partition_point(|point| point.sample_position(sample_rate) < start);
+
+        self.points[first_index..].iter()
+            .map(move |point| (point.sample_position(sample_rate), point))
+            .take_while(move |(position, _)| *position < start + length)
+            .map(move |(position, point)| Event::ParameterValue {
+                sample_offset: position - start,
+                id: point.id,
+                value: point.value,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plinth_plugin::Event;
+
+    use super::Automation;
+
+    #[test]
+    fn parse() {
+        let automation = Automation::parse("# comment\n\n1.0 2 0.5\n0.5 1 1.0\n").unwrap();
+
+        assert_eq!(automation.points().len(), 2);
+        assert_eq!(automation.points()[0].id, 1);
+        assert_eq!(automation.points()[1].time, 1.0);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(Automation::parse("1.0 2").is_err());
+        assert!(Automation::parse("x 2 0.5").is_err());
+        assert!(Automation::parse("1.0 2 1.5").is_err());
+    }
+
+    #[test]
+    fn events_in_block() {
+        let automation = Automation::new()
+            .with_point(0.0, 1, 0.0)
+            .with_point(1.5, 1, 0.5)
+            .with_point(2.0, 1, 1.0);
+
+        let events: Vec<_> = automation.events(10.0, 10, 10).collect();
+        assert_eq!(events.len(), 1);
+
+        let Event::ParameterValue { sample_offset, value, .. } = events[0] else {
+            panic!();
+        };
+
+        assert_eq!(sample_offset, 5);
+        assert_eq!(value, 0.5);
+    }
+}
@@ -0,0 +1,438 @@
+use std::{collections::BTreeMap, ffi::{c_char, c_void, CStr, CString}, path::{Path, PathBuf}, ptr::{null, null_mut}, sync::atomic::{AtomicBool, Ordering}};
+
+use clap_sys::{audio_buffer::clap_audio_buffer, entry::clap_plugin_entry, events::{clap_event_header, clap_event_param_value, clap_event_transport, clap_input_events, clap_output_events, CLAP_CORE_EVENT_SPACE_ID, CLAP_EVENT_PARAM_VALUE, CLAP_EVENT_TRANSPORT, CLAP_TRANSPORT_HAS_BEATS_TIMELINE, CLAP_TRANSPORT_HAS_SECONDS_TIMELINE, CLAP_TRANSPORT_HAS_TEMPO, CLAP_TRANSPORT_HAS_TIME_SIGNATURE, CLAP_TRANSPORT_IS_PLAYING}, ext::{audio_ports::{clap_audio_port_info, clap_plugin_audio_ports, CLAP_EXT_AUDIO_PORTS}, latency::{clap_plugin_latency, CLAP_EXT_LATENCY}, params::{clap_param_info, clap_plugin_params, CLAP_EXT_PARAMS, CLAP_PARAM_IS_STEPPED}, render::{clap_plugin_render, CLAP_EXT_RENDER, CLAP_RENDER_OFFLINE}, state::{clap_plugin_state, CLAP_EXT_STATE}}, factory::plugin_factory::{clap_plugin_factory, CLAP_PLUGIN_FACTORY_ID}, fixedpoint::{CLAP_BEATTIME_FACTOR, CLAP_SECTIME_FACTOR}, host::clap_host, id::clap_id, plugin::clap_plugin, process::{clap_process, CLAP_PROCESS_ERROR}, stream::clap_istream, version::CLAP_VERSION};
+use libloading::Library;
+use plinth_core::{buffers::buffer::Buffer, signals::{signal::SignalMut, signal_base::SignalBase}};
+use plinth_plugin::{Event, ParameterValue};
+
+use crate::{automation::Automation, error::Error, render::{render_blocks, RenderSettings, CHANNELS}};
+
+/// Renders `input` through a built CLAP plugin in offline mode
+///
+/// If `plugin_id` is `None`, the first plugin in the file is used.
+/// The output is compensated for the plugin's latency so it lines up with the input.
+pub fn render(
+    path: impl AsRef<Path>,
+    plugin_id: Option<&str>,
+    input: &Buffer,
+    state: Option<&[u8]>,
+    automation: &Automation,
+    settings: &RenderSettings,
+) -> Result<Buffer, Error>
+{
+    let module = Module::load(path.as_ref())?;
+    let host = Host::new();
+    let plugin = module.create_plugin(&host, plugin_id)?;
+
+    match plugin.extension::<clap_plugin_render>(CLAP_EXT_RENDER) {
+        Some(render) => {
+            unsafe { (render.set.unwrap())(plugin.raw, CLAP_RENDER_OFFLINE) };
+        },
+
+        None => {
+            log::warn!("Plugin doesn't support the render extension, rendering in realtime mode");
+        },
+    }
+
+    if let Some(state) = state {
+        plugin.load_state(state)?;
+    }
+
+    let parameter_info = plugin.parameter_info();
+
+    // The input goes to the main input port and the output comes from the main output port, other ports are silent
+    let input_channels = plugin.audio_port_channels(true);
+    let output_channels = plugin.audio_port_channels(false);
+
+    if input_channels.first().is_some_and(|&channels| channels != CHANNELS) {
+        return Err(Error::ClapError(format!("Main input has {} channels, only stereo is supported", input_channels[0])));
+    }
+
+    match output_channels.first() {
+        Some(&CHANNELS) => {},
+        Some(&channels) => return Err(Error::ClapError(format!("Main output has {channels} channels, only stereo is supported"))),
+        None => return Err(Error::ClapError("Plugin has no audio outputs".to_string())),
+    }
+
+    if !unsafe { (plugin.raw().activate.unwrap())(plugin.raw, settings.sample_rate, 1, settings.block_size as _) } {
+        return Err(Error::ClapError("Plugin activation failed".to_string()));
+    }
+
+    let latency = plugin.extension::<clap_plugin_latency>(CLAP_EXT_LATENCY)
+        .map_or(0, |latency| unsafe { (latency.get.unwrap())(plugin.raw) } as usize);
+
+    if !unsafe { (plugin.raw().start_processing.unwrap())(plugin.raw) } {
+        unsafe { (plugin.raw().deactivate.unwrap())(plugin.raw) };
+        return Err(Error::ClapError("Plugin failed to start processing".to_string()));
+    }
+
+    let mut aux_inputs: Vec<_> = input_channels.iter().skip(1).map(|&channels| Buffer::new(channels, settings.block_size)).collect();
+    let mut outputs: Vec<_> = output_channels.iter().map(|&channels| Buffer::new(channels, settings.block_size)).collect();
+    let mut clap_events = Vec::new();
+
+    let result = render_blocks(input, automation, settings, latency, |block, events, position| {
+        for buffer in aux_inputs.iter_mut() {
+            buffer.resize(block.len());
+            buffer.fill(0.0);
+        }
+
+        for buffer in outputs.iter_mut() {
+            buffer.resize(block.len());
+        }
+
+        clap_events.clear();
+        for event in events {
+            if let Event::ParameterValue { sample_offset, id, value } = *event {
+                let Some(info) = parameter_info.get(&id) else {
+                    return Err(Error::ClapError(format!("Plugin has no parameter with id {id}")));
+                };
+
+                clap_events.push(clap_event_param_value {
+                    header: clap_event_header {
+                        size: size_of::<clap_event_param_value>() as _,
+                        time: sample_offset as _,
+                        space_id: CLAP_CORE_EVENT_SPACE_ID,
+                        type_: CLAP_EVENT_PARAM_VALUE,
+                        flags: 0,
+                    },
+                    param_id: id,
+                    cookie: info.cookie,
+                    note_id: -1,
+                    port_index: -1,
+                    channel: -1,
+                    key: -1,
+                    value: denormalize(info, value),
+                });
+            }
+        }
+
+        let in_events = clap_input_events {
+            ctx: &clap_events as *const Vec<clap_event_param_value> as _,
+            size: Some(input_events_size),
+            get: Some(input_events_get),
+        };
+
+        let out_events = clap_output_events {
+            ctx: null_mut(),
+            try_push: Some(output_events_try_push),
+        };
+
+        let transport = transport(settings, position);
+
+        let main_input = (!input_channels.is_empty()).then_some(&mut *block);
+        let mut input_pointers: Vec<Vec<_>> = main_input.into_iter().chain(aux_inputs.iter_mut())
+            .map(|buffer| buffer.iter_channels_mut().map(|channel| channel.as_mut_ptr()).collect())
+            .collect();
+        let mut output_pointers: Vec<Vec<_>> = outputs.iter_mut()
+            .map(|buffer| buffer.iter_channels_mut().map(|channel| channel.as_mut_ptr()).collect())
+            .collect();
+
+        let input_buffers: Vec<_> = input_pointers.iter_mut().map(|pointers| audio_buffer(pointers)).collect();
+        let mut output_buffers: Vec<_> = output_pointers.iter_mut().map(|pointers| audio_buffer(pointers)).collect();
+
+        let process = clap_process {
+            steady_time: position as _,
+            frames_count: block.len() as _,
+            transport: &transport,
+            audio_inputs: input_buffers.as_ptr(),
+            audio_outputs: output_buffers.as_mut_ptr(),
+            audio_inputs_count: input_buffers.len() as _,
+            audio_outputs_count: output_buffers.len() as _,
+            in_events: &in_events,
+            out_events: &out_events,
+        };
+
+        if unsafe { (plugin.raw().process.unwrap())(plugin.raw, &process) } == CLAP_PROCESS_ERROR {
+            return Err(Error::ProcessError);
+        }
+
+        // Only the first sample of a constant channel is guaranteed to be written
+        let constant_mask = output_buffers[0].constant_mask;
+        for (index, channel) in outputs[0].iter_channels_mut().enumerate() {
+            if index < 64 && constant_mask & (1 << index) != 0 && let Some(&value) = channel.first() {
+                channel.fill(value);
+            }
+        }
+
+        block.copy_from_signal(&outputs[0]);
+
+        if host.callback_requested.swap(false, Ordering::AcqRel) {
+            unsafe { (plugin.raw().on_main_thread.unwrap())(plugin.raw) };
+        }
+
+        Ok(())
+    });
+
+    unsafe {
+        (plugin.raw().stop_processing.unwrap())(plugin.raw);
+        (plugin.raw().deactivate.unwrap())(plugin.raw);
+    }
+
+    result
+}
+
+struct Module {
+    entry: *const clap_plugin_entry,
+    _library: Library,
+}
+
+impl Module {
+    fn load(path: &Path) -> Result<Self, Error> {
+        let library = unsafe { Library::new(binary_path(path))? };
+        let entry = unsafe { *library.get::<*const clap_plugin_entry>(b"clap_entry\0")? };
+
+        let path = CString::new(path.to_string_lossy().as_bytes()).unwrap();
+        if !unsafe { ((*entry).init.unwrap())(path.as_ptr()) } {
+            return Err(Error::ClapError("Plugin entry initialization failed".to_string()));
+        }
+
+        Ok(Self {
+            entry,
+            _library: library,
+        })
+    }
+
+    fn create_plugin(&self, host: &Host, plugin_id: Option<&str>) -> Result<PluginHandle, Error> {
+        let factory = unsafe { ((*self.entry).get_factory.unwrap())(CLAP_PLUGIN_FACTORY_ID.as_ptr()) } as *const clap_plugin_factory;
+        if factory.is_null() {
+            return Err(Error::ClapError("Plugin has no plugin factory".to_string()));
+        }
+
+        let plugin_count = unsafe { ((*factory).get_plugin_count.unwrap())(factory) };
+        let id = (0..plugin_count)
+            .map(|index| unsafe { CStr::from_ptr((*((*factory).get_plugin_descriptor.unwrap())(factory, index)).id) })
+            .find(|id| plugin_id.is_none_or(|plugin_id| id.to_bytes() == plugin_id.as_bytes()))
+            .ok_or_else(|| Error::ClapError(format!("Plugin with id {} not found", plugin_id.unwrap_or("<any>"))))?;
+
+        let raw = unsafe { ((*factory).create_plugin.unwrap())(factory, &host.raw, id.as_ptr()) };
+        if raw.is_null() {
+            return Err(Error::ClapError("Plugin creation failed".to_string()));
+        }
+
+        let plugin = PluginHandle {
+            raw,
+        };
+
+        if !unsafe { (plugin.raw().init.unwrap())(raw) } {
+            return Err(Error::ClapError("Plugin initialization failed".to_string()));
+        }
+
+        Ok(plugin)
+    }
+}
+
+impl Drop for Module {
+    fn drop(&mut self) {
+        unsafe { ((*self.entry).deinit.unwrap())() };
+    }
+}
+
+struct PluginHandle {
+    raw: *const clap_plugin,
+}
+
+impl PluginHandle {
+    fn raw(&self) -> &clap_plugin {
+        unsafe { &*self.raw }
+    }
+
+    fn extension<T>(&self, id: &CStr) -> Option<&T> {
+        let extension = unsafe { (self.raw().get_extension.unwrap())(self.raw, id.as_ptr()) } as *const T;
+        unsafe { extension.as_ref() }
+    }
+
+    fn load_state(&self, mut state: &[u8]) -> Result<(), Error> {
+        let Some(state_extension) = self.extension::<clap_plugin_state>(CLAP_EXT_STATE) else {
+            return Err(Error::ClapError("Plugin doesn't support the state extension".to_string()));
+        };
+
+        let stream = clap_istream {
+            ctx: &mut state as *mut &[u8] as _,
+            read: Some(read_state),
+        };
+
+        if !unsafe { (state_extension.load.unwrap())(self.raw, &stream) } {
+            return Err(Error::ClapError("Plugin failed to load state".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Channel counts of the audio ports, a plugin without the audio ports extension has none
+    fn audio_port_channels(&self, is_input: bool) -> Vec<usize> {
+        let Some(audio_ports) = self.extension::<clap_plugin_audio_ports>(CLAP_EXT_AUDIO_PORTS) else {
+            return Vec::new();
+        };
+
+        let count = unsafe { (audio_ports.count.unwrap())(self.raw, is_input) };
+        (0..count)
+            .map(|index| {
+                let mut info: clap_audio_port_info = unsafe { std::mem::zeroed() };
+                if unsafe { (audio_ports.get.unwrap())(self.raw, index, is_input, &mut info) } {
+                    info.channel_count as usize
+                } else {
+                    0
+                }
+            })
+            .collect()
+    }
+
+    fn parameter_info(&self) -> BTreeMap<clap_id, clap_param_info> {
+        let mut parameter_info = BTreeMap::new();
+
+        let Some(params) = self.extension::<clap_plugin_params>(CLAP_EXT_PARAMS) else {
+            return parameter_info;
+        };
+
+        let count = unsafe { (params.count.unwrap())(self.raw) };
+        for index in 0..count {
+            let mut info: clap_param_info = unsafe { std::mem::zeroed() };
+            if unsafe { (params.get_info.unwrap())(self.raw, index, &mut info) } {
+                parameter_info.insert(info.id, info);
+            }
+        }
+
+        parameter_info
+    }
+}
+
+impl Drop for PluginHandle {
+    fn drop(&mut self) {
+        unsafe { (self.raw().destroy.unwrap())(self.raw) };
+    }
+}
+
+struct Host {
+    raw: clap_host,
+    callback_requested: AtomicBool,
+}
+
+impl Host {
+    fn new() -> Box<Self> {
+        let mut host = Box::new(Self {
+            raw: clap_host {
+                clap_version: CLAP_VERSION,
+                host_data: null_mut(),
+                name: c"plinth-render".as_ptr(),
+                vendor: c"plinth".as_ptr(),
+                url: c"https://github.com/ilmai/plugin-things".as_ptr(),
+                version: c"0.1.0".as_ptr(),
+                get_extension: Some(Self::get_extension),
+                request_restart: Some(Self::request_restart),
+                request_process: Some(Self::request_process),
+                request_callback: Some(Self::request_callback),
+            },
+            callback_requested: false.into(),
+        });
+
+        host.raw.host_data = &*host as *const Self as _;
+        host
+    }
+
+    unsafe extern "C" fn get_extension(_host: *const clap_host, _extension_id: *const c_char) -> *const c_void {
+        null()
+    }
+
+    unsafe extern "C" fn request_restart(_host: *const clap_host) {
+    }
+
+    unsafe extern "C" fn request_process(_host: *const clap_host) {
+    }
+
+    unsafe extern "C" fn request_callback(host: *const clap_host) {
+        let host = unsafe { &*((*host).host_data as *const Self) };
+        host.callback_requested.store(true, Ordering::Release);
+    }
+}
+
+fn binary_path(path: &Path) -> PathBuf {
+    // macOS plugins are bundles
+    if path.is_dir() {
+        let name = path.file_stem().unwrap_or_default();
+        path.join("Contents").join("MacOS").join(name)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+fn audio_buffer(pointers: &mut [*mut f32]) -> clap_audio_buffer {
+    clap_audio_buffer {
+        data32: pointers.as_mut_ptr(),
+        data64: null_mut(),
+        channel_count: pointers.len() as _,
+        latency: 0,
+        constant_mask: 0,
+    }
+}
+
+fn denormalize(info: &clap_param_info, value: ParameterValue) -> f64 {
+    let value = info.min_value + value * (info.max_value - info.min_value);
+
+    if info.flags & CLAP_PARAM_IS_STEPPED != 0 {
+        value.round()
+    } else {
+        value
+    }
+}
+
+fn transport(settings: &RenderSettings, position: usize) -> clap_event_transport {
+    const BEATS_PER_BAR: f64 = 4.0;
+
+    let seconds = position as f64 / settings.sample_rate;
+    let beats = seconds * settings.tempo / 60.0;
+    let bar_number = f64::floor(beats / BEATS_PER_BAR);
+
+    clap_event_transport {
+        header: clap_event_header {
+            size: size_of::<clap_event_transport>() as _,
+            time: 0,
+            space_id: CLAP_CORE_EVENT_SPACE_ID,
+            type_: CLAP_EVENT_TRANSPORT,
+            flags: 0,
+        },
+        flags: CLAP_TRANSPORT_HAS_TEMPO
+            | CLAP_TRANSPORT_HAS_BEATS_TIMELINE
+            | CLAP_TRANSPORT_HAS_SECONDS_TIMELINE
+            | CLAP_TRANSPORT_HAS_TIME_SIGNATURE
+            | CLAP_TRANSPORT_IS_PLAYING,
+        song_pos_beats: f64::round(beats * CLAP_BEATTIME_FACTOR as f64) as _,
+        song_pos_seconds: f64::round(seconds * CLAP_SECTIME_FACTOR as f64) as _,
+        tempo: settings.tempo,
+        tempo_inc: 0.0,
+        loop_start_beats: 0,
+        loop_end_beats: 0,
+        loop_start_seconds: 0,
+        loop_end_seconds: 0,
+        bar_start: f64::round(bar_number * BEATS_PER_BAR * CLAP_BEATTIME_FACTOR as f64) as _,
+        bar_number: bar_number as _,
+        tsig_num: BEATS_PER_BAR as _,
+        tsig_denom: 4,
+    }
+}
+
+unsafe extern "C" fn input_events_size(list: *const clap_input_events) -> u32 {
+    let events = unsafe { &*((*list).ctx as *const Vec<clap_event_param_value>) };
+    events.len() as _
+}
+
+unsafe extern "C" fn input_events_get(list: *const clap_input_events, index: u32) -> *const clap_event_header {
+    let events = unsafe { &*((*list).ctx as *const Vec<clap_event_param_value>) };
+
+    events.get(index as usize)
+        .map_or(null(), |event| &event.header)
+}
+
+unsafe extern "C" fn output_events_try_push(_list: *const clap_output_events, _event: *const clap_event_header) -> bool {
+    // Output events aren't recorded
+    true
+}
+
+unsafe extern "C" fn read_state(stream: *const clap_istream, buffer: *mut c_void, size: u64) -> i64 {
+    let state = unsafe { &mut *((*stream).ctx as *mut &[u8]) };
+    let length = usize::min(size as _, state.len());
+
+    unsafe { std::ptr::copy_nonoverlapping(state.as_ptr(), buffer as *mut u8, length) };
+    *state = &state[length..];
+
+    length as _
+}
@@ -0,0 +1,144 @@
+use std::{path::PathBuf, process::ExitCode};
+
+use plinth_core::buffers::{buffer::Buffer, wav::{read_wav, write_wav, WavFormat}};
+use plinth_plugin::Plugin;
+
+use crate::{automation::Automation, error::Error, render::{render, RenderSettings}};
+
+const USAGE: &str = "\
+Usage: plinth-render [--plugin <path> [--plugin-id <id>]] --input <wav> --output <wav> [options]
+
+--plugin takes a .clap or .vst3 plugin, --plugin-id picks a CLAP plugin by id or a VST3 plugin by name.
+
+Options:
+    --state <file>          Plugin state to load before rendering
+    --automation <file>     Automation script, one `<seconds> <parameter id> <normalized value>` per line
+    --block-size <samples>  Maximum block size, defaults to 512
+    --tail <seconds>        Length to render after the end of the input, defaults to 0
+    --tempo <bpm>           Transport tempo, defaults to 120";
+
+pub struct Arguments {
+    pub plugin: Option<PathBuf>,
+    pub plugin_id: Option<String>,
+    pub input: PathBuf,
+    pub output: PathBuf,
+    pub state: Option<PathBuf>,
+    pub automation: Option<PathBuf>,
+    pub block_size: usize,
+    pub tail: f64,
+    pub tempo: f64,
+}
+
+impl Arguments {
+    pub fn parse(arguments: impl IntoIterator<Item = String>) -> Result<Self, Error> {
+        let defaults = RenderSettings::default();
+
+        let mut plugin = None;
+        let mut plugin_id = None;
+        let mut input = None;
+        let mut output = None;
+        let mut state = None;
+        let mut automation = None;
+        let mut block_size = defaults.block_size;
+        let mut tail = 0.0;
+        let mut tempo = defaults.tempo;
+
+        let mut arguments = arguments.into_iter();
+        while let Some(argument) = arguments.next() {
+            let mut value = || arguments.next()
+                .ok_or_else(|| Error::ArgumentError(format!("Missing value for {argument}")));
+
+            match argument.as_str() {
+                "--plugin" => plugin = Some(value()?.into()),
+                "--plugin-id" => plugin_id = Some(value()?),
+                "--input" => input = Some(value()?.into()),
+                "--output" => output = Some(value()?.into()),
+                "--state" => state = Some(value()?.into()),
+                "--automation" => automation = Some(value()?.into()),
+                "--block-size" => block_size = parse_number(&argument, value()?)?,
+                "--tail" => tail = parse_number(&argument, value()?)?,
+                "--tempo" => tempo = parse_number(&argument, value()?)?,
+
+                _ => {
+                    return Err(Error::ArgumentError(format!("Unknown argument {argument}")));
+                },
+            }
+        }
+
+        if block_size == 0 {
+            return Err(Error::ArgumentError("Block size can't be zero".to_string()));
+        }
+
+        Ok(Self {
+            plugin,
+            plugin_id,
+            input: input.ok_or_else(|| Error::ArgumentError("--input is required".to_string()))?,
+            output: output.ok_or_else(|| Error::ArgumentError("--output is required".to_string()))?,
+            state,
+            automation,
+            block_size,
+            tail,
+            tempo,
+        })
+    }
+}
+
+/// Entry point for a binary that renders a plugin type, for example
+/// `fn main() -> ExitCode { plinth_render::cli::main::<MyPlugin>() }`
+pub fn main<P: Plugin>() -> ExitCode {
+    run(|_, input, state, automation, settings| render::<P>(input, state, automation, settings))
+}
+
+/// Parses the command line, reads the inputs, renders with `render` and writes the output
+pub fn run(render: impl FnOnce(&Arguments, &Buffer, Option<&[u8]>, &Automation, &RenderSettings) -> Result<Buffer, Error>) -> ExitCode {
+    let arguments: Vec<_> = std::env::args().skip(1).collect();
+    if arguments.iter().any(|argument| argument == "-h" || argument == "--help") {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+
+    let result = Arguments::parse(arguments)
+        .and_then(|arguments| {
+            let (input, sample_rate) = read_wav(&arguments.input)?;
+
+            let state = arguments.state.as_ref()
+                .map(std::fs::read)
+                .transpose()?;
+
+            let automation = match arguments.automation.as_ref() {
+                Some(path) => Automation::load(path)?,
+                None => Automation::new(),
+            };
+
+            let settings = RenderSettings {
+                sample_rate,
+                block_size: arguments.block_size,
+                tail_length: f64::round(arguments.tail * sample_rate) as _,
+                tempo: arguments.tempo,
+            };
+
+            let output = render(&arguments, &input, state.as_deref(), &automation, &settings)?;
+            write_wav(&arguments.output, &output, sample_rate, WavFormat::Float32)?;
+
+            Ok(())
+        });
+
+    match result {
+        Ok(_) => ExitCode::SUCCESS,
+
+        Err(error) => {
+            eprintln!("Render failed: {error:?}");
+
+            if let Error::ArgumentError(_) = error {
+                eprintln!("\n{USAGE}");
+            }
+
+            ExitCode::FAILURE
+        },
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(argument: &str, value: String) -> Result<T, Error> {
+    value.parse()
+        .map_err(|_| Error::ArgumentError(format!("Invalid value {value} for {argument}")))
+}
@@ -0,0 +1,41 @@
+#[derive(Debug)]
+pub enum Error {
+    AutomationError {
+        line: usize,
+        message: String,
+    },
+    ArgumentError(String),
+    ChannelCountError(usize),
+    ClapError(String),
+    IoError(std::io::Error),
+    LibraryError(libloading::Error),
+    PluginError(plinth_plugin::Error),
+    ProcessError,
+    UnsupportedFormat(String),
+    Vst3Error(String),
+    WavError(plinth_core::buffers::wav::WavError),
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Self::IoError(error)
+    }
+}
+
+impl From<libloading::Error> for Error {
+    fn from(error: libloading::Error) -> Self {
+        Self::LibraryError(error)
+    }
+}
+
+impl From<plinth_plugin::Error> for Error {
+    fn from(error: plinth_plugin::Error) -> Self {
+        Self::PluginError(error)
+    }
+}
+
+impl From<plinth_core::buffers::wav::WavError> for Error {
+    fn from(error: plinth_core::buffers::wav::WavError) -> Self {
+        Self::WavError(error)
+    }
+}
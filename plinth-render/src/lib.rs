@@ -0,0 +1,11 @@
+pub use automation::{Automation, AutomationPoint};
+pub use error::Error;
+pub use render::{render, RenderSettings};
+pub use plinth_core::buffers::wav::{read_wav, write_wav, WavFormat};
+
+mod automation;
+pub mod clap;
+pub mod cli;
+mod error;
+mod render;
+pub mod vst3;
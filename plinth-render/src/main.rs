@@ -0,0 +1,17 @@
+use std::process::ExitCode;
+
+use plinth_render::{cli, clap, vst3, Error};
+
+fn main() -> ExitCode {
+    cli::run(|arguments, input, state, automation, settings| {
+        let Some(plugin) = arguments.plugin.as_ref() else {
+            return Err(Error::ArgumentError("--plugin is required".to_string()));
+        };
+
+        match plugin.extension().and_then(|extension| extension.to_str()) {
+            Some("clap") => clap::render(plugin, arguments.plugin_id.as_deref(), input, state, automation, settings),
+            Some("vst3") => vst3::render(plugin, arguments.plugin_id.as_deref(), input, state, automation, settings),
+            _ => Err(Error::UnsupportedFormat(format!("Unknown plugin format: {}", plugin.display()))),
+        }
+    })
+}
@@ -0,0 +1,128 @@
+use plinth_core::{buffers::buffer::Buffer, signals::{signal::{Signal, SignalMut}, signal_base::SignalBase}};
+use plinth_plugin::{reported_latency, Bypass, Event, HostInfo, Plugin, PluginFormat, ProcessMode, ProcessState, Processor, ProcessorConfig, Transport};
+
+use crate::{automation::Automation, error::Error};
+
+pub(crate) const CHANNELS: usize = 2;
+pub(crate) const HOST_NAME: &str = "plinth-render";
+
+#[derive(Clone, Debug)]
+pub struct RenderSettings {
+    pub sample_rate: f64,
+    pub block_size: usize,
+    /// Samples rendered after the end of the input
+    pub tail_length: usize,
+    pub tempo: f64,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            sample_rate: 48000.0,
+            block_size: 512,
+            tail_length: 0,
+            tempo: 120.0,
+        }
+    }
+}
+
+/// Renders `input` through a plugin type in offline mode
+///
+/// The output is compensated for the plugin's latency so it lines up with the input.
+pub fn render<P: Plugin>(input: &Buffer, state: Option<&[u8]>, automation: &Automation, settings: &RenderSettings) -> Result<Buffer, Error> {
+    let mut plugin = P::new(HostInfo {
+        name: Some(HOST_NAME.to_string()),
+        vendor: None,
+        version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        url: None,
+        format: PluginFormat::Native,
+        main_thread_waker: Default::default(),
+        capabilities: Default::default(),
+    });
+
+    if let Some(mut state) = state {
+        plugin.load_state(&mut state)?;
+    }
+
+    let config = ProcessorConfig {
+        sample_rate: settings.sample_rate,
+        min_block_size: 1,
+        max_block_size: settings.block_size,
+        process_mode: ProcessMode::Offline,
+        parallel: Default::default(),
+    };
+
+    let mut processor = plugin.create_processor(config.clone());
+    let mut bypass = Bypass::for_plugin(&plugin, &config, CHANNELS);
+    let latency = reported_latency(&plugin) as usize;
+
+    render_blocks(input, automation, settings, latency, |block, events, position| {
+        let transport = Transport::new(true, settings.tempo, position as _);
+
+        let state = match bypass.as_mut() {
+            Some(bypass) => bypass.process(&mut processor, P::PROCESS_WHILE_BYPASSED, block, None::<&Buffer>, Some(transport), events.iter().cloned()),
+            None => processor.process(block, None::<&Buffer>, Some(transport), events.iter().cloned()),
+        };
+
+        if let ProcessState::Error = state {
+            return Err(Error::ProcessError);
+        }
+
+        for event in events {
+            plugin.process_event(event);
+        }
+
+        plugin.on_main_thread();
+
+        Ok(())
+    })
+}
+
+/// Splits the render into blocks, calling `process` with the block, its events and its position
+pub(crate) fn render_blocks(
+    input: &Buffer,
+    automation: &Automation,
+    settings: &RenderSettings,
+    latency: usize,
+    mut process: impl FnMut(&mut Buffer, &[Event], usize) -> Result<(), Error>,
+) -> Result<Buffer, Error>
+{
+    assert!(settings.block_size > 0);
+
+    let input = to_stereo(input)?;
+    let total_length = input.len() + settings.tail_length + latency;
+
+    let mut output = Buffer::new(CHANNELS, total_length);
+    let mut block = Buffer::new(CHANNELS, settings.block_size);
+    let mut events = Vec::new();
+
+    let mut position = 0;
+    while position < total_length {
+        let block_length = usize::min(settings.block_size, total_length - position);
+        let input_length = usize::min(block_length, input.len().saturating_sub(position));
+
+        block.resize(block_length);
+        block.fill(0.0);
+        if input_length > 0 {
+            block.slice_mut(..input_length).copy_from_signal(&input.slice(position..position + input_length));
+        }
+
+        events.clear();
+        events.extend(automation.events(settings.sample_rate, position, block_length));
+
+        process(&mut block, &events, position)?;
+
+        output.slice_mut(position..position + block_length).copy_from_signal(&block);
+        position += block_length;
+    }
+
+    Ok(Buffer::from_signal(&output.slice(latency..)))
+}
+
+fn to_stereo(input: &Buffer) -> Result<Buffer, Error> {
+    match input.channels() {
+        1 => Ok(Buffer::from(vec![input.channel(0).to_vec(); CHANNELS])),
+        CHANNELS => Ok(input.clone()),
+        channels => Err(Error::ChannelCountError(channels)),
+    }
+}
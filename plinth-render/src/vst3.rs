@@ -0,0 +1,579 @@
+use std::{cell::Cell, collections::BTreeMap, ffi::{c_void, CStr}, path::{Path, PathBuf}, ptr::null_mut};
+
+use libloading::Library;
+use plinth_core::{buffers::buffer::Buffer, signals::{signal::SignalMut, signal_base::SignalBase}};
+use plinth_plugin::{string::copy_str_to_char16, Event};
+use vst3::{ComPtr, ComWrapper, Interface};
+use vst3::Steinberg::{int32, int64, kInvalidArgument, kNotImplemented, kResultFalse, kResultOk, tresult, FIDString, IBStream, IBStreamTrait, IPluginBaseTrait, IPluginFactory, IPluginFactoryTrait, PClassInfo, TUID};
+use vst3::Steinberg::Vst::{AudioBusBuffers, BusDirection, BusDirections_, BusInfo, IAudioProcessor, IAudioProcessorTrait, IComponent, IComponentTrait, IHostApplication, IHostApplicationTrait, IParamValueQueue, IParamValueQueueTrait, IParameterChanges, IParameterChangesTrait, MediaTypes_, ParamID, ParamValue, ProcessContext, ProcessData, ProcessModes_, ProcessSetup, SpeakerArr, SpeakerArrangement, String128, SymbolicSampleSizes_};
+use vst3::Steinberg::Vst::ProcessContext_::StatesAndFlags_::{kBarPositionValid, kPlaying, kProjectTimeMusicValid, kTempoValid, kTimeSigValid};
+
+use crate::{automation::Automation, error::Error, render::{render_blocks, RenderSettings, CHANNELS, HOST_NAME}};
+
+const AUDIO_MODULE_CLASS: &CStr = c"Audio Module Class";
+
+/// Renders `input` through a built VST3 plugin in offline mode
+///
+/// If `plugin_name` is `None`, the first audio processor in the module is used, otherwise the one with that name.
+/// Automation values are passed to the plugin as they are, without checking the parameter ids.
+/// The output is compensated for the plugin's latency so it lines up with the input.
+pub fn render(
+    path: impl AsRef<Path>,
+    plugin_name: Option<&str>,
+    input: &Buffer,
+    state: Option<&[u8]>,
+    automation: &Automation,
+    settings: &RenderSettings,
+) -> Result<Buffer, Error>
+{
+    let module = Module::load(path.as_ref())?;
+    let plugin = module.create_plugin(plugin_name)?;
+
+    if let Some(state) = state {
+        let stream = ComWrapper::new(StateStream::new(state)).to_com_ptr::<IBStream>().unwrap();
+        if unsafe { plugin.component.setState(stream.as_ptr()) } != kResultOk {
+            return Err(Error::Vst3Error("Plugin failed to load state".to_string()));
+        }
+    }
+
+    // The input goes to the main input bus and the output comes from the main output bus, other buses are silent
+    let (input_channels, output_channels) = plugin.setup_audio_buses();
+
+    if input_channels.first().is_some_and(|&channels| channels != CHANNELS) {
+        return Err(Error::Vst3Error(format!("Main input has {} channels, only stereo is supported", input_channels[0])));
+    }
+
+    match output_channels.first() {
+        Some(&CHANNELS) => {},
+        Some(&channels) => return Err(Error::Vst3Error(format!("Main output has {channels} channels, only stereo is supported"))),
+        None => return Err(Error::Vst3Error("Plugin has no audio outputs".to_string())),
+    }
+
+    if unsafe { plugin.processor.canProcessSampleSize(SymbolicSampleSizes_::kSample32 as _) } != kResultOk {
+        return Err(Error::Vst3Error("Plugin doesn't support 32-bit processing".to_string()));
+    }
+
+    let mut setup = ProcessSetup {
+        processMode: ProcessModes_::kOffline as _,
+        symbolicSampleSize: SymbolicSampleSizes_::kSample32 as _,
+        maxSamplesPerBlock: settings.block_size as _,
+        sampleRate: settings.sample_rate,
+    };
+
+    if unsafe { plugin.processor.setupProcessing(&mut setup) } != kResultOk {
+        return Err(Error::Vst3Error("Plugin processing setup failed".to_string()));
+    }
+
+    if unsafe { plugin.component.setActive(1) } != kResultOk {
+        return Err(Error::Vst3Error("Plugin activation failed".to_string()));
+    }
+
+    let latency = unsafe { plugin.processor.getLatencySamples() } as usize;
+
+    // Some plugins return kNotImplemented here
+    unsafe { plugin.processor.setProcessing(1) };
+
+    let mut aux_inputs: Vec<_> = input_channels.iter().skip(1).map(|&channels| Buffer::new(channels, settings.block_size)).collect();
+    let mut outputs: Vec<_> = output_channels.iter().map(|&channels| Buffer::new(channels, settings.block_size)).collect();
+
+    let result = render_blocks(input, automation, settings, latency, |block, events, position| {
+        for buffer in aux_inputs.iter_mut() {
+            buffer.resize(block.len());
+            buffer.fill(0.0);
+        }
+
+        for buffer in outputs.iter_mut() {
+            buffer.resize(block.len());
+        }
+
+        let mut points: BTreeMap<ParamID, Vec<(int32, ParamValue)>> = BTreeMap::new();
+        for event in events {
+            if let Event::ParameterValue { sample_offset, id, value } = *event {
+                points.entry(id).or_default().push((sample_offset as _, value));
+            }
+        }
+
+        let parameter_changes = ComWrapper::new(ParameterChanges::new(points)).to_com_ptr::<IParameterChanges>().unwrap();
+        let mut context = process_context(settings, position);
+
+        let main_input = (!input_channels.is_empty()).then_some(&mut *block);
+        let mut input_pointers: Vec<Vec<_>> = main_input.into_iter().chain(aux_inputs.iter_mut())
+            .map(|buffer| buffer.iter_channels_mut().map(|channel| channel.as_mut_ptr()).collect())
+            .collect();
+        let mut output_pointers: Vec<Vec<_>> = outputs.iter_mut()
+            .map(|buffer| buffer.iter_channels_mut().map(|channel| channel.as_mut_ptr()).collect())
+            .collect();
+
+        let mut input_buses: Vec<_> = input_pointers.iter_mut().map(|pointers| audio_bus_buffers(pointers)).collect();
+        let mut output_buses: Vec<_> = output_pointers.iter_mut().map(|pointers| audio_bus_buffers(pointers)).collect();
+
+        let mut data = ProcessData {
+            processMode: ProcessModes_::kOffline as _,
+            symbolicSampleSize: SymbolicSampleSizes_::kSample32 as _,
+            numSamples: block.len() as _,
+            numInputs: input_buses.len() as _,
+            numOutputs: output_buses.len() as _,
+            inputs: input_buses.as_mut_ptr(),
+            outputs: output_buses.as_mut_ptr(),
+            inputParameterChanges: parameter_changes.as_ptr(),
+            // Output parameter changes and events aren't recorded
+            outputParameterChanges: null_mut(),
+            inputEvents: null_mut(),
+            outputEvents: null_mut(),
+            processContext: &mut context,
+        };
+
+        if unsafe { plugin.processor.process(&mut data) } != kResultOk {
+            return Err(Error::ProcessError);
+        }
+
+        // Silent channels aren't guaranteed to be cleared
+        let silence_flags = output_buses[0].silenceFlags;
+        for (index, channel) in outputs[0].iter_channels_mut().enumerate() {
+            if index < 64 && silence_flags & (1 << index) != 0 {
+                channel.fill(0.0);
+            }
+        }
+
+        block.copy_from_signal(&outputs[0]);
+
+        Ok(())
+    });
+
+    unsafe {
+        plugin.processor.setProcessing(0);
+        plugin.component.setActive(0);
+    }
+
+    result
+}
+
+struct Module {
+    factory: Option<ComPtr<IPluginFactory>>,
+    host: ComPtr<IHostApplication>,
+    #[cfg(target_os="macos")]
+    bundle: *const c_void,
+    library: Library,
+}
+
+impl Module {
+    fn load(path: &Path) -> Result<Self, Error> {
+        let library = unsafe { Library::new(binary_path(path))? };
+
+        #[cfg(target_os="linux")]
+        let library = {
+            let library = libloading::os::unix::Library::from(library);
+            let handle = library.into_raw();
+            let library = unsafe { libloading::os::unix::Library::from_raw(handle) };
+
+            let entry = unsafe { library.get::<unsafe extern "system" fn(*mut c_void) -> bool>(b"ModuleEntry\0")? };
+            if !unsafe { entry(handle) } {
+                return Err(Error::Vst3Error("Module entry failed".to_string()));
+            }
+
+            Library::from(library)
+        };
+
+        #[cfg(target_os="windows")]
+        if let Ok(entry) = unsafe { library.get::<unsafe extern "system" fn() -> bool>(b"InitDll\0") } && !unsafe { entry() } {
+            return Err(Error::Vst3Error("Module entry failed".to_string()));
+        }
+
+        #[cfg(target_os="macos")]
+        let bundle = {
+            let bundle = mac::create_bundle(path);
+
+            let entry = unsafe { library.get::<unsafe extern "system" fn(*const c_void) -> bool>(b"bundleEntry\0")? };
+            if !unsafe { entry(bundle) } {
+                unsafe { mac::CFRelease(bundle) };
+                return Err(Error::Vst3Error("Module entry failed".to_string()));
+            }
+
+            bundle
+        };
+
+        // Created after the entry function so the module gets deinitialized on errors
+        let mut module = Self {
+            factory: None,
+            host: ComWrapper::new(HostApplication).to_com_ptr::<IHostApplication>().unwrap(),
+            #[cfg(target_os="macos")]
+            bundle,
+            library,
+        };
+
+        let get_factory = unsafe { module.library.get::<unsafe extern "system" fn() -> *mut IPluginFactory>(b"GetPluginFactory\0")? };
+        module.factory = unsafe { ComPtr::from_raw(get_factory()) };
+        if module.factory.is_none() {
+            return Err(Error::Vst3Error("Module has no plugin factory".to_string()));
+        }
+
+        Ok(module)
+    }
+
+    fn create_plugin(&self, plugin_name: Option<&str>) -> Result<PluginHandle, Error> {
+        let factory = self.factory.as_ref().unwrap();
+
+        let class_id = (0..unsafe { factory.countClasses() })
+            .filter_map(|index| {
+                let mut info: PClassInfo = unsafe { std::mem::zeroed() };
+                (unsafe { factory.getClassInfo(index, &mut info) } == kResultOk).then_some(info)
+            })
+            .find(|info| {
+                let category = unsafe { CStr::from_ptr(info.category.as_ptr()) };
+                let name = unsafe { CStr::from_ptr(info.name.as_ptr()) };
+
+                category == AUDIO_MODULE_CLASS && plugin_name.is_none_or(|plugin_name| name.to_bytes() == plugin_name.as_bytes())
+            })
+            .map(|info| info.cid)
+            .ok_or_else(|| Error::Vst3Error(format!("Plugin {} not found", plugin_name.unwrap_or("<any>"))))?;
+
+        let mut component: *mut c_void = null_mut();
+        let result = unsafe { factory.createInstance(class_id.as_ptr() as FIDString, IComponent::IID.as_ptr() as FIDString, &mut component) };
+        let Some(component) = (result == kResultOk).then(|| unsafe { ComPtr::from_raw(component as *mut IComponent) }).flatten() else {
+            return Err(Error::Vst3Error("Plugin creation failed".to_string()));
+        };
+
+        if unsafe { component.initialize(self.host.as_ptr() as _) } != kResultOk {
+            return Err(Error::Vst3Error("Plugin initialization failed".to_string()));
+        }
+
+        let Some(processor) = component.cast::<IAudioProcessor>() else {
+            unsafe { component.terminate() };
+            return Err(Error::Vst3Error("Plugin isn't an audio processor".to_string()));
+        };
+
+        Ok(PluginHandle {
+            component,
+            processor,
+        })
+    }
+}
+
+impl Drop for Module {
+    fn drop(&mut self) {
+        // Everything from the module has to be released before it exits
+        self.factory = None;
+
+        #[cfg(target_os="linux")]
+        if let Ok(exit) = unsafe { self.library.get::<unsafe extern "system" fn() -> bool>(b"ModuleExit\0") } {
+            unsafe { exit() };
+        }
+
+        #[cfg(target_os="windows")]
+        if let Ok(exit) = unsafe { self.library.get::<unsafe extern "system" fn() -> bool>(b"ExitDll\0") } {
+            unsafe { exit() };
+        }
+
+        #[cfg(target_os="macos")]
+        unsafe {
+            if let Ok(exit) = self.library.get::<unsafe extern "system" fn() -> bool>(b"bundleExit\0") {
+                exit();
+            }
+
+            mac::CFRelease(self.bundle);
+        }
+    }
+}
+
+struct PluginHandle {
+    component: ComPtr<IComponent>,
+    processor: ComPtr<IAudioProcessor>,
+}
+
+impl PluginHandle {
+    /// Asks for stereo main buses and activates them, then returns the channel counts of the input and output buses
+    fn setup_audio_buses(&self) -> (Vec<usize>, Vec<usize>) {
+        let directions = [BusDirections_::kInput as BusDirection, BusDirections_::kOutput as BusDirection];
+
+        let [mut inputs, mut outputs] = directions.map(|direction| {
+            let mut arrangements: Vec<SpeakerArrangement> = (0..self.audio_bus_count(direction))
+                .map(|index| {
+                    let mut arrangement = 0;
+                    unsafe { self.processor.getBusArrangement(direction, index, &mut arrangement) };
+                    arrangement
+                })
+                .collect();
+
+            if let Some(main) = arrangements.first_mut() {
+                *main = SpeakerArr::kStereo;
+            }
+
+            arrangements
+        });
+
+        // The plugin can refuse, in which case it keeps its current arrangement
+        unsafe { self.processor.setBusArrangements(inputs.as_mut_ptr(), inputs.len() as _, outputs.as_mut_ptr(), outputs.len() as _) };
+
+        let [input_channels, output_channels] = directions.map(|direction| {
+            (0..self.audio_bus_count(direction))
+                .map(|index| {
+                    if index == 0 {
+                        unsafe { self.component.activateBus(MediaTypes_::kAudio as _, direction, index, 1) };
+                    }
+
+                    let mut info: BusInfo = unsafe { std::mem::zeroed() };
+                    if unsafe { self.component.getBusInfo(MediaTypes_::kAudio as _, direction, index, &mut info) } == kResultOk {
+                        info.channelCount as usize
+                    } else {
+                        0
+                    }
+                })
+                .collect()
+        });
+
+        (input_channels, output_channels)
+    }
+
+    fn audio_bus_count(&self, direction: BusDirection) -> int32 {
+        unsafe { self.component.getBusCount(MediaTypes_::kAudio as _, direction) }
+    }
+}
+
+impl Drop for PluginHandle {
+    fn drop(&mut self) {
+        unsafe { self.component.terminate() };
+    }
+}
+
+struct HostApplication;
+
+impl vst3::Class for HostApplication {
+    type Interfaces = (IHostApplication,);
+}
+
+impl IHostApplicationTrait for HostApplication {
+    unsafe fn getName(&self, name: *mut String128) -> tresult {
+        copy_str_to_char16(HOST_NAME, unsafe { &mut *name });
+        kResultOk
+    }
+
+    unsafe fn createInstance(&self, _cid: *mut TUID, _iid: *mut TUID, obj: *mut *mut c_void) -> tresult {
+        unsafe { *obj = null_mut() };
+        kNotImplemented
+    }
+}
+
+/// A read-only stream over plugin state
+struct StateStream {
+    data: Vec<u8>,
+    position: Cell<usize>,
+}
+
+impl StateStream {
+    fn new(data: &[u8]) -> Self {
+        Self {
+            data: data.to_vec(),
+            position: Cell::new(0),
+        }
+    }
+}
+
+impl vst3::Class for StateStream {
+    type Interfaces = (IBStream,);
+}
+
+impl IBStreamTrait for StateStream {
+    unsafe fn read(&self, buffer: *mut c_void, num_bytes: int32, num_bytes_read: *mut int32) -> tresult {
+        if num_bytes < 0 {
+            return kInvalidArgument;
+        }
+
+        let position = self.position.get();
+        let length = usize::min(num_bytes as _, self.data.len() - position);
+
+        unsafe { std::ptr::copy_nonoverlapping(self.data[position..].as_ptr(), buffer as *mut u8, length) };
+        self.position.set(position + length);
+
+        if !num_bytes_read.is_null() {
+            unsafe { *num_bytes_read = length as _ };
+        }
+
+        kResultOk
+    }
+
+    unsafe fn write(&self, _buffer: *mut c_void, _num_bytes: int32, _num_bytes_written: *mut int32) -> tresult {
+        kNotImplemented
+    }
+
+    unsafe fn seek(&self, pos: int64, mode: int32, result: *mut int64) -> tresult {
+        // kIBSeekSet, kIBSeekCur and kIBSeekEnd
+        let base = match mode {
+            0 => 0,
+            1 => self.position.get() as int64,
+            2 => self.data.len() as int64,
+            _ => return kInvalidArgument,
+        };
+
+        let position = base + pos;
+        if position < 0 || position > self.data.len() as int64 {
+            return kResultFalse;
+        }
+
+        self.position.set(position as _);
+
+        if !result.is_null() {
+            unsafe { *result = position };
+        }
+
+        kResultOk
+    }
+
+    unsafe fn tell(&self, pos: *mut int64) -> tresult {
+        if pos.is_null() {
+            return kInvalidArgument;
+        }
+
+        unsafe { *pos = self.position.get() as _ };
+        kResultOk
+    }
+}
+
+/// One block's automation
+struct ParameterChanges {
+    queues: Vec<ComPtr<IParamValueQueue>>,
+}
+
+impl ParameterChanges {
+    fn new(points: BTreeMap<ParamID, Vec<(int32, ParamValue)>>) -> Self {
+        let queues = points.into_iter()
+            .map(|(id, points)| ComWrapper::new(ParamValueQueue { id, points }).to_com_ptr::<IParamValueQueue>().unwrap())
+            .collect();
+
+        Self {
+            queues,
+        }
+    }
+}
+
+impl vst3::Class for ParameterChanges {
+    type Interfaces = (IParameterChanges,);
+}
+
+impl IParameterChangesTrait for ParameterChanges {
+    unsafe fn getParameterCount(&self) -> int32 {
+        self.queues.len() as _
+    }
+
+    unsafe fn getParameterData(&self, index: int32) -> *mut IParamValueQueue {
+        self.queues.get(index as usize)
+            .map_or(null_mut(), |queue| queue.as_ptr())
+    }
+
+    unsafe fn addParameterData(&self, _id: *const ParamID, _index: *mut int32) -> *mut IParamValueQueue {
+        null_mut()
+    }
+}
+
+struct ParamValueQueue {
+    id: ParamID,
+    points: Vec<(int32, ParamValue)>,
+}
+
+impl vst3::Class for ParamValueQueue {
+    type Interfaces = (IParamValueQueue,);
+}
+
+impl IParamValueQueueTrait for ParamValueQueue {
+    unsafe fn getParameterId(&self) -> ParamID {
+        self.id
+    }
+
+    unsafe fn getPointCount(&self) -> int32 {
+        self.points.len() as _
+    }
+
+    unsafe fn getPoint(&self, index: int32, sample_offset: *mut int32, value: *mut ParamValue) -> tresult {
+        let Some(&(point_offset, point_value)) = self.points.get(index as usize) else {
+            return kInvalidArgument;
+        };
+
+        unsafe {
+            *sample_offset = point_offset;
+            *value = point_value;
+        }
+
+        kResultOk
+    }
+
+    unsafe fn addPoint(&self, _sample_offset: int32, _value: ParamValue, _index: *mut int32) -> tresult {
+        kResultFalse
+    }
+}
+
+fn binary_path(path: &Path) -> PathBuf {
+    // VST3 plugins are usually bundles, with the binary in a platform specific directory
+    if !path.is_dir() {
+        return path.to_path_buf();
+    }
+
+    let name = path.file_stem().unwrap_or_default();
+    let contents = path.join("Contents");
+
+    if cfg!(target_os="macos") {
+        contents.join("MacOS").join(name)
+    } else if cfg!(target_os="windows") {
+        contents.join(format!("{}-win", std::env::consts::ARCH)).join(path.file_name().unwrap_or_default())
+    } else {
+        contents.join(format!("{}-linux", std::env::consts::ARCH)).join(name).with_extension("so")
+    }
+}
+
+fn audio_bus_buffers(pointers: &mut [*mut f32]) -> AudioBusBuffers {
+    let mut buffers: AudioBusBuffers = unsafe { std::mem::zeroed() };
+    buffers.numChannels = pointers.len() as _;
+    buffers.silenceFlags = 0;
+    buffers.__field0.channelBuffers32 = pointers.as_mut_ptr();
+
+    buffers
+}
+
+fn process_context(settings: &RenderSettings, position: usize) -> ProcessContext {
+    const BEATS_PER_BAR: f64 = 4.0;
+
+    let seconds = position as f64 / settings.sample_rate;
+    let beats = seconds * settings.tempo / 60.0;
+
+    let mut context: ProcessContext = unsafe { std::mem::zeroed() };
+
+    // These casts are needed on some platforms
+    #[allow(clippy::unnecessary_cast)]
+    {
+        context.state = (kPlaying | kTempoValid | kTimeSigValid | kProjectTimeMusicValid | kBarPositionValid) as u32;
+    }
+
+    context.sampleRate = settings.sample_rate;
+    context.projectTimeSamples = position as _;
+    context.continousTimeSamples = position as _;
+    context.projectTimeMusic = beats;
+    context.barPositionMusic = f64::floor(beats / BEATS_PER_BAR) * BEATS_PER_BAR;
+    context.tempo = settings.tempo;
+    context.timeSigNumerator = BEATS_PER_BAR as _;
+    context.timeSigDenominator = 4;
+
+    context
+}
+
+#[cfg(target_os="macos")]
+mod mac {
+    use std::{ffi::c_void, os::unix::ffi::OsStrExt, path::Path, ptr::null};
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    unsafe extern "C" {
+        pub(super) fn CFRelease(cf: *const c_void);
+        fn CFBundleCreate(allocator: *const c_void, bundle_url: *const c_void) -> *const c_void;
+        fn CFURLCreateFromFileSystemRepresentation(allocator: *const c_void, buffer: *const u8, length: isize, is_directory: u8) -> *const c_void;
+    }
+
+    /// The plugin's bundle for `bundleEntry()`, to be released with `CFRelease()`
+    pub(super) fn create_bundle(path: &Path) -> *const c_void {
+        let path = path.as_os_str().as_bytes();
+
+        unsafe {
+            let url = CFURLCreateFromFileSystemRepresentation(null(), path.as_ptr(), path.len() as _, 1);
+            let bundle = CFBundleCreate(null(), url);
+            CFRelease(url);
+
+            bundle
+        }
+    }
+}
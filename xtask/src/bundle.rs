@@ -0,0 +1,284 @@
+use std::{fs, path::{Path, PathBuf}, process::Command};
+
+use crate::{module_info::module_info, target::{Os, Target}, Result};
+
+pub struct Bundles {
+    pub target: Target,
+    pub clap: PathBuf,
+    pub vst3: PathBuf,
+}
+
+pub fn bundle(package: &str, cargo_arguments: &[String]) -> Result<Bundles> {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+
+    let status = Command::new(cargo)
+        .args(["build", "--package", package])
+        .args(cargo_arguments)
+        .status()?;
+
+    if !status.success() {
+        return Err(format!("Building {package} failed").into());
+    }
+
+    let target = match argument_value(cargo_arguments, "--target") {
+        Some(triple) => Target::from_triple(triple)?,
+        None => Target::host()?,
+    };
+
+    let mut build_dir = target_dir();
+    if let Some(triple) = target.triple.as_ref() {
+        build_dir.push(triple);
+    }
+    build_dir.push(profile_dir(cargo_arguments));
+
+    let library_name = package.replace('-', "_");
+    let library = build_dir.join(target.library_file_name(&library_name));
+    if !library.exists() {
+        return Err(format!("{} not found, is {package} a cdylib?", library.display()).into());
+    }
+
+    let bundle_dir = target_dir().join("bundled");
+    fs::create_dir_all(&bundle_dir)?;
+
+    let clap = bundle_dir.join(format!("{package}.clap"));
+    let vst3 = bundle_dir.join(format!("{package}.vst3"));
+
+    bundle_clap(&library, &clap, package, &target)?;
+    bundle_vst3(&library, &vst3, package, &target)?;
+
+    println!("Created {}", clap.display());
+    println!("Created {}", vst3.display());
+
+    Ok(Bundles {
+        target,
+        clap,
+        vst3,
+    })
+}
+
+fn bundle_clap(library: &Path, bundle: &Path, name: &str, target: &Target) -> Result<()> {
+    remove_existing(bundle)?;
+
+    match target.os {
+        Os::Linux | Os::Windows => {
+            fs::copy(library, bundle)?;
+        },
+
+        Os::Mac => {
+            create_macos_bundle(library, bundle, name)?;
+        },
+    }
+
+    Ok(())
+}
+
+fn bundle_vst3(library: &Path, bundle: &Path, name: &str, target: &Target) -> Result<()> {
+    create_vst3_layout(library, bundle, name, target)?;
+
+    // The library can only be loaded to query the module info when it was built for this machine
+    if target.is_host() {
+        match module_info(library)? {
+            Some(module_info) => {
+                let resources_dir = bundle.join("Contents").join("Resources");
+                fs::create_dir_all(&resources_dir)?;
+                fs::write(resources_dir.join("moduleinfo.json"), module_info)?;
+            },
+
+            None => {
+                println!("Warning: {} doesn't export module info, skipping moduleinfo.json", library.display());
+            },
+        }
+    } else {
+        println!("Warning: cross compiling, skipping moduleinfo.json");
+    }
+
+    Ok(())
+}
+
+fn create_vst3_layout(library: &Path, bundle: &Path, name: &str, target: &Target) -> Result<()> {
+    remove_existing(bundle)?;
+
+    let contents_dir = bundle.join("Contents");
+
+    match target.os {
+        Os::Linux => {
+            let binary_dir = contents_dir.join(target.vst3_architecture());
+            fs::create_dir_all(&binary_dir)?;
+            fs::copy(library, binary_dir.join(format!("{name}.so")))?;
+        },
+
+        Os::Windows => {
+            let binary_dir = contents_dir.join(target.vst3_architecture());
+            fs::create_dir_all(&binary_dir)?;
+            fs::copy(library, binary_dir.join(format!("{name}.vst3")))?;
+        },
+
+        Os::Mac => {
+            create_macos_bundle(library, bundle, name)?;
+        },
+    }
+
+    Ok(())
+}
+
+fn create_macos_bundle(library: &Path, bundle: &Path, name: &str) -> Result<()> {
+    let contents_dir = bundle.join("Contents");
+    let binary_dir = contents_dir.join("MacOS");
+    fs::create_dir_all(&binary_dir)?;
+
+    fs::copy(library, binary_dir.join(name))?;
+    fs::write(contents_dir.join("PkgInfo"), "BNDL????")?;
+    fs::write(contents_dir.join("Info.plist"), info_plist(name))?;
+
+    Ok(())
+}
+
+fn info_plist(name: &str) -> String {
+    format!(
+r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>CFBundleDevelopmentRegion</key>
+    <string>English</string>
+    <key>CFBundleExecutable</key>
+    <string>{name}</string>
+    <key>CFBundleIdentifier</key>
+    <string>plinth.{name}</string>
+    <key>CFBundleInfoDictionaryVersion</key>
+    <string>6.0</string>
+    <key>CFBundleName</key>
+    <string>{name}</string>
+    <key>CFBundlePackageType</key>
+    <string>BNDL</string>
+    <key>CFBundleSignature</key>
+    <string>????</string>
+</dict>
+</plist>
+"#)
+}
+
+fn remove_existing(path: &Path) -> Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)?;
+    } else if path.exists() {
+        fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+pub fn copy_recursive(source: &Path, target: &Path) -> Result<()> {
+    remove_existing(target)?;
+
+    if source.is_dir() {
+        fs::create_dir_all(target)?;
+
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &target.join(entry.file_name()))?;
+        }
+    } else {
+        fs::copy(source, target)?;
+    }
+
+    Ok(())
+}
+
+fn target_dir() -> PathBuf {
+    if let Ok(target_dir) = std::env::var("CARGO_TARGET_DIR") {
+        return target_dir.into();
+    }
+
+    // xtask lives in the workspace root
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().join("target")
+}
+
+fn profile_dir(cargo_arguments: &[String]) -> String {
+    match argument_value(cargo_arguments, "--profile") {
+        Some("dev") | Some("test") => "debug".to_string(),
+        Some("bench") => "release".to_string(),
+        Some(profile) => profile.to_string(),
+
+        None if cargo_arguments.iter().any(|argument| argument == "--release" || argument == "-r") => "release".to_string(),
+        None => "debug".to_string(),
+    }
+}
+
+/// Supports both `--name value` and `--name=value`
+fn argument_value<'a>(arguments: &'a [String], name: &str) -> Option<&'a str> {
+    arguments.iter()
+        .enumerate()
+        .find_map(|(index, argument)| {
+            if argument == name {
+                arguments.get(index + 1).map(String::as_str)
+            } else {
+                argument.strip_prefix(name)?.strip_prefix('=')
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use crate::target::Target;
+
+    use super::{bundle_clap, create_vst3_layout};
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("plinth-xtask-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn vst3_layouts() {
+        let dir = test_dir("vst3");
+        let library = dir.join("library");
+        fs::write(&library, "binary").unwrap();
+
+        let layouts = [
+            ("x86_64-unknown-linux-gnu", "Contents/x86_64-linux/plug.so"),
+            ("aarch64-unknown-linux-gnu", "Contents/aarch64-linux/plug.so"),
+            ("x86_64-pc-windows-msvc", "Contents/x86_64-win/plug.vst3"),
+            ("aarch64-pc-windows-msvc", "Contents/arm64-win/plug.vst3"),
+            ("aarch64-apple-darwin", "Contents/MacOS/plug"),
+        ];
+
+        for (triple, binary) in layouts {
+            let bundle = dir.join(format!("{triple}.vst3"));
+            create_vst3_layout(&library, &bundle, "plug", &Target::from_triple(triple).unwrap()).unwrap();
+
+            assert_eq!(fs::read_to_string(bundle.join(binary)).unwrap(), "binary", "{triple}");
+        }
+
+        let mac_bundle = dir.join("aarch64-apple-darwin.vst3/Contents");
+        assert_eq!(fs::read_to_string(mac_bundle.join("PkgInfo")).unwrap(), "BNDL????");
+        assert!(fs::read_to_string(mac_bundle.join("Info.plist")).unwrap().contains("<string>plug</string>"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn clap_layouts() {
+        let dir = test_dir("clap");
+        let library = dir.join("library");
+        fs::write(&library, "binary").unwrap();
+
+        let linux = dir.join("linux.clap");
+        bundle_clap(&library, &linux, "plug", &Target::from_triple("x86_64-unknown-linux-gnu").unwrap()).unwrap();
+        assert!(linux.is_file());
+
+        let mac = dir.join("mac.clap");
+        bundle_clap(&library, &mac, "plug", &Target::from_triple("x86_64-apple-darwin").unwrap()).unwrap();
+        assert_eq!(fs::read_to_string(mac.join("Contents/MacOS/plug")).unwrap(), "binary");
+
+        // Bundling again replaces the old bundle
+        bundle_clap(&library, &mac, "plug", &Target::from_triple("x86_64-unknown-linux-gnu").unwrap()).unwrap();
+        assert!(mac.is_file());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+
+use crate::{bundle::{copy_recursive, Bundles}, target::Os, Result};
+
+pub fn install(bundles: &Bundles) -> Result<()> {
+    if !bundles.target.is_host() {
+        return Err("Can't install plugins built for another target".into());
+    }
+
+    for (bundle, directory) in [(&bundles.clap, clap_directory(bundles.target.os)?), (&bundles.vst3, vst3_directory(bundles.target.os)?)] {
+        std::fs::create_dir_all(&directory)?;
+
+        let target = directory.join(bundle.file_name().unwrap());
+        copy_recursive(bundle, &target)?;
+
+        println!("Installed {}", target.display());
+    }
+
+    Ok(())
+}
+
+fn clap_directory(os: Os) -> Result<PathBuf> {
+    Ok(match os {
+        Os::Linux => home_dir()?.join(".clap"),
+        Os::Mac => home_dir()?.join("Library/Audio/Plug-Ins/CLAP"),
+        Os::Windows => local_app_data_dir()?.join("Programs\\Common\\CLAP"),
+    })
+}
+
+fn vst3_directory(os: Os) -> Result<PathBuf> {
+    Ok(match os {
+        Os::Linux => home_dir()?.join(".vst3"),
+        Os::Mac => home_dir()?.join("Library/Audio/Plug-Ins/VST3"),
+        Os::Windows => local_app_data_dir()?.join("Programs\\Common\\VST3"),
+    })
+}
+
+fn home_dir() -> Result<PathBuf> {
+    std::env::home_dir().ok_or_else(|| "Couldn't find the home directory".into())
+}
+
+fn local_app_data_dir() -> Result<PathBuf> {
+    std::env::var("LOCALAPPDATA")
+        .map(PathBuf::from)
+        .map_err(|_| "LOCALAPPDATA isn't set".into())
+}
@@ -1,3 +1,45 @@
-fn main() -> nih_plug_xtask::Result<()> {
-    nih_plug_xtask::main()
+use std::process::ExitCode;
+
+mod bundle;
+mod install;
+mod module_info;
+mod target;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+const USAGE: &str = "\
+Usage: cargo xtask <command> <package> [cargo build arguments]
+
+Commands:
+    bundle   Build the package and create .clap and .vst3 bundles in target/bundled
+    install  Bundle the package and install the bundles into the user's plugin folders";
+
+fn main() -> ExitCode {
+    let mut arguments = std::env::args().skip(1);
+
+    let (Some(command), Some(package)) = (arguments.next(), arguments.next()) else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+
+    let cargo_arguments: Vec<_> = arguments.collect();
+
+    let result = match command.as_str() {
+        "bundle" => bundle::bundle(&package, &cargo_arguments).map(|_| ()),
+        "install" => bundle::bundle(&package, &cargo_arguments).and_then(|bundles| install::install(&bundles)),
+
+        _ => {
+            eprintln!("Unknown command {command}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        },
+    };
+
+    match result {
+        Ok(_) => ExitCode::SUCCESS,
+
+        Err(error) => {
+            eprintln!("Error: {error}");
+            ExitCode::FAILURE
+        },
+    }
 }
@@ -0,0 +1,19 @@
+use std::{ffi::{c_char, CStr}, path::Path};
+
+use libloading::Library;
+
+use crate::Result;
+
+/// Loads the plugin library and asks it for the contents of moduleinfo.json,
+/// returns `None` if the library isn't a plinth VST3 plugin
+pub fn module_info(library_path: &Path) -> Result<Option<String>> {
+    let library = unsafe { Library::new(library_path)? };
+
+    let Ok(get_module_info) = (unsafe { library.get::<unsafe extern "C" fn() -> *const c_char>(b"PlinthGetModuleInfo\0") }) else {
+        return Ok(None);
+    };
+
+    let module_info = unsafe { CStr::from_ptr(get_module_info()) };
+
+    Ok(Some(module_info.to_str()?.to_string()))
+}
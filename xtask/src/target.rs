@@ -0,0 +1,91 @@
+use crate::Result;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Os {
+    Linux,
+    Mac,
+    Windows,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Arch {
+    Aarch64,
+    X86_64,
+}
+
+pub struct Target {
+    pub os: Os,
+    pub arch: Arch,
+    /// Set when cross compiling with `--target`
+    pub triple: Option<String>,
+}
+
+impl Target {
+    pub fn host() -> Result<Self> {
+        Ok(Self {
+            os: parse_os(std::env::consts::OS)?,
+            arch: parse_arch(std::env::consts::ARCH)?,
+            triple: None,
+        })
+    }
+
+    pub fn from_triple(triple: &str) -> Result<Self> {
+        let arch = triple.split('-').next().unwrap_or_default();
+
+        let os = if triple.contains("linux") {
+            Os::Linux
+        } else if triple.contains("apple-darwin") {
+            Os::Mac
+        } else if triple.contains("windows") {
+            Os::Windows
+        } else {
+            return Err(format!("Unsupported target {triple}").into());
+        };
+
+        Ok(Self {
+            os,
+            arch: parse_arch(arch)?,
+            triple: Some(triple.to_string()),
+        })
+    }
+
+    pub fn is_host(&self) -> bool {
+        Self::host().is_ok_and(|host| host.os == self.os && host.arch == self.arch)
+    }
+
+    pub fn library_file_name(&self, library_name: &str) -> String {
+        match self.os {
+            Os::Linux => format!("lib{library_name}.so"),
+            Os::Mac => format!("lib{library_name}.dylib"),
+            Os::Windows => format!("{library_name}.dll"),
+        }
+    }
+
+    /// Name of the architecture directory inside a VST3 bundle
+    pub fn vst3_architecture(&self) -> &'static str {
+        match (self.os, self.arch) {
+            (Os::Linux, Arch::Aarch64) => "aarch64-linux",
+            (Os::Linux, Arch::X86_64) => "x86_64-linux",
+            (Os::Windows, Arch::Aarch64) => "arm64-win",
+            (Os::Windows, Arch::X86_64) => "x86_64-win",
+            (Os::Mac, _) => "MacOS",
+        }
+    }
+}
+
+fn parse_os(os: &str) -> Result<Os> {
+    match os {
+        "linux" => Ok(Os::Linux),
+        "macos" => Ok(Os::Mac),
+        "windows" => Ok(Os::Windows),
+        _ => Err(format!("Unsupported OS {os}").into()),
+    }
+}
+
+fn parse_arch(arch: &str) -> Result<Arch> {
+    match arch {
+        "aarch64" => Ok(Arch::Aarch64),
+        "x86_64" => Ok(Arch::X86_64),
+        _ => Err(format!("Unsupported architecture {arch}").into()),
+    }
+}